@@ -134,7 +134,7 @@ pub trait Proj {
   fn info_html(&self) -> String {
     let info = self.info();
     let name = if COMPARE {
-      format!("{}", info.name)
+      info.name.to_string()
     } else {
       format!("<a href=\"{}\">{}</a>",info.url, info.name)
     };
@@ -52,8 +52,8 @@ pub(crate) struct FieldsType<'a> {
   pub(crate) attributes_name: &'a Ident,
   /// comment name
   pub(crate) comments_name: &'a Ident,
-  /// extra_ctx name
-  pub(crate) extra_ctx_name: &'a Ident,
+  /// extra_ctx names
+  pub(crate) extra_ctx_names: Vec<&'a Ident>,
 }
 
 #[allow(clippy::type_complexity)]
@@ -63,7 +63,7 @@ pub(crate) fn parse_fields_type(
   let mut name_vec = Vec::new();
   let mut _attributes_name = None;
   let mut _comments_name = None;
-  let mut _extra_ctx_name = None;
+  let mut extra_ctx_names = Vec::new();
   let mut attri_type_map = HashMap::new();
   let mut default_map = HashMap::new();
   let mut dynamic_key_map = HashMap::new();
@@ -122,14 +122,7 @@ pub(crate) fn parse_fields_type(
           }
         }
         FieldType::Internal(InternalType::ExtraCtx) => {
-          if let Some(name) = &_extra_ctx_name {
-            return Err(syn::Error::new(
-              proc_macro2::Span::call_site(),
-              format!("duplicated extra_ctx {name}."),
-            ));
-          } else {
-            _extra_ctx_name = Some(field_name);
-          }
+          extra_ctx_names.push(field_name);
         }
         FieldType::Attri(attri_type) => {
           _ = attri_type_map.insert(field_name, attri_type);
@@ -143,7 +136,7 @@ pub(crate) fn parse_fields_type(
     }
   }
 
-  match (_attributes_name, _comments_name, _extra_ctx_name) {
+  match (_attributes_name, _comments_name, extra_ctx_names.is_empty()) {
     (None, _, _) => Err(syn::Error::new(
       proc_macro2::Span::call_site(),
       "Can not find attributes".to_string(),
@@ -152,23 +145,21 @@ pub(crate) fn parse_fields_type(
       proc_macro2::Span::call_site(),
       "Can not find comment".to_string(),
     )),
-    (_, _, None) => Err(syn::Error::new(
+    (_, _, true) => Err(syn::Error::new(
       proc_macro2::Span::call_site(),
       "Can not find extra_ctx".to_string(),
     )),
-    (Some(attributes_name), Some(comments_name), Some(extra_ctx_name)) => {
-      Ok(FieldsType {
-        attri_type_map,
-        default_map,
-        dynamic_key_map,
-        before_build_map,
-        after_build_map,
-        name_vec,
-        attributes_name,
-        comments_name,
-        extra_ctx_name,
-      })
-    }
+    (Some(attributes_name), Some(comments_name), false) => Ok(FieldsType {
+      attri_type_map,
+      default_map,
+      dynamic_key_map,
+      before_build_map,
+      after_build_map,
+      name_vec,
+      attributes_name,
+      comments_name,
+      extra_ctx_names,
+    }),
   }
 }
 
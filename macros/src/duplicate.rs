@@ -199,20 +199,14 @@ pub(crate) fn inner(ast: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
       for f in mem::take(&mut named.named) {
         if !config.not_exclude.contains(f.ident.as_ref().unwrap()) {
           match parse_field_attrs(&f.attrs)? {
-            Some(FieldType::Attri(AttriType::Simple)) => {
-              if config.exclude_simple {
-                continue;
-              }
+            Some(FieldType::Attri(AttriType::Simple)) if config.exclude_simple => {
+              continue;
             }
-            Some(FieldType::Attri(AttriType::Complex)) => {
-              if config.exclude_complex {
-                continue;
-              }
+            Some(FieldType::Attri(AttriType::Complex)) if config.exclude_complex => {
+              continue;
             }
-            Some(FieldType::Attri(AttriType::Group)) => {
-              if config.exclude_group {
-                continue;
-              }
+            Some(FieldType::Attri(AttriType::Group)) if config.exclude_group => {
+              continue;
             }
             _ => {}
           }
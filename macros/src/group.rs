@@ -265,7 +265,7 @@ pub(crate) fn inner(ast: &DeriveInput) -> syn::Result<proc_macro2::TokenStream>
       name_vec,
       attributes_name,
       comments_name,
-      extra_ctx_name,
+      extra_ctx_names,
     } = parse_fields_type(fields)?;
     let mut comment_fns = quote! {
       #[inline]
@@ -288,7 +288,7 @@ pub(crate) fn inner(ast: &DeriveInput) -> syn::Result<proc_macro2::TokenStream>
     let mut build_arms = quote! {
       #comments_name: Default::default(),
       #attributes_name: builder.#attributes_name,
-      #extra_ctx_name: Default::default(),
+      #(#extra_ctx_names: Default::default(),)*
     };
     for (flatten, name_field) in &name_vec {
       let i = name_field.ident.clone().unwrap();
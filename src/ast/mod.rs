@@ -50,11 +50,18 @@ pub(crate) struct BuilderScope<C: 'static + Ctx> {
   #[cfg(feature = "lut_template")]
   pub(crate) current_template: HashMap<String, Arc<TableTemple<C>>, RandomState>,
   #[cfg(feature = "lut_template")]
+  pub(crate) noise_lut_template: HashMap<String, Arc<TableTemple<C>>, RandomState>,
+  #[cfg(feature = "lut_template")]
+  pub(crate) noise_propagation_template:
+    HashMap<String, Arc<TableTemple<C>>, RandomState>,
+  #[cfg(feature = "lut_template")]
   pub(crate) compact_lut_template:
     HashMap<String, Arc<CompactLutTemplate<C>>, RandomState>,
   #[cfg(feature = "lut_template")]
   pub(crate) poly_template: HashMap<String, Arc<PolyTemplate<C>>, RandomState>,
   #[cfg(feature = "lut_template")]
+  pub(crate) power_poly_template: HashMap<String, Arc<PolyTemplate<C>>, RandomState>,
+  #[cfg(feature = "lut_template")]
   pub(crate) propagation_lut_template:
     HashMap<String, Arc<PropagationLutTemplate<C>>, RandomState>,
   pub(crate) voltage_map: HashMap<String, f64, RandomState>,
@@ -1302,7 +1309,7 @@ pub(crate) fn fmt_library_beginning<T: Write, I: Indentation>(
       write!(f, "** {s}")
     }
   } else {
-    write!(f, "** Generated by {} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"),)
+    write!(f, "** Generated by {} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"))
   }
 }
 impl Format for SimpleWrapper {
@@ -15,6 +15,12 @@ use crate::{
 };
 use core::fmt::{self, Write};
 
+mod noise;
+mod receiver;
+pub use receiver::{ReceiverError, ReceiverLoad, ReceiverModel, ReceiverSegment};
+mod stage;
+pub use stage::{StageError, VoltageWaveform};
+
 /// Use the `ccsn_first_stage` group to specify CCS noise for the first stage of the channel-
 /// connected block (CCB).
 ///
@@ -0,0 +1,87 @@
+//! Propagated noise through a CCS noise stage, by the `propagated_noise_high/low`
+//! vectors, see [`CCSNStage::propagated_noise`].
+#![allow(clippy::multiple_inherent_impl)]
+use super::{CCSNStage, VoltageWaveform};
+use crate::{
+  Ctx,
+  pin::{NoiseError, NoiseRegion},
+  timing::PropagatedNoise,
+};
+
+impl VoltageWaveform {
+  /// The glitch of the waveform, from its first voltage.
+  ///
+  /// The height is the maximum deviation from the first voltage, the width is
+  /// measured at the half height, and the peak time is from the rising
+  /// half-height crossing to the peak. `None` if there is no deviation, or the
+  /// waveform does not return below the half height.
+  #[inline]
+  #[must_use]
+  #[expect(clippy::float_arithmetic)]
+  pub fn glitch(&self) -> Option<PropagatedNoise> {
+    let base = *self.voltage.first()?;
+    let deviation: Vec<f64> = self.voltage.iter().map(|v| (v - base).abs()).collect();
+    let (peak, height) = deviation
+      .iter()
+      .copied()
+      .enumerate()
+      .max_by(|(_, a), (_, b)| a.total_cmp(b))?;
+    if height <= 0.0 {
+      return None;
+    }
+    let half = height / 2.0;
+    let crossing = |(t, d): (&[f64], &[f64])| match (t, d) {
+      ([t0, t1], [d0, d1]) if (*d0 < half) != (*d1 < half) => {
+        Some((t1 - t0).mul_add((half - d0) / (d1 - d0), *t0))
+      }
+      _ => None,
+    };
+    let start = self
+      .time
+      .get(..=peak)?
+      .windows(2)
+      .zip(deviation.get(..=peak)?.windows(2))
+      .rev()
+      .find_map(crossing)?;
+    let end = self
+      .time
+      .get(peak..)?
+      .windows(2)
+      .zip(deviation.get(peak..)?.windows(2))
+      .find_map(crossing)?;
+    let peak_time = self.time.get(peak)?;
+    Some(PropagatedNoise {
+      height,
+      width: end - start,
+      peak_time: Some(peak_time - start),
+      extrapolated: false,
+    })
+  }
+}
+
+impl<C: 'static + Ctx> CCSNStage<C> {
+  /// The propagated glitch at the output `load`, for the input glitch of
+  /// `height` and `width` in `region`, by the output-voltage waveform of
+  /// `propagated_noise_high/low`, see [`Vector4DGrpup::waveform`](crate::table::Vector4DGrpup::waveform)
+  /// and [`VoltageWaveform::glitch`].
+  ///
+  /// Only the [`NoiseRegion::High`] and [`NoiseRegion::Low`] are supported.
+  #[inline]
+  pub fn propagated_noise(
+    &self,
+    region: NoiseRegion,
+    height: f64,
+    width: f64,
+    load: f64,
+  ) -> Result<PropagatedNoise, NoiseError> {
+    let vectors = match region {
+      NoiseRegion::High => self.propagated_noise_high.as_ref(),
+      NoiseRegion::Low => self.propagated_noise_low.as_ref(),
+      NoiseRegion::AboveHigh | NoiseRegion::BelowLow => None,
+    }
+    .ok_or(NoiseError::NoModel(region))?;
+    let waveform = vectors.waveform(height, width, load)?;
+    let glitch = waveform.value.glitch().ok_or(NoiseError::NoGlitch)?;
+    Ok(PropagatedNoise { extrapolated: waveform.extrapolated, ..glitch })
+  }
+}
@@ -0,0 +1,303 @@
+//! Evaluation of the CCS receiver capacitance models, see [`Pin::receiver_load`].
+#![allow(clippy::multiple_inherent_impl)]
+use super::ReceiverCapacitance;
+use crate::{
+  Ctx,
+  ast::LibertySet,
+  library::Library,
+  pin::Pin,
+  table::{
+    LookupConfig, LookupError, OutOfRange, TableLookUp, TableLookUpMultiSegment, Variable,
+  },
+  timing::Timing,
+};
+
+/// The model used by [`Pin::receiver_load`]
+#[derive(Debug, Clone, Copy)]
+#[derive(Hash, PartialEq, Eq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub enum ReceiverModel {
+  /// `receiver_capacitance1/2_rise/fall`, split at the input delay threshold
+  TwoPiece,
+  /// `receiver_capacitance_rise/fall`, split at the
+  /// `receiver_capacitance_rise/fall_threshold_pct` of library
+  MultiSegment,
+  /// `rise_capacitance` / `fall_capacitance` / `capacitance` of pin,
+  /// without the CCS receiver data
+  Pin,
+}
+
+/// One segment of the receiver capacitance
+#[derive(Debug, Clone, Copy)]
+#[derive(PartialEq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct ReceiverSegment {
+  /// The start of the segment, in percentage of the rail voltage
+  pub from: f64,
+  /// The end of the segment, in percentage of the rail voltage
+  pub to: f64,
+  /// The capacitance in the segment
+  pub capacitance: f64,
+}
+
+/// The receiver capacitance seen by the net, see [`Pin::receiver_load`]
+#[derive(Debug, Clone)]
+#[derive(PartialEq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct ReceiverLoad {
+  /// The model of the capacitance
+  pub model: ReceiverModel,
+  /// The segments in the order of the transition
+  pub segments: Vec<ReceiverSegment>,
+  /// The effective capacitance, i.e., the average of segments weighted by
+  /// their voltage spans, so that it takes the same charge over the transition
+  pub effective: f64,
+  /// Whether the lookup is out of the table range and clamped
+  pub extrapolated: bool,
+}
+
+impl ReceiverLoad {
+  /// The capacitance of the segment containing `pct` of the rail voltage
+  #[inline]
+  #[must_use]
+  pub fn at(&self, pct: f64) -> Option<f64> {
+    self
+      .segments
+      .iter()
+      .find(|segment| {
+        (f64::min(segment.from, segment.to)..=f64::max(segment.from, segment.to))
+          .contains(&pct)
+      })
+      .map(|segment| segment.capacitance)
+  }
+}
+
+/// The error of [`Pin::receiver_load`]
+#[derive(Debug, Clone, Copy, thiserror::Error, PartialEq)]
+pub enum ReceiverError {
+  /// Neither the CCS receiver data nor the pin capacitance
+  #[error("no receiver capacitance")]
+  NoCapacitance,
+  /// The number of multi-segment tables mismatches with the library thresholds
+  #[error("expect {expected} segments by the threshold_pct, found {found}")]
+  Segments {
+    /// The number of segments by the library thresholds
+    expected: usize,
+    /// The number of tables
+    found: usize,
+  },
+  /// The table lookup fails
+  #[error(transparent)]
+  Lookup(#[from] LookupError),
+}
+
+/// The receiver capacitance tables of one direction
+struct Tables<'a, C: 'static + Ctx> {
+  c1: Option<&'a TableLookUp<C>>,
+  c2: Option<&'a TableLookUp<C>>,
+  multi_segment: &'a LibertySet<TableLookUpMultiSegment<C>>,
+}
+
+/// The effective capacitance of `segments`, weighted by their voltage spans
+#[expect(clippy::float_arithmetic, clippy::as_conversions, clippy::cast_precision_loss)]
+fn effective(segments: &[ReceiverSegment]) -> f64 {
+  let (charge, span) = segments.iter().fold((0.0, 0.0), |(charge, span), segment| {
+    let width = (segment.to - segment.from).abs();
+    (segment.capacitance.mul_add(width, charge), span + width)
+  });
+  if span > 0.0 {
+    charge / span
+  } else {
+    segments.iter().map(|segment| segment.capacitance).sum::<f64>()
+      / segments.len().max(1) as f64
+  }
+}
+
+impl<C: 'static + Ctx> Tables<'_, C> {
+  /// Evaluate the multi-segment model if defined, or the two-piece one,
+  /// `None` without both
+  fn evaluate(
+    &self,
+    rise: bool,
+    slew: f64,
+    load: f64,
+    library: &Library<C>,
+  ) -> Result<Option<ReceiverLoad>, ReceiverError> {
+    let config = LookupConfig {
+      out_of_range: OutOfRange::Clamp,
+      ..LookupConfig::default()
+    };
+    let variables =
+      [Variable::INPUT_NET_TRANSITION, Variable::TOTAL_OUTPUT_NET_CAPACITANCE];
+    let query = |variable| {
+      if variable == Some(Variable::TOTAL_OUTPUT_NET_CAPACITANCE) { load } else { slew }
+    };
+    let mut extrapolated = false;
+    let (model, segments) = if self.multi_segment.is_empty() {
+      let (Some(c1), Some(c2)) = (self.c1, self.c2) else {
+        return Ok(None);
+      };
+      let (start, split, end) = if rise {
+        (
+          library.slew_lower_threshold_pct_rise,
+          library.input_threshold_pct_rise,
+          library.slew_upper_threshold_pct_rise,
+        )
+      } else {
+        (
+          library.slew_upper_threshold_pct_fall,
+          library.input_threshold_pct_fall,
+          library.slew_lower_threshold_pct_fall,
+        )
+      };
+      let mut segments = Vec::with_capacity(2);
+      for (from, to, table) in [(start, split, c1), (split, end, c2)] {
+        let lookup = table.lookup_variables(&variables, query, config)?;
+        extrapolated |= lookup.extrapolated;
+        segments.push(ReceiverSegment { from, to, capacitance: lookup.value });
+      }
+      (ReceiverModel::TwoPiece, segments)
+    } else {
+      let thresholds = if rise {
+        &library.receiver_capacitance_rise_threshold_pct
+      } else {
+        &library.receiver_capacitance_fall_threshold_pct
+      };
+      let mut tables: Vec<_> = self.multi_segment.iter().collect();
+      tables.sort_by_key(|table| table.segment());
+      if thresholds.len() != tables.len().saturating_add(1) {
+        return Err(ReceiverError::Segments {
+          expected: thresholds.len().saturating_sub(1),
+          found: tables.len(),
+        });
+      }
+      let mut segments = Vec::with_capacity(tables.len());
+      for (table, bounds) in tables.into_iter().zip(thresholds.windows(2)) {
+        let lookup = table.lookup_variables(&variables, query, config)?;
+        extrapolated |= lookup.extrapolated;
+        if let [from, to] = bounds {
+          segments.push(ReceiverSegment {
+            from: *from,
+            to: *to,
+            capacitance: lookup.value,
+          });
+        }
+      }
+      (ReceiverModel::MultiSegment, segments)
+    };
+    Ok(Some(ReceiverLoad {
+      model,
+      effective: effective(&segments),
+      segments,
+      extrapolated,
+    }))
+  }
+}
+
+impl<C: 'static + Ctx> ReceiverCapacitance<C> {
+  /// The receiver capacitance of the `rise` / fall input transition with `slew`,
+  /// see [`Pin::receiver_load`], `None` without the tables of the direction
+  #[inline]
+  pub fn receiver_load(
+    &self,
+    rise: bool,
+    slew: f64,
+    load: f64,
+    library: &Library<C>,
+  ) -> Result<Option<ReceiverLoad>, ReceiverError> {
+    let tables = if rise {
+      Tables {
+        c1: self.receiver_capacitance1_rise.as_ref(),
+        c2: self.receiver_capacitance2_rise.as_ref(),
+        multi_segment: &self.receiver_capacitance_rise,
+      }
+    } else {
+      Tables {
+        c1: self.receiver_capacitance1_fall.as_ref(),
+        c2: self.receiver_capacitance2_fall.as_ref(),
+        multi_segment: &self.receiver_capacitance_fall,
+      }
+    };
+    tables.evaluate(rise, slew, load, library)
+  }
+}
+
+impl<C: 'static + Ctx> Timing<C> {
+  /// The receiver capacitance of the related pin in this arc, of the `rise` /
+  /// fall input transition with `slew` and the output `load`,
+  /// see [`Pin::receiver_load`], `None` without the tables of the direction
+  #[inline]
+  pub fn receiver_load(
+    &self,
+    rise: bool,
+    slew: f64,
+    load: f64,
+    library: &Library<C>,
+  ) -> Result<Option<ReceiverLoad>, ReceiverError> {
+    let tables = if rise {
+      Tables {
+        c1: self.receiver_capacitance1_rise.as_ref(),
+        c2: self.receiver_capacitance2_rise.as_ref(),
+        multi_segment: &self.receiver_capacitance_rise,
+      }
+    } else {
+      Tables {
+        c1: self.receiver_capacitance1_fall.as_ref(),
+        c2: self.receiver_capacitance2_fall.as_ref(),
+        multi_segment: &self.receiver_capacitance_fall,
+      }
+    };
+    tables.evaluate(rise, slew, load, library)
+  }
+}
+
+impl<C: 'static + Ctx> Pin<C> {
+  /// The receiver capacitance seen by the net driving this pin, of the
+  /// `rise` / fall transition with `slew`, and the output `load` of `arc`.
+  ///
+  /// The model comes from the first one defined of
+  /// + the timing-level tables of `arc`, the timing arc related to this pin,
+  /// + the `receiver_capacitance` group of the pin, the one without `when` first,
+  /// + the `rise_capacitance` / `fall_capacitance`, or `capacitance` of the pin.
+  ///
+  /// The multi-segment model is preferred to the two-piece one, and its tables
+  /// are ordered by `segment` to the intervals of the library thresholds.
+  /// The tables are clamped into their range.
+  #[inline]
+  pub fn receiver_load(
+    &self,
+    arc: Option<&Timing<C>>,
+    rise: bool,
+    slew: f64,
+    load: f64,
+    library: &Library<C>,
+  ) -> Result<ReceiverLoad, ReceiverError> {
+    if let Some(found) = arc
+      .map(|timing| timing.receiver_load(rise, slew, load, library))
+      .transpose()?
+      .flatten()
+    {
+      return Ok(found);
+    }
+    let mut groups: Vec<_> = self.receiver_capacitance.iter().collect();
+    groups.sort_by_key(|group| group.when.is_some());
+    for group in groups {
+      if let Some(found) = group.receiver_load(rise, slew, load, library)? {
+        return Ok(found);
+      }
+    }
+    let capacitance = if rise { self.rise_capacitance } else { self.fall_capacitance }
+      .or(self.capacitance)
+      .ok_or(ReceiverError::NoCapacitance)?;
+    Ok(ReceiverLoad {
+      model: ReceiverModel::Pin,
+      segments: vec![ReceiverSegment {
+        from: if rise { 0.0 } else { 100.0 },
+        to: if rise { 100.0 } else { 0.0 },
+        capacitance,
+      }],
+      effective: capacitance,
+      extrapolated: false,
+    })
+  }
+}
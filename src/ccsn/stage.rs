@@ -0,0 +1,313 @@
+//! Transient simulation of a CCS noise stage, see [`CCSNStage::simulate`].
+#![allow(clippy::multiple_inherent_impl)]
+use super::CCSNStage;
+use crate::{
+  Ctx,
+  table::{LookupConfig, LookupError, OutOfRange},
+};
+
+/// A piecewise-linear voltage waveform, constant beyond its ends
+#[derive(Debug, Clone, Default)]
+#[derive(PartialEq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct VoltageWaveform {
+  /// The time points, ascending
+  pub time: Vec<f64>,
+  /// The voltage at the time points
+  pub voltage: Vec<f64>,
+}
+
+/// The error of [`CCSNStage::simulate`]
+#[derive(Debug, Clone, Copy, thiserror::Error, PartialEq)]
+pub enum StageError {
+  /// The stage has no `dc_current` table
+  #[error("no dc_current")]
+  NoDcCurrent,
+  /// The `dc_current` table is not able to look up
+  #[error(transparent)]
+  Lookup(#[from] LookupError),
+  /// The input waveform is empty
+  #[error("empty input waveform")]
+  EmptyInput,
+  /// The time step is not positive, or the end time is before the input start
+  #[error("invalid time step {step} to {end}")]
+  Step {
+    /// The time step
+    step: f64,
+    /// The end time
+    end: f64,
+  },
+  /// There is no DC operating point of the output at the input voltage
+  #[error("no DC operating point at input voltage {0}")]
+  OperatingPoint(f64),
+}
+
+impl VoltageWaveform {
+  /// The linear ramp from `from` to `to`, starting at `start` and lasting `duration`
+  #[inline]
+  #[must_use]
+  #[expect(clippy::float_arithmetic)]
+  pub fn ramp(start: f64, duration: f64, from: f64, to: f64) -> Self {
+    Self {
+      time: vec![start, start + duration],
+      voltage: vec![from, to],
+    }
+  }
+  /// The voltage at `t`
+  #[inline]
+  #[must_use]
+  #[expect(clippy::float_arithmetic)]
+  pub fn at(&self, t: f64) -> f64 {
+    let i = self.time.partition_point(|v| *v <= t);
+    match (
+      i.checked_sub(1)
+        .and_then(|i0| self.time.get(i0).zip(self.voltage.get(i0))),
+      self.time.get(i).zip(self.voltage.get(i)),
+    ) {
+      (Some((t0, v0)), Some((t1, v1))) => (v1 - v0).mul_add((t - t0) / (t1 - t0), *v0),
+      (Some((_, v)), None) | (None, Some((_, v))) => *v,
+      (None, None) => 0.0,
+    }
+  }
+  /// The slope of voltage at `t`, zero beyond the ends
+  #[inline]
+  #[must_use]
+  #[expect(clippy::float_arithmetic)]
+  pub fn slope(&self, t: f64) -> f64 {
+    let i = self.time.partition_point(|v| *v <= t);
+    let (Some(i0), Some(i1)) = (i.checked_sub(1), (i < self.time.len()).then_some(i))
+    else {
+      return 0.0;
+    };
+    match (
+      self.time.get(i0),
+      self.time.get(i1),
+      self.voltage.get(i0),
+      self.voltage.get(i1),
+    ) {
+      (Some(t0), Some(t1), Some(v0), Some(v1)) if t1 > t0 => (v1 - v0) / (t1 - t0),
+      _ => 0.0,
+    }
+  }
+  /// The first time when the waveform crosses `level`, in either direction
+  #[inline]
+  #[must_use]
+  #[expect(clippy::float_arithmetic)]
+  pub fn crossing(&self, level: f64) -> Option<f64> {
+    let side = self.voltage.first()?.total_cmp(&level);
+    if side.is_eq() {
+      return self.time.first().copied();
+    }
+    self
+      .time
+      .windows(2)
+      .zip(self.voltage.windows(2))
+      .find_map(|(t, v)| match (t, v) {
+        ([t0, t1], [v0, v1]) if v1.total_cmp(&level) != side => {
+          Some((t1 - t0).mul_add((level - v0) / (v1 - v0), *t0))
+        }
+        _ => None,
+      })
+  }
+  /// The delay from this input waveform crossing `input_level` to the
+  /// `output` waveform crossing `output_level`, e.g., the CCSN delay with the
+  /// output of [`CCSNStage::simulate`]
+  #[inline]
+  #[must_use]
+  #[expect(clippy::float_arithmetic)]
+  pub fn delay(&self, output: &Self, input_level: f64, output_level: f64) -> Option<f64> {
+    Some(output.crossing(output_level)? - self.crossing(input_level)?)
+  }
+}
+
+impl<C: 'static + Ctx> CCSNStage<C> {
+  /// The DC current into the output node at the input voltage `vin` and
+  /// the output voltage `vout`, clamped into the `dc_current` table range
+  #[inline]
+  pub fn dc_current_at(&self, vin: f64, vout: f64) -> Result<f64, StageError> {
+    let config = LookupConfig {
+      out_of_range: OutOfRange::Clamp,
+      ..LookupConfig::default()
+    };
+    Ok(
+      self
+        .dc_current
+        .as_ref()
+        .ok_or(StageError::NoDcCurrent)?
+        .lookup_with(vin, vout, config)?
+        .value,
+    )
+  }
+  /// The DC operating point of the output at the input voltage `vin`,
+  /// where the DC current is zero, in the `index_2` range of `dc_current`
+  #[inline]
+  pub fn operating_point(&self, vin: f64) -> Result<f64, StageError> {
+    let table = self.dc_current.as_ref().ok_or(StageError::NoDcCurrent)?;
+    let mut lower = None;
+    for vout in &table.index_2 {
+      let current = self.dc_current_at(vin, *vout)?;
+      if current == 0.0 {
+        return Ok(*vout);
+      }
+      if let Some((v0, i0)) = lower
+        && (current > 0.0) != (i0 > 0.0)
+      {
+        // bisection in the bracket
+        let (mut lo, mut hi, mut i_lo) = (v0, *vout, i0);
+        for _ in 0..64 {
+          let mid = f64::midpoint(lo, hi);
+          let i_mid = self.dc_current_at(vin, mid)?;
+          if (i_mid > 0.0) == (i_lo > 0.0) {
+            (lo, i_lo) = (mid, i_mid);
+          } else {
+            hi = mid;
+          }
+        }
+        return Ok(f64::midpoint(lo, hi));
+      }
+      lower = Some((*vout, current));
+    }
+    Err(StageError::OperatingPoint(vin))
+  }
+  /// Simulate the output waveform of the stage driven by `input` into the
+  /// capacitance `load`, from the input start to `end` with the time `step`.
+  ///
+  /// The output node follows
+  /// `(load + miller) * dVout/dt = I_dc(Vin, Vout) + miller * dVin/dt`,
+  /// starting at the DC operating point, integrated by the 4th-order Runge-Kutta.
+  /// The Miller capacitance is `miller_cap_rise` or `miller_cap_fall` by the
+  /// direction of the output. For an internal stage, the `load` can be the
+  /// `load_cap_rise` or `load_cap_fall` of the stage.
+  ///
+  /// The units shall be consistent, e.g., the library default `mA`, `ns`,
+  /// `pF` and `V`.
+  #[inline]
+  #[expect(
+    clippy::float_arithmetic,
+    clippy::as_conversions,
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss,
+    clippy::cast_precision_loss
+  )]
+  pub fn simulate(
+    &self,
+    input: &VoltageWaveform,
+    load: f64,
+    step: f64,
+    end: f64,
+  ) -> Result<VoltageWaveform, StageError> {
+    let (Some(start), Some(vin_start), Some(vin_end)) =
+      (input.time.first().copied(), input.voltage.first(), input.voltage.last())
+    else {
+      return Err(StageError::EmptyInput);
+    };
+    if !(step > 0.0 && end >= start && ((end - start) / step).is_finite()) {
+      return Err(StageError::Step { step, end });
+    }
+    let initial = self.operating_point(*vin_start)?;
+    let rise = self.operating_point(*vin_end).map_or(
+      // without the final operating point, the inverting stage is the opposite of input
+      (*vin_end > *vin_start) != self.is_inverting.unwrap_or(true),
+      |last| last > initial,
+    );
+    let miller =
+      if rise { self.miller_cap_rise } else { self.miller_cap_fall }.unwrap_or_default();
+    let capacitance = load + miller;
+    let derivative = |t: f64, vout: f64| -> Result<f64, StageError> {
+      let current = self.dc_current_at(input.at(t), vout)?;
+      Ok(miller.mul_add(input.slope(t), current) / capacitance)
+    };
+    let steps = ((end - start) / step).ceil() as usize;
+    let mut output = VoltageWaveform {
+      time: Vec::with_capacity(steps.saturating_add(1)),
+      voltage: Vec::with_capacity(steps.saturating_add(1)),
+    };
+    let mut vout = initial;
+    output.time.push(start);
+    output.voltage.push(vout);
+    for n in 0..steps {
+      let t = step.mul_add(n as f64, start);
+      let h = step.min(end - t);
+      let k1 = derivative(t, vout)?;
+      let k2 = derivative(t + h / 2.0, (h / 2.0).mul_add(k1, vout))?;
+      let k3 = derivative(t + h / 2.0, (h / 2.0).mul_add(k2, vout))?;
+      let k4 = derivative(t + h, h.mul_add(k3, vout))?;
+      vout += h / 6.0 * 2.0_f64.mul_add(k2 + k3, k1 + k4);
+      output.time.push(t + h);
+      output.voltage.push(vout);
+    }
+    Ok(output)
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::{DefaultCtx, Library};
+  const LIB: &str = include_str!("../../dev/tech/ccsn.lib");
+  /// The maximum error of the crossing times to `output_voltage_*` vectors
+  /// with the load no less than `min_load`, relative to the last crossing time,
+  /// with the input ramp from time zero lasting the slew
+  fn validate(
+    stage: &CCSNStage<DefaultCtx>,
+    vectors: &crate::table::Vector3DGrpup<DefaultCtx>,
+    vdd: f64,
+    rise: bool,
+    min_load: f64,
+  ) -> f64 {
+    let mut max_error = 0.0_f64;
+    for vector in vectors.vector.iter().filter(|vector| vector.index_2 >= min_load) {
+      let (from, to) = if rise { (vdd, 0.0) } else { (0.0, vdd) };
+      let input = VoltageWaveform::ramp(0.0, vector.index_1, from, to);
+      let end = vector.index_3.last().unwrap() * 1.5;
+      let output = stage.simulate(&input, vector.index_2, end / 2000.0, end).unwrap();
+      let last = vector.index_3.last().unwrap();
+      for (t, v) in vector.index_3.iter().zip(&vector.values) {
+        let simulated = output.crossing(*v).unwrap();
+        max_error = max_error.max((simulated - t).abs() / last);
+      }
+    }
+    max_error
+  }
+  #[test]
+  fn stage_simulate() {
+    let library = Library::<DefaultCtx>::parse_lib(LIB, None).unwrap();
+    let vdd = library.nom_voltage.unwrap();
+    let cell = library.cell.iter().next().unwrap();
+    let pin = cell.pin.get("Z").unwrap();
+    let stage = pin.output_ccb.iter().next().unwrap();
+    // the inverting stage at DC
+    assert!(stage.operating_point(0.0).unwrap() > 0.99 * vdd);
+    assert!(stage.operating_point(vdd).unwrap() < 0.01 * vdd);
+    // the smallest load is sensitive to the parasitics out of the stage model
+    let rise = stage.output_voltage_rise.as_ref().unwrap();
+    let fall = stage.output_voltage_fall.as_ref().unwrap();
+    for (min_load, tolerance) in [(1e-4, 0.3), (1e-3, 0.1)] {
+      let rise_error = validate(stage, rise, vdd, true, min_load);
+      let fall_error = validate(stage, fall, vdd, false, min_load);
+      assert!(rise_error < tolerance, "{rise_error}");
+      assert!(fall_error < tolerance, "{fall_error}");
+    }
+    // a narrow glitch is filtered, a wide one propagates
+    let glitch = |width: f64| VoltageWaveform {
+      time: vec![0.0, 0.005, 0.005 + width, 0.01 + width],
+      voltage: vec![0.0, vdd, vdd, 0.0],
+    };
+    let load = 0.01;
+    let narrow = stage.simulate(&glitch(0.0), load, 1e-5, 0.1).unwrap();
+    let wide = stage.simulate(&glitch(0.05), load, 1e-4, 0.5).unwrap();
+    let min = |w: &VoltageWaveform| w.voltage.iter().copied().fold(f64::MAX, f64::min);
+    assert!(min(&narrow) > 0.5 * vdd);
+    assert!(min(&wide) < 0.1 * vdd);
+    assert!(wide.voltage.last().unwrap() > &(0.99 * vdd));
+    // the CCSN delay, the output crosses 50% at 0.0610911 in `output_voltage_fall`
+    let input = VoltageWaveform::ramp(0.0, 0.0223, 0.0, vdd);
+    let output = stage.simulate(&input, 0.02709, 1e-5, 0.2).unwrap();
+    let delay = input.delay(&output, 0.5 * vdd, 0.5 * vdd).unwrap();
+    assert!((delay - (0.0610911 - 0.0223 / 2.0)).abs() < 0.005, "{delay}");
+    assert_eq!(
+      stage.simulate(&glitch(0.0), load, 0.0, 0.1),
+      Err(StageError::Step { step: 0.0, end: 0.1 })
+    );
+  }
+}
@@ -0,0 +1,384 @@
+//! Functional cell classification, based on NPN matching of the `function` BDDs.
+#![allow(clippy::multiple_inherent_impl)]
+use super::{Cell, CellCtx as _};
+use crate::{
+  Ctx,
+  expression::npn::{NpnTransform, TruthTable},
+  pin::Direction,
+};
+use core::fmt;
+use std::sync::LazyLock;
+
+/// Canonical function class of a cell or an output pin.
+///
+/// The inputs order of each class (used by [`OutputClass::inputs`]) is listed in the document.
+#[derive(Debug, Clone, Copy)]
+#[derive(Hash, PartialEq, Eq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub enum FunctionClass {
+  /// No logic function, e.g. filler, decap, tap or endcap cell
+  Filler,
+  /// Constant `1`
+  TieHigh,
+  /// Constant `0`
+  TieLow,
+  /// Cell with both constant `1` and `0` outputs
+  TieHighLow,
+  /// `A`
+  Buf,
+  /// `!A`
+  Inv,
+  /// `A & B & ..`
+  And(usize),
+  /// `!(A & B & ..)`
+  Nand(usize),
+  /// `A | B | ..`
+  Or(usize),
+  /// `!(A | B | ..)`
+  Nor(usize),
+  /// `A ^ B ^ ..`
+  Xor(usize),
+  /// `!(A ^ B ^ ..)`
+  Xnor(usize),
+  /// `A1 & A2 | B`
+  Ao21,
+  /// `!(A1 & A2 | B)`
+  Aoi21,
+  /// `(A1 | A2) & B`
+  Oa21,
+  /// `!((A1 | A2) & B)`
+  Oai21,
+  /// `A1 & A2 | B1 & B2`
+  Ao22,
+  /// `!(A1 & A2 | B1 & B2)`
+  Aoi22,
+  /// `(A1 | A2) & (B1 | B2)`
+  Oa22,
+  /// `!((A1 | A2) & (B1 | B2))`
+  Oai22,
+  /// `!(A1 & A2 | B | C)`
+  Aoi211,
+  /// `!((A1 | A2) & B & C)`
+  Oai211,
+  /// `S ? I1 : I0`, inputs order is `I0, I1, S`
+  Mux2,
+  /// `A & B | B & C | A & C`
+  Maj3,
+  /// Outputs `A ^ B` and `A & B`
+  HalfAdder,
+  /// Outputs `A ^ B ^ CI` and `A & B | B & CI | A & CI`
+  FullAdder,
+  /// Cell with `ff` / `ff_bank` group
+  FlipFlop {
+    /// with asynchronous `clear`
+    clear: bool,
+    /// with asynchronous `preset`
+    preset: bool,
+  },
+  /// Cell with `latch` / `latch_bank` group
+  Latch {
+    /// with asynchronous `clear`
+    clear: bool,
+    /// with asynchronous `preset`
+    preset: bool,
+  },
+  /// Integrated clock-gating cell
+  ClockGate,
+  /// Not match any known class
+  Unknown,
+}
+
+impl fmt::Display for FunctionClass {
+  #[inline]
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::Filler => write!(f, "FILLER"),
+      Self::TieHigh => write!(f, "TIEHI"),
+      Self::TieLow => write!(f, "TIELO"),
+      Self::TieHighLow => write!(f, "TIEHILO"),
+      Self::Buf => write!(f, "BUF"),
+      Self::Inv => write!(f, "INV"),
+      Self::And(n) => write!(f, "AND{n}"),
+      Self::Nand(n) => write!(f, "NAND{n}"),
+      Self::Or(n) => write!(f, "OR{n}"),
+      Self::Nor(n) => write!(f, "NOR{n}"),
+      Self::Xor(n) => write!(f, "XOR{n}"),
+      Self::Xnor(n) => write!(f, "XNOR{n}"),
+      Self::Ao21 => write!(f, "AO21"),
+      Self::Aoi21 => write!(f, "AOI21"),
+      Self::Oa21 => write!(f, "OA21"),
+      Self::Oai21 => write!(f, "OAI21"),
+      Self::Ao22 => write!(f, "AO22"),
+      Self::Aoi22 => write!(f, "AOI22"),
+      Self::Oa22 => write!(f, "OA22"),
+      Self::Oai22 => write!(f, "OAI22"),
+      Self::Aoi211 => write!(f, "AOI211"),
+      Self::Oai211 => write!(f, "OAI211"),
+      Self::Mux2 => write!(f, "MUX2"),
+      Self::Maj3 => write!(f, "MAJ3"),
+      Self::HalfAdder => write!(f, "HA"),
+      Self::FullAdder => write!(f, "FA"),
+      Self::FlipFlop { clear, preset } => write!(
+        f,
+        "DFF{}{}",
+        if *clear { "R" } else { "" },
+        if *preset { "S" } else { "" }
+      ),
+      Self::Latch { clear, preset } => write!(
+        f,
+        "LATCH{}{}",
+        if *clear { "R" } else { "" },
+        if *preset { "S" } else { "" }
+      ),
+      Self::ClockGate => write!(f, "ICG"),
+      Self::Unknown => write!(f, "UNKNOWN"),
+    }
+  }
+}
+
+/// The reference truth tables of combinational classes,
+/// when several classes match, the former one is preferred
+static REFERENCES: LazyLock<Vec<(FunctionClass, TruthTable)>> = LazyLock::new(|| {
+  let bit = |m: usize, i: usize| (m >> i) & 1 == 1;
+  let and = |m: usize, n: usize| (0..n).all(|i| bit(m, i));
+  let xor = |m: usize, n: usize| (0..n).fold(false, |acc, i| acc ^ bit(m, i));
+  let ao21 = |m| bit(m, 0) && bit(m, 1) || bit(m, 2);
+  let oa21 = |m| (bit(m, 0) || bit(m, 1)) && bit(m, 2);
+  let ao22 = |m| bit(m, 0) && bit(m, 1) || bit(m, 2) && bit(m, 3);
+  let oa22 = |m| (bit(m, 0) || bit(m, 1)) && (bit(m, 2) || bit(m, 3));
+  let aoi211 = |m| !(bit(m, 0) && bit(m, 1) || bit(m, 2) || bit(m, 3));
+  let oai211 = |m| !((bit(m, 0) || bit(m, 1)) && bit(m, 2) && bit(m, 3));
+  let mux2 = |m| if bit(m, 2) { bit(m, 1) } else { bit(m, 0) };
+  let maj3 = |m| bit(m, 0) && bit(m, 1) || bit(m, 1) && bit(m, 2) || bit(m, 0) && bit(m, 2);
+  [
+    (FunctionClass::Buf, TruthTable::new(1, |m| m == 1)),
+    (FunctionClass::Inv, TruthTable::new(1, |m| m == 0)),
+  ]
+  .into_iter()
+  .chain((2..=4).flat_map(|n| {
+    [
+      (FunctionClass::And(n), TruthTable::new(n, |m| and(m, n))),
+      (FunctionClass::Nand(n), TruthTable::new(n, |m| !and(m, n))),
+      (FunctionClass::Or(n), TruthTable::new(n, |m| m != 0)),
+      (FunctionClass::Nor(n), TruthTable::new(n, |m| m == 0)),
+      (FunctionClass::Xor(n), TruthTable::new(n, |m| xor(m, n))),
+      (FunctionClass::Xnor(n), TruthTable::new(n, |m| !xor(m, n))),
+    ]
+  }))
+  .chain([
+    (FunctionClass::Ao21, TruthTable::new(3, ao21)),
+    (FunctionClass::Aoi21, TruthTable::new(3, |m| !ao21(m))),
+    (FunctionClass::Oa21, TruthTable::new(3, oa21)),
+    (FunctionClass::Oai21, TruthTable::new(3, |m| !oa21(m))),
+    (FunctionClass::Ao22, TruthTable::new(4, ao22)),
+    (FunctionClass::Aoi22, TruthTable::new(4, |m| !ao22(m))),
+    (FunctionClass::Oa22, TruthTable::new(4, oa22)),
+    (FunctionClass::Oai22, TruthTable::new(4, |m| !oa22(m))),
+    (FunctionClass::Aoi211, TruthTable::new(4, aoi211)),
+    (FunctionClass::Oai211, TruthTable::new(4, oai211)),
+    (FunctionClass::Mux2, TruthTable::new(3, mux2)),
+    (FunctionClass::Maj3, TruthTable::new(3, maj3)),
+  ])
+  .filter_map(|(class, reference)| reference.map(|tt| (class, tt)))
+  .collect()
+});
+
+/// A cell pin connected to an input of the reference function.
+#[derive(Debug, Clone)]
+#[derive(Hash, PartialEq, Eq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct MappedPin {
+  /// Name of the cell pin (or internal node)
+  pub pin: String,
+  /// Whether the pin is inverted before connected to the reference input
+  pub inverted: bool,
+}
+
+/// Classification of one output pin.
+#[derive(Debug, Clone)]
+#[derive(PartialEq, Eq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct OutputClass {
+  /// Name of the output pin
+  pub pin: String,
+  /// The matched class
+  pub class: FunctionClass,
+  /// The `i`-th input of the reference function is connected to `inputs[i]`
+  pub inputs: Vec<MappedPin>,
+  /// Whether the output of the reference function is inverted
+  pub output_inverted: bool,
+}
+
+/// The result of [`Cell::classify`].
+#[derive(Debug, Clone)]
+#[derive(PartialEq, Eq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct CellClass {
+  /// The function class of the whole cell
+  pub class: FunctionClass,
+  /// Classification of each output pin with `function`, sorted by pin name
+  pub outputs: Vec<OutputClass>,
+}
+
+/// Match the truth table with references, return the one with least inversions
+#[inline]
+fn match_reference(tt: &TruthTable) -> Option<(FunctionClass, NpnTransform)> {
+  match tt.as_const() {
+    Some(true) => return Some((FunctionClass::TieHigh, NpnTransform::identity(0))),
+    Some(false) => return Some((FunctionClass::TieLow, NpnTransform::identity(0))),
+    None => {}
+  }
+  REFERENCES
+    .iter()
+    .filter(|(_, reference)| reference.inputs() == tt.inputs())
+    .filter_map(|(class, reference)| tt.npn_match(reference).map(|t| (*class, t)))
+    .min_by_key(|(_, t)| t.inversions())
+}
+
+impl<C: 'static + Ctx> Cell<C> {
+  /// Classify every output pin by NPN matching its `function` with the reference classes.
+  ///
+  /// The input variables of a function are its support set, which may contain the
+  /// internal nodes of `ff` / `latch`. Constant functions are `TieHigh` / `TieLow`.
+  #[must_use]
+  #[inline]
+  pub fn classify_outputs(&self) -> Vec<OutputClass> {
+    let variables = self.extra_ctx.logic_variables();
+    let mut outputs: Vec<OutputClass> = self
+      .pin
+      .iter()
+      .filter_map(|pin| {
+        pin.function.as_ref().map(|function| {
+          let mut support: Vec<_> = function.bdd.support_set().into_iter().collect();
+          support.sort_unstable();
+          let matched = TruthTable::from_bdd(&function.bdd, &support)
+            .and_then(|tt| match_reference(&tt));
+          let (class, inputs, output_inverted) = matched.map_or_else(
+            || {
+              (
+                FunctionClass::Unknown,
+                support
+                  .iter()
+                  .map(|var| MappedPin { pin: variables.name_of(*var), inverted: false })
+                  .collect(),
+                false,
+              )
+            },
+            |(class, t)| {
+              (
+                class,
+                t.permutation
+                  .iter()
+                  .zip(t.input_inverted)
+                  .filter_map(|(p, inverted)| {
+                    support
+                      .get(*p)
+                      .map(|var| MappedPin { pin: variables.name_of(*var), inverted })
+                  })
+                  .collect(),
+                t.output_inverted,
+              )
+            },
+          );
+          OutputClass { pin: pin.name.clone(), class, inputs, output_inverted }
+        })
+      })
+      .collect();
+    outputs.sort_unstable_by(|o1, o2| o1.pin.cmp(&o2.pin));
+    outputs
+  }
+  /// Assign a canonical function class to this cell, see [`FunctionClass`].
+  ///
+  /// The combinational classes are matched by NPN equivalence
+  /// (not by cell name), see [`Cell::classify_outputs`].
+  #[must_use]
+  #[inline]
+  pub fn classify(&self) -> CellClass {
+    let outputs = self.classify_outputs();
+    let class = self.cell_class(&outputs);
+    CellClass { class, outputs }
+  }
+  fn cell_class(&self, outputs: &[OutputClass]) -> FunctionClass {
+    if self.is_filler_cell == Some(true) || self.pin.is_empty() {
+      return FunctionClass::Filler;
+    }
+    if self.clock_gating_integrated_cell.is_some() || self.is_clock_gating_cell == Some(true)
+    {
+      return FunctionClass::ClockGate;
+    }
+    if !self.ff.is_empty() || !self.ff_bank.is_empty() {
+      return FunctionClass::FlipFlop {
+        clear: self.ff.iter().any(|ff| ff.clear.is_some())
+          || self.ff_bank.iter().any(|ff| ff.clear.is_some()),
+        preset: self.ff.iter().any(|ff| ff.preset.is_some())
+          || self.ff_bank.iter().any(|ff| ff.preset.is_some()),
+      };
+    }
+    if !self.latch.is_empty() || !self.latch_bank.is_empty() {
+      return FunctionClass::Latch {
+        clear: self.latch.iter().any(|latch| latch.clear.is_some())
+          || self.latch_bank.iter().any(|latch| latch.clear.is_some()),
+        preset: self.latch.iter().any(|latch| latch.preset.is_some())
+          || self.latch_bank.iter().any(|latch| latch.preset.is_some()),
+      };
+    }
+    if self.statetable.is_some() {
+      return FunctionClass::Unknown;
+    }
+    if outputs.is_empty() {
+      return if self.pin.iter().any(|pin| {
+        matches!(pin.direction, Some(Direction::Output | Direction::Inout))
+      }) {
+        FunctionClass::Unknown
+      } else {
+        FunctionClass::Filler
+      };
+    }
+    if outputs
+      .iter()
+      .all(|o| matches!(o.class, FunctionClass::TieHigh | FunctionClass::TieLow))
+    {
+      return if outputs.iter().all(|o| o.class == FunctionClass::TieHigh) {
+        FunctionClass::TieHigh
+      } else if outputs.iter().all(|o| o.class == FunctionClass::TieLow) {
+        FunctionClass::TieLow
+      } else {
+        FunctionClass::TieHighLow
+      };
+    }
+    if let [o1, o2] = outputs {
+      let same_inputs = || {
+        let mut pins1: Vec<_> = o1.inputs.iter().map(|p| &p.pin).collect();
+        let mut pins2: Vec<_> = o2.inputs.iter().map(|p| &p.pin).collect();
+        pins1.sort_unstable();
+        pins2.sort_unstable();
+        pins1 == pins2
+      };
+      // the carry of half adder is `A & B`, without input negations
+      let plain = |o: &OutputClass| o.inputs.iter().all(|p| !p.inverted);
+      match (o1.class, o2.class) {
+        (FunctionClass::Xor(2) | FunctionClass::Xnor(2), FunctionClass::And(2))
+          if same_inputs() && plain(o2) =>
+        {
+          return FunctionClass::HalfAdder;
+        }
+        (FunctionClass::And(2), FunctionClass::Xor(2) | FunctionClass::Xnor(2))
+          if same_inputs() && plain(o1) =>
+        {
+          return FunctionClass::HalfAdder;
+        }
+        (FunctionClass::Xor(3) | FunctionClass::Xnor(3), FunctionClass::Maj3)
+        | (FunctionClass::Maj3, FunctionClass::Xor(3) | FunctionClass::Xnor(3))
+          if same_inputs() =>
+        {
+          return FunctionClass::FullAdder;
+        }
+        _ => {}
+      }
+    }
+    match outputs {
+      [first, rest @ ..] if rest.iter().all(|o| o.class == first.class) => first.class,
+      _ => FunctionClass::Unknown,
+    }
+  }
+}
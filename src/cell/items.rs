@@ -305,12 +305,12 @@ impl<C: 'static + Ctx> NamedGroup<C> for Statetable<C> {
 }
 
 /// `StateTable` Table
-#[derive(Default, Debug, Clone)]
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
 #[derive(serde::Serialize, serde::Deserialize)]
 pub struct Table {
   pub inner: Vec<TableNodeValues>,
 }
-#[derive(Default, Debug, Clone)]
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
 #[derive(serde::Serialize, serde::Deserialize)]
 pub struct TableNodeValues {
   pub input_node_values: Vec<InputNodeValue>,
@@ -2021,6 +2021,59 @@ impl<C: 'static + Ctx> ComplexAttri<C> for PinOpposite {
   }
 }
 
+/// Use the `pin_equal` attribute to describe a group of functionally equivalent input or output
+/// pins.
+/// ### Syntax
+///
+/// ``` text
+/// pin_equal ("name_list") ;
+/// ```
+///
+/// + `name_list`: A list of input or output pins whose values must be equal.
+///
+/// In the following example, input pins IP1 and IP0 are logically equivalent.
+/// ``` text
+/// pin_equal ("IP1 IP0") ;
+/// ```
+/// <a name ="reference_link" href="
+/// https://zao111222333.github.io/liberty-db/2020.09/reference_manual.html?field=null&bgn=124.1&end=124.8
+/// ">Reference</a>
+#[derive(Debug, Clone)]
+#[derive(Hash, PartialEq, Eq)]
+#[derive(Ord, PartialOrd)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct PinEqual {
+  pub name_list: WordSet,
+}
+crate::ast::impl_self_builder!(PinEqual);
+impl<C: 'static + Ctx> ComplexAttri<C> for PinEqual {
+  #[inline]
+  fn parse<'a, I: Iterator<Item = &'a &'a str>>(
+    mut iter: I,
+    _scope: &mut ParseScope<'_>,
+  ) -> Result<Self, ComplexParseError> {
+    let name_list: WordSet = match iter.next() {
+      Some(&s) => match s.parse() {
+        Ok(f) => f,
+        Err(_) => return Err(ComplexParseError::Other),
+      },
+      None => return Err(ComplexParseError::LengthDismatch),
+    };
+    if iter.next().is_some() {
+      Err(ComplexParseError::LengthDismatch)
+    } else {
+      Ok(Self { name_list })
+    }
+  }
+  #[inline]
+  fn fmt_self<T: Write, I: Indentation>(
+    &self,
+    f: &mut CodeFormatter<'_, T, I>,
+  ) -> fmt::Result {
+    write!(f, "{}", self.name_list)
+  }
+}
+
 /// The `retention_condition` group includes attributes that specify the conditions for the
 /// retention cell to hold its state during the retention mode.
 /// <a name ="reference_link" href="
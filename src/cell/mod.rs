@@ -1,9 +1,22 @@
 //! <script>
 //! IFRAME('https://zao111222333.github.io/liberty-db/2020.09/reference_manual.html');
 //! </script>
+mod classify;
 mod items;
 mod parser;
+mod power;
+mod sequential;
+mod statetable;
+mod symmetry;
+pub use classify::{CellClass, FunctionClass, MappedPin, OutputClass};
 pub use items::*;
+pub use power::{
+  AveragePower, JointProbability, LeakageEstimate, PinActivity, RailLeakage, RailPower,
+  SignalProbability,
+};
+pub(crate) use sequential::Sequential;
+pub use statetable::{StatetableError, StatetableIssue};
+pub use symmetry::{PinSymmetry, PinSymmetryMismatch};
 #[cfg(test)]
 mod test;
 use alloc::collections::BTreeSet;
@@ -546,6 +559,23 @@ pub struct Cell<C: 'static + Ctx> {
   /// ">Reference</a>
   #[liberty(complex)]
   pub output_voltage_range: Option<(f64, f64)>,
+  /// Use the `pin_equal` attribute to describe a group of functionally equivalent input or
+  /// output pins.
+  /// ### Syntax
+  ///
+  /// ``` text
+  /// pin_equal ("name_list") ;
+  /// ```
+  ///
+  /// In the following example, input pins IP1 and IP0 are logically equivalent.
+  /// ``` text
+  /// pin_equal ("IP1 IP0") ;
+  /// ```
+  /// <a name ="reference_link" href="
+  /// https://zao111222333.github.io/liberty-db/2020.09/reference_manual.html?field=null&bgn=124.1&end=124.8
+  /// ">Reference</a>
+  #[liberty(complex)]
+  pub pin_equal: LibertyVec<PinEqual>,
   /// Use the `pin_opposite` attribute to describe functionally opposite (logically inverse) groups
   /// of input or output pins.
   /// ### Syntax
@@ -0,0 +1,373 @@
+//! Estimation of the state-dependent leakage and the average power of a cell
+//! from the static probabilities of signals, see [`Cell::average_power`].
+#![allow(clippy::multiple_inherent_impl)]
+use super::{Cell, CellCtx as _};
+use crate::{
+  Ctx,
+  expression::{Bdd, BddVariableSet, logic},
+  internal_power::{InternalPowerError, SwitchingEvent},
+  library::Library,
+};
+use alloc::collections::BTreeMap;
+
+/// The joint distribution of correlated pins, see [`SignalProbability`]
+#[derive(Debug, Clone, Default)]
+#[derive(PartialEq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct JointProbability {
+  /// The correlated pins
+  pub pins: Vec<String>,
+  /// The levels of `pins` with their probabilities, which are normalized by
+  /// their sum, and the states not given never happen
+  pub states: Vec<(Vec<bool>, f64)>,
+}
+
+/// The static probabilities of signals, i.e., the probabilities of being high.
+///
+/// The pins are independent, except the pins of each [`JointProbability`],
+/// which are independent of the others. The pins not given are `0.5`.
+#[derive(Debug, Clone, Default)]
+#[derive(PartialEq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct SignalProbability {
+  /// The probability of each independent pin being high
+  pub high: BTreeMap<String, f64>,
+  /// The joint distributions of correlated pins
+  pub correlated: Vec<JointProbability>,
+}
+
+impl SignalProbability {
+  /// The probability of the independent `pin` being high, `0.5` when not given
+  #[inline]
+  #[must_use]
+  pub fn high_of(&self, pin: &str) -> f64 {
+    self.high.get(pin).copied().unwrap_or(0.5)
+  }
+  /// The probability that `bdd` holds.
+  ///
+  /// Each state of the correlated pins restricts `bdd`, and the rest is
+  /// evaluated over the nodes bottom-up, i.e., `P(node) = p * P(high) + (1 - p) * P(low)`.
+  #[inline]
+  #[must_use]
+  #[expect(clippy::float_arithmetic)]
+  pub fn probability(&self, bdd: &Bdd, variables: &BddVariableSet) -> f64 {
+    let mut total = 0.0;
+    let mut states = vec![(Vec::new(), 1.0)];
+    for joint in &self.correlated {
+      let sum: f64 = joint.states.iter().map(|(_, p)| p).sum();
+      if sum <= 0.0 || sum.is_nan() {
+        continue;
+      }
+      states = states
+        .into_iter()
+        .flat_map(|(restriction, weight): (Vec<_>, f64)| {
+          joint.states.iter().map(move |(levels, p)| {
+            let mut restricted = restriction.clone();
+            restricted.extend(joint.pins.iter().zip(levels).filter_map(
+              |(pin, level)| variables.var_by_name(pin).map(|var| (var, *level)),
+            ));
+            (restricted, weight * p / sum)
+          })
+        })
+        .collect();
+    }
+    for (restriction, weight) in states {
+      total =
+        weight.mul_add(self.independent(&bdd.restrict(&restriction), variables), total);
+    }
+    total
+  }
+  /// The probability that `bdd` holds with independent pins, the nodes are
+  /// in topological order, so the children come before their parent
+  #[expect(clippy::float_arithmetic)]
+  fn independent(&self, bdd: &Bdd, variables: &BddVariableSet) -> f64 {
+    let mut probability: Vec<f64> = Vec::with_capacity(bdd.size());
+    for node in bdd.pointers() {
+      let p = if node.is_zero() {
+        0.0
+      } else if node.is_one() {
+        1.0
+      } else {
+        let of = |child: usize| probability.get(child).copied().unwrap_or(0.0);
+        let low = of(bdd.low_link_of(node).to_index());
+        let high = of(bdd.high_link_of(node).to_index());
+        self
+          .high_of(variables.name_of_str(bdd.var_of(node)))
+          .mul_add(high - low, low)
+      };
+      probability.push(p);
+    }
+    probability.last().copied().unwrap_or(0.0)
+  }
+}
+
+/// The expected leakage power of one power rail, see [`Cell::leakage_estimate`]
+#[derive(Debug, Clone)]
+#[derive(PartialEq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct RailLeakage {
+  /// The `related_pg_pin` of the groups, empty for the groups without it
+  pub related_pg_pin: String,
+  /// The expected leakage power
+  pub power: f64,
+  /// The probability that no `when` of the rail holds, so the group without
+  /// `when` is used, or zero power without it
+  pub uncovered: f64,
+}
+
+/// The expected leakage power of a cell, see [`Cell::leakage_estimate`]
+#[derive(Debug, Clone)]
+#[derive(PartialEq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct LeakageEstimate {
+  /// The leakage of each rail, in the order of their first groups
+  pub rails: Vec<RailLeakage>,
+  /// The expected leakage of the states no `leakage_power` group describes,
+  /// by `cell_leakage_power` or `default_cell_leakage_power`
+  pub default: f64,
+  /// The probability of the states no `leakage_power` group describes
+  pub uncovered: f64,
+}
+
+/// The average power of one power rail, see [`Cell::average_power`]
+#[derive(Debug, Clone)]
+#[derive(PartialEq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct RailPower {
+  /// The `related_pg_pin` of the groups, empty for the groups without it
+  pub related_pg_pin: String,
+  /// The expected leakage power, see [`Cell::leakage_estimate`]
+  pub leakage: f64,
+  /// The average internal power of the activities
+  pub internal: f64,
+}
+
+/// The average power of a cell, see [`Cell::average_power`]
+#[derive(Debug, Clone)]
+#[derive(PartialEq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct AveragePower {
+  /// The power of each rail, in the order of their first groups
+  pub rails: Vec<RailPower>,
+  /// The expected default leakage of the cell, see [`LeakageEstimate::default`]
+  pub default_leakage: f64,
+  /// The sum of the default leakage, and the leakage and internal power of all rails
+  pub total: f64,
+}
+
+/// The switching activity of a pin, see [`Cell::average_power`]
+#[derive(Debug, Clone, Copy)]
+pub struct PinActivity<'a> {
+  /// The switching pin, a pin of the cell or of its buses
+  pub pin: &'a str,
+  /// The pin causing the transitions, see [`SwitchingEvent::related_pin`]
+  pub related_pin: Option<&'a str>,
+  /// The transitions per unit time, half of them rise and half fall
+  pub toggle_rate: f64,
+  /// The input transition time
+  pub slew: f64,
+  /// The total output net capacitance of the pin
+  pub load: f64,
+  /// The net capacitance of the `equal_or_opposite_output` pins
+  pub opposite_load: f64,
+  /// The other output pins switching together, see [`SwitchingEvent::opposite_pins`]
+  pub opposite_pins: &'a [&'a str],
+}
+
+/// The expectation over the groups of one `related_pg_pin`. The groups with
+/// `when` are exclusive in order, i.e., a group is used when its `when` holds
+/// and no earlier `when` holds, otherwise the group without `when` is used.
+struct Expectation {
+  name: String,
+  /// The union of `when` so far
+  covered: Bdd,
+  /// The expected value of the groups with `when`
+  value: f64,
+  /// The value of the first group without `when`
+  default: Option<f64>,
+}
+
+impl Expectation {
+  /// Accumulate the `value` of a group with `when` into the rail `name`
+  fn accumulate(
+    rails: &mut Vec<Self>,
+    name: &str,
+    when: Option<&Bdd>,
+    value: f64,
+    probability: &SignalProbability,
+    variables: &BddVariableSet,
+  ) {
+    if !rails.iter().any(|rail| rail.name == name) {
+      rails.push(Self {
+        name: name.to_owned(),
+        covered: variables.mk_false(),
+        value: 0.0,
+        default: None,
+      });
+    }
+    if let Some(rail) = rails.iter_mut().find(|rail| rail.name == name) {
+      if let Some(bdd) = when {
+        let exclusive = bdd.and_not(&rail.covered);
+        rail.value = probability
+          .probability(&exclusive, variables)
+          .mul_add(value, rail.value);
+        rail.covered = rail.covered.or(bdd);
+      } else {
+        _ = rail.default.get_or_insert(value);
+      }
+    }
+  }
+  /// The expected value and the uncovered probability,
+  /// the uncovered states are zero without the default group
+  #[expect(clippy::float_arithmetic)]
+  fn finish(
+    &self,
+    probability: &SignalProbability,
+    variables: &BddVariableSet,
+  ) -> (f64, f64) {
+    let uncovered = 1.0 - probability.probability(&self.covered, variables);
+    (uncovered.mul_add(self.default.unwrap_or(0.0), self.value), uncovered)
+  }
+}
+
+impl<C: 'static + Ctx> Cell<C> {
+  /// The expected leakage power per `related_pg_pin` under `probability`,
+  /// in the order of the `leakage_power` groups.
+  ///
+  /// Of each rail, the groups with `when` are exclusive in order, and the
+  /// states no `when` covers take the group without `when`, or zero power
+  /// without it, which is reported as `uncovered`.
+  ///
+  /// The states no group describes, i.e., no `when` of any rail holds and
+  /// every group has `when`, take the `cell_leakage_power` once per cell, or
+  /// the `default_cell_leakage_power` of `library` when it is missing or negative.
+  #[inline]
+  #[must_use]
+  #[expect(clippy::float_arithmetic)]
+  pub fn leakage_estimate(
+    &self,
+    probability: &SignalProbability,
+    library: &Library<C>,
+  ) -> LeakageEstimate {
+    let variables = self.extra_ctx.logic_variables();
+    let mut rails = Vec::new();
+    let mut described = variables.mk_false();
+    for group in &self.leakage_power {
+      let when = group.when.as_ref().map(|when| &when.bdd);
+      described = when.map_or_else(|| variables.mk_true(), |bdd| described.or(bdd));
+      Expectation::accumulate(
+        &mut rails,
+        &group.related_pg_pin.to_string(),
+        when,
+        group.value,
+        probability,
+        variables,
+      );
+    }
+    let uncovered = 1.0 - probability.probability(&described, variables);
+    let default = self
+      .cell_leakage_power
+      .filter(|power| *power >= 0.0)
+      .or(library.default_cell_leakage_power)
+      .unwrap_or(0.0);
+    LeakageEstimate {
+      rails: rails
+        .into_iter()
+        .map(|rail| {
+          let (power, rail_uncovered) = rail.finish(probability, variables);
+          RailLeakage {
+            related_pg_pin: rail.name,
+            power,
+            uncovered: rail_uncovered,
+          }
+        })
+        .collect(),
+      default: uncovered * default,
+      uncovered,
+    }
+  }
+  /// The average power per `related_pg_pin`, the expected leakage of
+  /// [`Cell::leakage_estimate`] plus the internal power of `activities`,
+  /// and the expected default leakage of the cell.
+  ///
+  /// The internal power of an activity is `toggle_rate / 2` times the expected
+  /// rise and fall energy. The energy is the expectation over the `when` of the
+  /// `internal_power` groups selected as [`crate::pin::Pin::internal_energy`],
+  /// which are exclusive in order, and the states no `when` covers take the
+  /// group without `when`, or zero without it.
+  /// The power is in the energy unit of the library per the time unit of `toggle_rate`.
+  #[inline]
+  #[expect(clippy::float_arithmetic)]
+  pub fn average_power(
+    &self,
+    probability: &SignalProbability,
+    activities: &[PinActivity<'_>],
+    library: &Library<C>,
+  ) -> Result<AveragePower, InternalPowerError> {
+    let variables = self.extra_ctx.logic_variables();
+    let leakage = self.leakage_estimate(probability, library);
+    let mut rails: Vec<RailPower> = leakage
+      .rails
+      .into_iter()
+      .map(|rail| RailPower {
+        related_pg_pin: rail.related_pg_pin,
+        leakage: rail.power,
+        internal: 0.0,
+      })
+      .collect();
+    for activity in activities {
+      let pin = self
+        .pin
+        .get(activity.pin)
+        .or_else(|| self.bus.iter().find_map(|bus| bus.pin.get(activity.pin)))
+        .ok_or_else(|| InternalPowerError::NoPin(activity.pin.to_owned()))?;
+      for edge in [logic::Edge::R, logic::Edge::F] {
+        let event = SwitchingEvent {
+          related_pin: activity.related_pin,
+          edge,
+          slew: activity.slew,
+          load: activity.load,
+          opposite_load: activity.opposite_load,
+          opposite_pins: activity.opposite_pins,
+          state: &[],
+        };
+        let mut energies = Vec::new();
+        for group in &pin.internal_power {
+          if !group.is_related(activity.related_pin)
+            || !group.is_opposite(activity.opposite_pins)
+          {
+            continue;
+          }
+          if let Some(lookup) = group.lookup_energy(&event)? {
+            Expectation::accumulate(
+              &mut energies,
+              &group.related_pg_pin.to_string(),
+              group.when.as_ref().map(|when| &when.bdd),
+              lookup.value,
+              probability,
+              variables,
+            );
+          }
+        }
+        for energy in energies {
+          let internal =
+            0.5 * activity.toggle_rate * energy.finish(probability, variables).0;
+          if let Some(rail) =
+            rails.iter_mut().find(|rail| rail.related_pg_pin == energy.name)
+          {
+            rail.internal += internal;
+          } else {
+            rails.push(RailPower {
+              related_pg_pin: energy.name,
+              leakage: 0.0,
+              internal,
+            });
+          }
+        }
+      }
+    }
+    let total = rails.iter().map(|rail| rail.leakage + rail.internal).sum::<f64>()
+      + leakage.default;
+    Ok(AveragePower { rails, default_leakage: leakage.default, total })
+  }
+}
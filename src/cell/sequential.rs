@@ -0,0 +1,832 @@
+//! Conversion between the sequential groups (`ff`, `ff_bank`, `latch`, `latch_bank`)
+//! and the equivalent [`Statetable`], verified by exhaustive simulation.
+#![allow(clippy::multiple_inherent_impl, clippy::redundant_pub_crate)]
+use super::{
+  CurrentInternalNodeValue, InputNodeValue, NextInternalNodeValue, Statetable, Table,
+  TableNodeValues,
+  statetable::{Transition, to_static},
+};
+use crate::{
+  Ctx,
+  expression::{
+    Bdd, BddVariableSet, ClearPresetState, Expr, FF, FFBank, Latch, LatchBank,
+    LogicBooleanExpression,
+    logic::{Level, Static},
+  },
+};
+use alloc::collections::BTreeSet;
+use biodivine_lib_bdd::{BddPartialValuation, BddVariable};
+use itertools::Itertools as _;
+
+/// Common accessors of `ff`, `ff_bank`, `latch` and `latch_bank`
+pub(crate) trait Sequential {
+  fn is_latch(&self) -> bool;
+  /// `ff_bank` / `latch_bank` bits, `0` for `ff` / `latch`
+  fn bank_bits(&self) -> usize;
+  fn variables(&self) -> [&str; 2];
+  /// `[clear, preset, clocked_on / enable, clocked_on_also / enable_also, next_state / data_in]`
+  fn functions(&self) -> [Option<&LogicBooleanExpression>; 5];
+  fn clear_preset_var(&self) -> [Option<&ClearPresetState>; 2];
+}
+
+#[duplicate::duplicate_item(
+  FF_type bits;
+  [FF] [0];
+  [FFBank] [self.bits];
+)]
+impl<C: 'static + Ctx> Sequential for FF_type<C> {
+  #[inline]
+  fn is_latch(&self) -> bool {
+    false
+  }
+  #[inline]
+  fn bank_bits(&self) -> usize {
+    bits
+  }
+  #[inline]
+  fn variables(&self) -> [&str; 2] {
+    [&self.variable1, &self.variable2]
+  }
+  #[inline]
+  fn functions(&self) -> [Option<&LogicBooleanExpression>; 5] {
+    [
+      self.clear.as_ref(),
+      self.preset.as_ref(),
+      self.clocked_on.as_ref(),
+      self.clocked_on_also.as_ref(),
+      self.next_state.as_ref(),
+    ]
+  }
+  #[inline]
+  fn clear_preset_var(&self) -> [Option<&ClearPresetState>; 2] {
+    [self.clear_preset_var1.as_ref(), self.clear_preset_var2.as_ref()]
+  }
+}
+
+#[duplicate::duplicate_item(
+  Latch_type bits;
+  [Latch] [0];
+  [LatchBank] [self.bits];
+)]
+impl<C: 'static + Ctx> Sequential for Latch_type<C> {
+  #[inline]
+  fn is_latch(&self) -> bool {
+    true
+  }
+  #[inline]
+  fn bank_bits(&self) -> usize {
+    bits
+  }
+  #[inline]
+  fn variables(&self) -> [&str; 2] {
+    [&self.variable1, &self.variable2]
+  }
+  #[inline]
+  fn functions(&self) -> [Option<&LogicBooleanExpression>; 5] {
+    [
+      self.clear.as_ref(),
+      self.preset.as_ref(),
+      self.enable.as_ref(),
+      self.enable_also.as_ref(),
+      self.data_in.as_ref(),
+    ]
+  }
+  #[inline]
+  fn clear_preset_var(&self) -> [Option<&ClearPresetState>; 2] {
+    [self.clear_preset_var1.as_ref(), self.clear_preset_var2.as_ref()]
+  }
+}
+
+const STABLE_L: Transition = (Level::L, Level::L);
+const STABLE_H: Transition = (Level::H, Level::H);
+const RISE: Transition = (Level::L, Level::H);
+const FALL: Transition = (Level::H, Level::L);
+/// All the concrete transitions, the first two are the stable levels
+const TRANSITIONS: [Transition; 4] = [STABLE_L, STABLE_H, RISE, FALL];
+const LEVELS: [Level; 2] = [Level::L, Level::H];
+
+/// Evaluate the expression, return `None` when any variable is unknown
+fn eval<F: Fn(&str) -> Option<bool>>(expr: &Expr, value: &F) -> Option<bool> {
+  Some(match expr {
+    Expr::Const(b) => *b,
+    Expr::Variable(name) => value(name)?,
+    Expr::Not(e) => !eval(e, value)?,
+    Expr::And(e1, e2) => eval(e1, value)? & eval(e2, value)?,
+    Expr::Or(e1, e2) => eval(e1, value)? | eval(e2, value)?,
+    Expr::Xor(e1, e2) => eval(e1, value)? ^ eval(e2, value)?,
+    Expr::Imp(e1, e2) => !eval(e1, value)? | eval(e2, value)?,
+    Expr::Iff(e1, e2) => eval(e1, value)? == eval(e2, value)?,
+    Expr::Cond(e1, e2, e3) => {
+      if eval(e1, value)? {
+        eval(e2, value)?
+      } else {
+        eval(e3, value)?
+      }
+    }
+  })
+}
+
+const fn inverse(value: Static) -> Static {
+  match value {
+    Static::L => Static::H,
+    Static::H => Static::L,
+    Static::X | Static::Z => Static::X,
+  }
+}
+
+const fn clear_preset_value(state: Option<&ClearPresetState>, current: Level) -> Static {
+  match state {
+    Some(ClearPresetState::L) => Static::L,
+    Some(ClearPresetState::H) => Static::H,
+    Some(ClearPresetState::N) => to_static(current),
+    Some(ClearPresetState::T) => to_static(current.inverse()),
+    Some(ClearPresetState::X) | None => Static::X,
+  }
+}
+
+/// The next `[variable1, variable2]` of the sequential group, the same as
+/// [`LatchFF::variable_expr`](crate::expression::LatchFF::variable_expr).
+///
+/// The clock edge of `ff` is detected on the `(bgn, end)` levels of inputs,
+/// and all the other functions are evaluated on the `end` levels.
+/// Return `None` when any node is unknown.
+fn sequential_next<S: Sequential + ?Sized>(
+  group: &S,
+  input_nodes: &[String],
+  inputs: &[Transition],
+  current: [Level; 2],
+) -> Option<[Static; 2]> {
+  let [variable1, variable2] = group.variables();
+  let [cur1, cur2] = current;
+  let value = |bgn: bool| {
+    move |name: &str| {
+      if name == variable1 {
+        Some(cur1.into())
+      } else if name == variable2 {
+        Some(cur2.into())
+      } else {
+        input_nodes
+          .iter()
+          .position(|node| node == name)
+          .and_then(|i| inputs.get(i))
+          .map(|(bgn_level, end_level)| {
+            (if bgn { *bgn_level } else { *end_level }).into()
+          })
+      }
+    }
+  };
+  let (bgn, end) = (value(true), value(false));
+  let [clear, preset, active, active_also, next_state] = group.functions();
+  let level = |function: Option<&LogicBooleanExpression>| {
+    function.map_or(Some(false), |f| eval(&f.expr, &end))
+  };
+  let is_active = |function: Option<&LogicBooleanExpression>| {
+    function.map_or(Some(false), |f| {
+      if group.is_latch() {
+        eval(&f.expr, &end)
+      } else {
+        Some(!eval(&f.expr, &bgn)? && eval(&f.expr, &end)?)
+      }
+    })
+  };
+  let [clear_preset_var1, clear_preset_var2] = group.clear_preset_var();
+  Some(match (level(clear)?, level(preset)?) {
+    (true, true) => [
+      clear_preset_value(clear_preset_var1, cur1),
+      clear_preset_value(clear_preset_var2, cur2),
+    ],
+    (true, false) => [Static::L, Static::H],
+    (false, true) => [Static::H, Static::L],
+    (false, false) => match (next_state, is_active(active)?, is_active(active_also)?) {
+      (Some(_), true, true) => [Static::X, Static::X],
+      (Some(function), true, false) | (Some(function), false, true) => {
+        let next = to_static(eval(&function.expr, &end)?.into());
+        [next, inverse(next)]
+      }
+      (None, _, _) | (Some(_), false, false) => [to_static(cur1), to_static(cur2)],
+    },
+  })
+}
+
+/// The outcomes of the statetable with all the current values,
+/// the second internal node is `!variable1` when there is only one internal node
+fn statetable_outcomes<C: 'static + Ctx>(
+  statetable: &Statetable<C>,
+  inputs: &[Transition],
+) -> Option<Vec<([Level; 2], [Static; 2])>> {
+  let single = statetable.internal_nodes.len() == 1;
+  LEVELS
+    .iter()
+    .cartesian_product(LEVELS)
+    .filter(|(cur1, cur2)| !single || cur1.inverse() == *cur2)
+    .map(|(cur1, cur2)| {
+      let next = if single {
+        statetable.evaluate_concrete(inputs, &[*cur1]).ok()?
+      } else {
+        statetable.evaluate_concrete(inputs, &[*cur1, cur2]).ok()?
+      };
+      Some(match next.as_slice() {
+        [next1] => ([*cur1, cur2], [*next1, inverse(*next1)]),
+        [next1, next2] => ([*cur1, cur2], [*next1, *next2]),
+        _ => return None,
+      })
+    })
+    .collect()
+}
+
+/// Exhaustively simulate the sequential group and the statetable,
+/// with all the input transitions and current values
+fn simulate_eq<C: 'static + Ctx, S: Sequential + ?Sized>(
+  group: &S,
+  statetable: &Statetable<C>,
+) -> bool {
+  let nodes = statetable.internal_nodes.len();
+  statetable
+    .input_nodes
+    .iter()
+    .map(|_| TRANSITIONS)
+    .multi_cartesian_product()
+    .all(|inputs| {
+      statetable_outcomes(statetable, &inputs).is_some_and(|outcomes| {
+        outcomes.into_iter().all(|(current, next)| {
+          sequential_next(group, &statetable.input_nodes, &inputs, current)
+            .is_some_and(|expected| expected.get(..nodes) == next.get(..nodes))
+        })
+      })
+    })
+}
+
+/// Bit mask of the concrete transitions, in the order of [`TRANSITIONS`]
+const fn transition_mask(transition: Transition) -> u8 {
+  match transition {
+    (Level::L, Level::L) => 0b0001,
+    (Level::H, Level::H) => 0b0010,
+    (Level::L, Level::H) => 0b0100,
+    (Level::H, Level::L) => 0b1000,
+  }
+}
+
+/// The mask of the transitions with the same `end` level
+const fn end_level_mask(level: Level) -> u8 {
+  match level {
+    Level::L => transition_mask(STABLE_L) | transition_mask(FALL),
+    Level::H => transition_mask(STABLE_H) | transition_mask(RISE),
+  }
+}
+
+const fn input_node_value(mask: u8) -> Option<InputNodeValue> {
+  Some(match mask {
+    0b1001 => InputNodeValue::L,
+    0b0110 => InputNodeValue::H,
+    0b0100 => InputNodeValue::R,
+    0b1000 => InputNodeValue::F,
+    0b1011 => InputNodeValue::NotR,
+    0b0111 => InputNodeValue::NotF,
+    0b1111 => InputNodeValue::DontCare,
+    _ => return None,
+  })
+}
+
+/// The mask of current level, `0b01` for `L` and `0b10` for `H`
+const fn level_mask(level: Level) -> u8 {
+  match level {
+    Level::L => 0b01,
+    Level::H => 0b10,
+  }
+}
+
+const fn current_node_value(mask: u8) -> CurrentInternalNodeValue {
+  match mask {
+    0b01 => CurrentInternalNodeValue::L,
+    0b10 => CurrentInternalNodeValue::H,
+    _ => CurrentInternalNodeValue::DontCare,
+  }
+}
+
+const fn next_node_value(value: Static) -> NextInternalNodeValue {
+  match value {
+    Static::L => NextInternalNodeValue::L,
+    Static::H => NextInternalNodeValue::H,
+    Static::X | Static::Z => NextInternalNodeValue::X,
+  }
+}
+
+/// A statetable row, with the mask of values
+#[derive(Debug, Clone)]
+struct MaskRow {
+  inputs: Vec<u8>,
+  current: Vec<u8>,
+  next: Vec<NextInternalNodeValue>,
+}
+
+impl MaskRow {
+  /// Merge two rows which only differ in one column
+  fn merge(&self, other: &Self) -> Option<Self> {
+    if self.next != other.next {
+      return None;
+    }
+    let columns = |a: &[u8], b: &[u8]| {
+      a.iter()
+        .zip(b)
+        .enumerate()
+        .filter(|(_, (x, y))| x != y)
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>()
+    };
+    let union = |a: &[u8], b: &[u8], i: usize| {
+      a.iter()
+        .zip(b)
+        .enumerate()
+        .map(|(j, (x, y))| if j == i { x | y } else { *x })
+        .collect()
+    };
+    match (
+      columns(&self.inputs, &other.inputs).as_slice(),
+      columns(&self.current, &other.current).as_slice(),
+    ) {
+      ([i], []) => Some(Self {
+        inputs: union(&self.inputs, &other.inputs, *i),
+        current: self.current.clone(),
+        next: self.next.clone(),
+      }),
+      ([], [i]) => Some(Self {
+        inputs: self.inputs.clone(),
+        current: union(&self.current, &other.current, *i),
+        next: self.next.clone(),
+      }),
+      _ => None,
+    }
+  }
+  fn into_values(self) -> Option<TableNodeValues> {
+    Some(TableNodeValues {
+      input_node_values: self
+        .inputs
+        .into_iter()
+        .map(input_node_value)
+        .collect::<Option<_>>()?,
+      current_next_internal_node_values: self
+        .current
+        .into_iter()
+        .map(current_node_value)
+        .zip(self.next)
+        .collect(),
+    })
+  }
+}
+
+/// Merge the rows until no rows can be merged
+fn merge_rows(mut rows: Vec<MaskRow>) -> Vec<MaskRow> {
+  while let Some((i, j, merged)) = rows.iter().enumerate().find_map(|(i, row)| {
+    rows
+      .iter()
+      .enumerate()
+      .skip(i)
+      .skip(1)
+      .find_map(|(j, other)| row.merge(other).map(|merged| (i, j, merged)))
+  }) {
+    _ = rows.remove(j);
+    if let Some(row) = rows.get_mut(i) {
+      *row = merged;
+    }
+  }
+  rows
+}
+
+/// The next value for all current values: constant, `N` (hold), or `None`
+fn compress_next(
+  outcomes: &[([Level; 2], [Static; 2])],
+  node: usize,
+) -> Option<NextInternalNodeValue> {
+  let nexts: Vec<(Static, Static)> = outcomes
+    .iter()
+    .filter_map(|(current, next)| {
+      current.get(node).map(|c| to_static(*c)).zip(next.get(node).copied())
+    })
+    .collect();
+  if nexts.iter().map(|(_, next)| next).all_equal() {
+    nexts.first().map(|(_, next)| next_node_value(*next))
+  } else if nexts.iter().all(|(current, next)| current == next) {
+    Some(NextInternalNodeValue::N)
+  } else {
+    None
+  }
+}
+
+/// Build the statetable of the sequential group, with internal nodes
+/// `[variable1, variable2]`, and verify it by exhaustive simulation.
+fn to_statetable<C: 'static + Ctx, S: Sequential + ?Sized>(
+  group: &S,
+) -> Option<Statetable<C>> {
+  let [variable1, variable2] = group.variables();
+  let functions = group.functions();
+  let support = |fs: &[Option<&LogicBooleanExpression>]| {
+    fs.iter()
+      .flatten()
+      .flat_map(|f| f.expr.support_set())
+      .filter(|node| node != variable1 && node != variable2)
+      .collect::<BTreeSet<_>>()
+  };
+  let input_nodes: Vec<String> = support(&functions).into_iter().collect();
+  let clock_nodes =
+    if group.is_latch() { BTreeSet::new() } else { support(&functions[2..4]) };
+  let domains: Vec<&[Transition]> = input_nodes
+    .iter()
+    .map(
+      |node| {
+        if clock_nodes.contains(node) { &TRANSITIONS[..] } else { &TRANSITIONS[..2] }
+      },
+    )
+    .collect();
+  let mut rows = Vec::new();
+  for inputs in domains
+    .iter()
+    .map(|domain| domain.iter().copied())
+    .multi_cartesian_product()
+  {
+    let masks: Vec<u8> = inputs
+      .iter()
+      .zip(&domains)
+      .map(|(transition, domain)| {
+        if domain.len() == TRANSITIONS.len() {
+          transition_mask(*transition)
+        } else {
+          end_level_mask(transition.1)
+        }
+      })
+      .collect();
+    let outcomes: Vec<([Level; 2], [Static; 2])> = LEVELS
+      .iter()
+      .cartesian_product(LEVELS)
+      .map(|(cur1, cur2)| {
+        let current = [*cur1, cur2];
+        sequential_next(group, &input_nodes, &inputs, current).map(|next| (current, next))
+      })
+      .collect::<Option<_>>()?;
+    if let Some(next) = (0..2).map(|node| compress_next(&outcomes, node)).collect() {
+      rows.push(MaskRow { inputs: masks, current: vec![0b11, 0b11], next });
+    } else {
+      rows.extend(outcomes.into_iter().map(|(current, next)| MaskRow {
+        inputs: masks.clone(),
+        current: current.into_iter().map(level_mask).collect(),
+        next: next.into_iter().map(next_node_value).collect(),
+      }));
+    }
+  }
+  let mut statetable = Statetable::<C>::default();
+  statetable.input_nodes = input_nodes;
+  statetable.internal_nodes = vec![variable1.to_owned(), variable2.to_owned()];
+  statetable.table = Table {
+    inner: merge_rows(rows)
+      .into_iter()
+      .map(MaskRow::into_values)
+      .collect::<Option<_>>()?,
+  };
+  simulate_eq(group, &statetable).then_some(statetable)
+}
+
+#[duplicate::duplicate_item(
+  Group_type;
+  [FF];
+  [FFBank];
+  [Latch];
+  [LatchBank];
+)]
+impl<C: 'static + Ctx> Group_type<C> {
+  /// Convert to the equivalent [`Statetable`], with the `input_nodes` from
+  /// the support of all the functions, and the `internal_nodes` `[variable1, variable2]`.
+  /// For `ff_bank` / `latch_bank`, the statetable describes one bit.
+  ///
+  /// The statetable is verified by exhaustive simulation, see [`Statetable::evaluate`].
+  /// Return `None` when the behavior can not be described by a statetable,
+  /// e.g., the `ff` triggered at both edges of one clock.
+  #[must_use]
+  #[inline]
+  pub fn to_statetable(&self) -> Option<Statetable<C>> {
+    to_statetable(self)
+  }
+}
+
+/// The `clear` / `preset` activity of a point
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AsyncClass {
+  Inactive,
+  Clear,
+  Preset,
+  Both([ClearPresetState; 2]),
+}
+
+impl AsyncClass {
+  fn new(outcomes: &[([Level; 2], [Static; 2])]) -> Option<Self> {
+    let state = |node: usize| {
+      let nexts: Vec<(Level, Static)> = outcomes
+        .iter()
+        .filter_map(|(current, next)| {
+          current.get(node).copied().zip(next.get(node).copied())
+        })
+        .collect();
+      [
+        (ClearPresetState::L, Static::L),
+        (ClearPresetState::H, Static::H),
+        (ClearPresetState::X, Static::X),
+      ]
+      .into_iter()
+      .find(|(_, value)| nexts.iter().all(|(_, next)| next == value))
+      .map(|(state, _)| state)
+      .or_else(|| {
+        if nexts.iter().all(|(current, next)| to_static(*current) == *next) {
+          Some(ClearPresetState::N)
+        } else if nexts
+          .iter()
+          .all(|(current, next)| to_static(current.inverse()) == *next)
+        {
+          Some(ClearPresetState::T)
+        } else {
+          None
+        }
+      })
+    };
+    Some(match [state(0)?, state(1)?] {
+      [ClearPresetState::N, ClearPresetState::N] => Self::Inactive,
+      [ClearPresetState::L, ClearPresetState::H] => Self::Clear,
+      [ClearPresetState::H, ClearPresetState::L] => Self::Preset,
+      states => Self::Both(states),
+    })
+  }
+}
+
+/// The conjunctive clause of the levels
+fn clause(variables: &BddVariableSet, values: &[(BddVariable, Level)]) -> Bdd {
+  variables.mk_conjunctive_clause(&BddPartialValuation::from_values(
+    &values
+      .iter()
+      .map(|(var, level)| (*var, bool::from(*level)))
+      .collect::<Vec<_>>(),
+  ))
+}
+
+/// Simplify `on` by the don't-care points outside `care`
+fn minimize(on: &Bdd, care: &Bdd) -> Bdd {
+  let target = on.and(care);
+  let mut support: Vec<BddVariable> = target.support_set().into_iter().collect();
+  support.sort_unstable();
+  support.into_iter().fold(target.clone(), |f, var| {
+    [f.var_exists(var), f.var_for_all(var)]
+      .into_iter()
+      .find(|g| g.and(care) == target)
+      .unwrap_or(f)
+  })
+}
+
+/// Sum of products of the BDD
+fn dnf_expr(bdd: &Bdd, variables: &BddVariableSet) -> Expr {
+  bdd
+    .to_optimized_dnf()
+    .iter()
+    .map(|clause| {
+      clause
+        .to_values()
+        .into_iter()
+        .map(|(var, value)| {
+          let literal = Expr::Variable(variables.name_of(var));
+          if value { literal } else { Expr::Not(Box::new(literal)) }
+        })
+        .reduce(|e1, e2| Expr::And(Box::new(e1), Box::new(e2)))
+        .unwrap_or(Expr::Const(true))
+    })
+    .reduce(|e1, e2| Expr::Or(Box::new(e1), Box::new(e2)))
+    .unwrap_or(Expr::Const(false))
+}
+
+/// The synthesized functions from statetable
+struct Synthesized {
+  variables: [String; 2],
+  clear: Option<Expr>,
+  preset: Option<Expr>,
+  clear_preset_var: Option<[ClearPresetState; 2]>,
+  active: Expr,
+  active_also: Option<Expr>,
+  next_state: Expr,
+}
+
+/// A literal of the input node, `(index, polarity)`
+type Literal = (usize, bool);
+
+impl<C: 'static + Ctx> Statetable<C> {
+  fn has_edge(&self, column: usize) -> bool {
+    self.table.inner.iter().any(|row| {
+      row.input_node_values.get(column).is_some_and(|value| {
+        matches!(
+          value,
+          InputNodeValue::R
+            | InputNodeValue::F
+            | InputNodeValue::NotR
+            | InputNodeValue::NotF
+        )
+      })
+    })
+  }
+  /// Synthesize the functions with the given `active` / `active_also` literals
+  #[expect(clippy::too_many_lines)]
+  fn synthesize(
+    &self,
+    latch: bool,
+    active: Literal,
+    active_also: Option<Literal>,
+    logic_variables: &BddVariableSet,
+  ) -> Option<Synthesized> {
+    let variable1 = self.internal_nodes.first()?.clone();
+    let variable2 = self
+      .internal_nodes
+      .get(1)
+      .cloned()
+      .unwrap_or_else(|| format!("{variable1}N"));
+    let input_vars: Vec<BddVariable> = self
+      .input_nodes
+      .iter()
+      .map(|node| logic_variables.var_by_name(node))
+      .collect::<Option<_>>()?;
+    let current_vars: Vec<BddVariable> = [&variable1, &variable2]
+      .into_iter()
+      .take(self.internal_nodes.len())
+      .filter_map(|node| logic_variables.var_by_name(node))
+      .collect();
+    let literal_value = |(i, polarity): Literal, levels: &[Level]| -> Option<bool> {
+      levels.get(i).map(|level| bool::from(*level) == polarity)
+    };
+    let point = |levels: &[Level]| -> Vec<(BddVariable, Level)> {
+      input_vars.iter().copied().zip(levels.iter().copied()).collect()
+    };
+    // the async points: stable inputs, and the latch is not enabled
+    let mut care = logic_variables.mk_false();
+    let mut clear_on = logic_variables.mk_false();
+    let mut preset_on = logic_variables.mk_false();
+    let mut clear_preset_var = None;
+    for levels in self.input_nodes.iter().map(|_| LEVELS).multi_cartesian_product() {
+      if latch
+        && (literal_value(active, &levels)?
+          || active_also.and_then(|also| literal_value(also, &levels)) == Some(true))
+      {
+        continue;
+      }
+      let inputs: Vec<Transition> = levels.iter().map(|level| (*level, *level)).collect();
+      let class = AsyncClass::new(&statetable_outcomes(self, &inputs)?)?;
+      let bdd = clause(logic_variables, &point(&levels));
+      care = care.or(&bdd);
+      match class {
+        AsyncClass::Inactive => {}
+        AsyncClass::Clear => clear_on = clear_on.or(&bdd),
+        AsyncClass::Preset => preset_on = preset_on.or(&bdd),
+        AsyncClass::Both(states) => {
+          if clear_preset_var.is_some_and(|s| s != states) {
+            return None;
+          }
+          clear_preset_var = Some(states);
+          clear_on = clear_on.or(&bdd);
+          preset_on = preset_on.or(&bdd);
+        }
+      }
+    }
+    let clear = minimize(&clear_on, &care);
+    let preset = minimize(&preset_on, &care);
+    // the sync points: the active edge / level without clear and preset
+    let mut next_care = logic_variables.mk_false();
+    let mut next_on = logic_variables.mk_false();
+    let domains: Vec<&[Transition]> = (0..self.input_nodes.len())
+      .map(
+        |i| if !latch && self.has_edge(i) { &TRANSITIONS[..] } else { &TRANSITIONS[..2] },
+      )
+      .collect();
+    for inputs in domains
+      .iter()
+      .map(|domain| domain.iter().copied())
+      .multi_cartesian_product()
+    {
+      let bgn: Vec<Level> = inputs.iter().map(|(level, _)| *level).collect();
+      let end: Vec<Level> = inputs.iter().map(|(_, level)| *level).collect();
+      let is_active = |literal: Literal| -> Option<bool> {
+        if latch {
+          literal_value(literal, &end)
+        } else {
+          Some(!literal_value(literal, &bgn)? && literal_value(literal, &end)?)
+        }
+      };
+      let also = match active_also {
+        Some(literal) => is_active(literal)?,
+        None => false,
+      };
+      let point_end = point(&end);
+      let point_bdd = clause(logic_variables, &point_end);
+      if is_active(active)? == also || !point_bdd.and(&clear.or(&preset)).is_false() {
+        continue;
+      }
+      next_care = next_care.or(&point_bdd);
+      for (current, next) in statetable_outcomes(self, &inputs)? {
+        let bdd = clause(
+          logic_variables,
+          &point_end
+            .iter()
+            .copied()
+            .chain(current_vars.iter().copied().zip(current))
+            .collect::<Vec<_>>(),
+        );
+        match next.first()? {
+          Static::H => next_on = next_on.or(&bdd),
+          Static::L => {}
+          Static::X | Static::Z => return None,
+        }
+      }
+    }
+    let next_state = minimize(&next_on, &next_care);
+    let literal_expr = |(i, polarity): Literal| -> Option<Expr> {
+      let var = Expr::Variable(self.input_nodes.get(i)?.clone());
+      Some(if polarity { var } else { Expr::Not(Box::new(var)) })
+    };
+    Some(Synthesized {
+      variables: [variable1, variable2],
+      clear: (!clear.is_false()).then(|| dnf_expr(&clear, logic_variables)),
+      preset: (!preset.is_false()).then(|| dnf_expr(&preset, logic_variables)),
+      clear_preset_var,
+      active: literal_expr(active)?,
+      active_also: active_also.and_then(literal_expr),
+      next_state: dnf_expr(&next_state, logic_variables),
+    })
+  }
+  /// The candidates of `(active, active_also)` literals
+  fn candidates(&self, latch: bool) -> Vec<(Literal, Option<Literal>)> {
+    let columns: Vec<usize> = (0..self.input_nodes.len())
+      .filter(|i| latch != self.has_edge(*i))
+      .collect();
+    let literals: Vec<Literal> =
+      columns.iter().flat_map(|i| [(*i, true), (*i, false)]).collect();
+    literals
+      .iter()
+      .map(|active| (*active, None))
+      .chain(
+        literals
+          .iter()
+          .cartesian_product(&literals)
+          .filter(|((i, _), (j, _))| !latch && i != j)
+          .map(|(active, also)| (*active, Some(*also))),
+      )
+      .collect()
+  }
+  /// Recognize the statetable of a standard flip-flop, and synthesize the equivalent [`FF`].
+  ///
+  /// The statetable should have one or two internal nodes, which are `variable1`
+  /// and `variable2` (`"{variable1}N"` by default), and the `clocked_on` / `clocked_on_also`
+  /// are the inputs with edge values. The `logic_variables` (usually from the cell)
+  /// should contain all the nodes.
+  ///
+  /// Return `None` when it is not a flip-flop, or the synthesized `ff` is not
+  /// equivalent in the exhaustive simulation.
+  #[must_use]
+  #[inline]
+  pub fn to_ff(&self, logic_variables: &BddVariableSet) -> Option<FF<C>> {
+    self.candidates(false).into_iter().find_map(|(active, active_also)| {
+      let synthesized = self.synthesize(false, active, active_also, logic_variables)?;
+      let new = |expr: Expr| LogicBooleanExpression::new(expr, logic_variables);
+      let [variable1, variable2] = synthesized.variables;
+      let mut ff = FF::<C>::default();
+      ff.variable1 = variable1;
+      ff.variable2 = variable2;
+      ff.clear = synthesized.clear.map(new);
+      ff.preset = synthesized.preset.map(new);
+      ff.clear_preset_var1 = synthesized.clear_preset_var.map(|[var1, _]| var1);
+      ff.clear_preset_var2 = synthesized.clear_preset_var.map(|[_, var2]| var2);
+      ff.clocked_on = Some(new(synthesized.active));
+      ff.clocked_on_also = synthesized.active_also.map(new);
+      ff.next_state = Some(new(synthesized.next_state));
+      simulate_eq(&ff, self).then_some(ff)
+    })
+  }
+  /// Recognize the statetable of a standard latch, and synthesize the equivalent [`Latch`].
+  ///
+  /// The statetable should have one or two internal nodes, which are `variable1`
+  /// and `variable2` (`"{variable1}N"` by default), and no edge values.
+  /// The `logic_variables` (usually from the cell) should contain all the nodes.
+  ///
+  /// Return `None` when it is not a latch, or the synthesized `latch` is not
+  /// equivalent in the exhaustive simulation.
+  #[must_use]
+  #[inline]
+  pub fn to_latch(&self, logic_variables: &BddVariableSet) -> Option<Latch<C>> {
+    if (0..self.input_nodes.len()).any(|i| self.has_edge(i)) {
+      return None;
+    }
+    self.candidates(true).into_iter().find_map(|(active, active_also)| {
+      let synthesized = self.synthesize(true, active, active_also, logic_variables)?;
+      let new = |expr: Expr| LogicBooleanExpression::new(expr, logic_variables);
+      let [variable1, variable2] = synthesized.variables;
+      let mut latch = Latch::<C>::default();
+      latch.variable1 = variable1;
+      latch.variable2 = variable2;
+      latch.clear = synthesized.clear.map(new);
+      latch.preset = synthesized.preset.map(new);
+      latch.clear_preset_var1 = synthesized.clear_preset_var.map(|[var1, _]| var1);
+      latch.clear_preset_var2 = synthesized.clear_preset_var.map(|[_, var2]| var2);
+      latch.enable = Some(new(synthesized.active));
+      latch.data_in = Some(new(synthesized.next_state));
+      simulate_eq(&latch, self).then_some(latch)
+    })
+  }
+}
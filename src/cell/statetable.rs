@@ -0,0 +1,318 @@
+//! Simulation of [`Statetable`].
+use super::{
+  CurrentInternalNodeValue, InputNodeValue, NextInternalNodeValue, Statetable,
+  TableNodeValues,
+};
+use crate::{
+  Ctx,
+  expression::logic::{Level, State, Static},
+};
+use itertools::Itertools as _;
+
+/// The error of [`Statetable::evaluate`]
+#[derive(Clone, Copy, Debug, thiserror::Error, PartialEq, Eq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub enum StatetableError {
+  /// The number of input node values mismatch
+  #[error("expect {expected} input node values, found {found}")]
+  InputLength {
+    /// The number of `input_nodes`
+    expected: usize,
+    /// The number of given values
+    found: usize,
+  },
+  /// The number of current internal node values mismatch
+  #[error("expect {expected} internal node values, found {found}")]
+  InternalLength {
+    /// The number of `internal_nodes`
+    expected: usize,
+    /// The number of given values
+    found: usize,
+  },
+  /// The number of values in the table row mismatch with the nodes
+  #[error("table row {0} mismatch with the nodes")]
+  RowLength(usize),
+  /// Two matched table rows give different next values
+  #[error("table rows {0} and {1} conflict")]
+  Conflict(usize, usize),
+  /// None of the table rows matches
+  #[error("no table row matches")]
+  Incomplete,
+}
+
+/// A conflicting or missing case found by [`Statetable::check`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct StatetableIssue {
+  /// The input node values
+  pub inputs: Vec<State>,
+  /// The current internal node values
+  pub current: Vec<Static>,
+  /// What is wrong
+  pub error: StatetableError,
+}
+
+/// `(bgn, end)` levels of a concrete input
+pub(super) type Transition = (Level, Level);
+
+/// All the possible levels, `X` and `Z` expand to both `L` and `H`
+const fn levels(value: Static) -> &'static [Level] {
+  match value {
+    Static::L => &[Level::L],
+    Static::H => &[Level::H],
+    Static::X | Static::Z => &[Level::L, Level::H],
+  }
+}
+
+pub(super) const fn to_static(level: Level) -> Static {
+  match level {
+    Level::L => Static::L,
+    Level::H => Static::H,
+  }
+}
+
+const fn level_eq(a: Level, b: Level) -> bool {
+  matches!((a, b), (Level::L, Level::L) | (Level::H, Level::H))
+}
+
+/// `branch` selects the expanded value of `L/H` and `H/L`,
+/// `false` for the first one and `true` for the second one
+const fn expand(level_h: bool, branch: bool) -> Level {
+  if level_h == branch { Level::L } else { Level::H }
+}
+
+/// Level values match the present (end) level,
+/// edge values match the transition.
+const fn input_match(
+  value: InputNodeValue,
+  (bgn, end): Transition,
+  branch: bool,
+) -> bool {
+  let rise = matches!((bgn, end), (Level::L, Level::H));
+  let fall = matches!((bgn, end), (Level::H, Level::L));
+  match value {
+    InputNodeValue::L => matches!(end, Level::L),
+    InputNodeValue::H => matches!(end, Level::H),
+    InputNodeValue::DontCare => true,
+    InputNodeValue::LH => level_eq(expand(false, branch), end),
+    InputNodeValue::HL => level_eq(expand(true, branch), end),
+    InputNodeValue::R => rise,
+    InputNodeValue::F => fall,
+    InputNodeValue::NotR => !rise,
+    InputNodeValue::NotF => !fall,
+  }
+}
+
+const fn current_match(
+  value: CurrentInternalNodeValue,
+  level: Level,
+  branch: bool,
+) -> bool {
+  let expected = match value {
+    CurrentInternalNodeValue::L => Level::L,
+    CurrentInternalNodeValue::H => Level::H,
+    CurrentInternalNodeValue::DontCare => return true,
+    CurrentInternalNodeValue::LH => expand(false, branch),
+    CurrentInternalNodeValue::HL => expand(true, branch),
+  };
+  level_eq(expected, level)
+}
+
+const fn next_value(
+  value: NextInternalNodeValue,
+  current: Level,
+  branch: bool,
+) -> Static {
+  match value {
+    NextInternalNodeValue::L => Static::L,
+    NextInternalNodeValue::H => Static::H,
+    NextInternalNodeValue::NotSpecified | NextInternalNodeValue::X => Static::X,
+    NextInternalNodeValue::LH => to_static(expand(false, branch)),
+    NextInternalNodeValue::HL => to_static(expand(true, branch)),
+    NextInternalNodeValue::N => to_static(current),
+  }
+}
+
+/// Whether the row contains `L/H` or `H/L`, which expands to two rows
+fn has_branch(row: &TableNodeValues) -> bool {
+  row
+    .input_node_values
+    .iter()
+    .any(|v| matches!(v, InputNodeValue::LH | InputNodeValue::HL))
+    || row.current_next_internal_node_values.iter().any(|(current, next)| {
+      matches!(current, CurrentInternalNodeValue::LH | CurrentInternalNodeValue::HL)
+        || matches!(next, NextInternalNodeValue::LH | NextInternalNodeValue::HL)
+    })
+}
+
+/// The next values of all matched expansions of the row
+fn row_next(
+  row: &TableNodeValues,
+  inputs: &[Transition],
+  current: &[Level],
+) -> Vec<Vec<Static>> {
+  let branches: &[bool] = if has_branch(row) { &[false, true] } else { &[false] };
+  branches
+    .iter()
+    .filter(|branch| {
+      row
+        .input_node_values
+        .iter()
+        .zip(inputs)
+        .all(|(value, transition)| input_match(*value, *transition, **branch))
+        && row
+          .current_next_internal_node_values
+          .iter()
+          .zip(current)
+          .all(|((value, _), level)| current_match(*value, *level, **branch))
+    })
+    .map(|branch| {
+      row
+        .current_next_internal_node_values
+        .iter()
+        .zip(current)
+        .map(|((_, value), level)| next_value(*value, *level, *branch))
+        .collect()
+    })
+    .collect()
+}
+
+impl<C: 'static + Ctx> Statetable<C> {
+  /// The index of rows whose number of values mismatch with the nodes
+  fn mismatched_rows(&self) -> impl Iterator<Item = usize> {
+    self
+      .table
+      .inner
+      .iter()
+      .enumerate()
+      .filter(|(_, row)| {
+        row.input_node_values.len() != self.input_nodes.len()
+          || row.current_next_internal_node_values.len() != self.internal_nodes.len()
+      })
+      .map(|(i, _)| i)
+  }
+  pub(super) fn evaluate_concrete(
+    &self,
+    inputs: &[Transition],
+    current: &[Level],
+  ) -> Result<Vec<Static>, StatetableError> {
+    let mut next: Option<(usize, Vec<Static>)> = None;
+    for (i, row) in self.table.inner.iter().enumerate() {
+      for row_next in row_next(row, inputs, current) {
+        match &next {
+          None => next = Some((i, row_next)),
+          Some((first, first_next)) => {
+            if *first_next != row_next {
+              return Err(StatetableError::Conflict(*first, i));
+            }
+          }
+        }
+      }
+    }
+    next.map(|(_, values)| values).ok_or(StatetableError::Incomplete)
+  }
+  /// Evaluate the next internal node values, with the `inputs` values
+  /// in the order of `input_nodes`, and the `current` values in the order of `internal_nodes`.
+  ///
+  /// + The level values (`L`, `H`, `L/H`, `H/L`) match the present level of input,
+  ///   and the edge values (`R`, `F`, `~R`, `~F`) match the transition, e.g., [`State::LH`] is rising.
+  /// + `N` keeps the current value, and `X` / `-` in the next values give [`Static::X`].
+  /// + The `X` and `Z` values (of inputs or current) are evaluated as both `L` and `H`,
+  ///   the next value is [`Static::X`] when the results differ.
+  ///
+  /// # Errors
+  /// Return [`StatetableError`] when the number of values mismatch,
+  /// or when any matched rows conflict, or no row matches
+  #[inline]
+  pub fn evaluate(
+    &self,
+    inputs: &[State],
+    current: &[Static],
+  ) -> Result<Vec<Static>, StatetableError> {
+    if inputs.len() != self.input_nodes.len() {
+      return Err(StatetableError::InputLength {
+        expected: self.input_nodes.len(),
+        found: inputs.len(),
+      });
+    }
+    if current.len() != self.internal_nodes.len() {
+      return Err(StatetableError::InternalLength {
+        expected: self.internal_nodes.len(),
+        found: current.len(),
+      });
+    }
+    if let Some(i) = self.mismatched_rows().next() {
+      return Err(StatetableError::RowLength(i));
+    }
+    let input_cases = inputs
+      .iter()
+      .map(|state| {
+        levels(state.bgn())
+          .iter()
+          .cartesian_product(levels(state.end()))
+          .map(|(bgn, end)| (*bgn, *end))
+          .collect::<Vec<_>>()
+      })
+      .multi_cartesian_product();
+    let current_cases: Vec<Vec<Level>> = current
+      .iter()
+      .map(|value| levels(*value).iter().copied())
+      .multi_cartesian_product()
+      .collect();
+    let mut next: Option<Vec<Static>> = None;
+    for (case_inputs, case_current) in input_cases.cartesian_product(current_cases) {
+      let case_next = self.evaluate_concrete(&case_inputs, &case_current)?;
+      next = Some(match next {
+        None => case_next,
+        Some(values) => values
+          .into_iter()
+          .zip(case_next)
+          .map(|(v1, v2)| if v1 == v2 { v1 } else { Static::X })
+          .collect(),
+      });
+    }
+    Ok(next.unwrap_or_default())
+  }
+  /// Exhaustively check the table with all the inputs values `L`, `H`, `R` and `F`,
+  /// and all the current values `L` and `H`. Return all the conflicting or missing cases.
+  ///
+  /// When the number of values in any row mismatches with the nodes,
+  /// only the [`StatetableError::RowLength`] issues are returned.
+  #[must_use]
+  #[inline]
+  pub fn check(&self) -> Vec<StatetableIssue> {
+    let row_length_issues: Vec<_> = self
+      .mismatched_rows()
+      .map(|i| StatetableIssue {
+        inputs: Vec::new(),
+        current: Vec::new(),
+        error: StatetableError::RowLength(i),
+      })
+      .collect();
+    if !row_length_issues.is_empty() {
+      return row_length_issues;
+    }
+    let input_states = [State::L, State::H, State::LH, State::HL];
+    let current_states = [Static::L, Static::H];
+    self
+      .input_nodes
+      .iter()
+      .map(|_| input_states.iter().copied())
+      .multi_cartesian_product()
+      .cartesian_product(
+        self
+          .internal_nodes
+          .iter()
+          .map(|_| current_states.iter().copied())
+          .multi_cartesian_product(),
+      )
+      .filter_map(|(inputs, current)| {
+        self.evaluate(&inputs, &current).err().map(|error| StatetableIssue {
+          inputs,
+          current,
+          error,
+        })
+      })
+      .collect()
+  }
+}
@@ -0,0 +1,264 @@
+//! Swappable-pin analysis, based on the `function` BDDs of a cell.
+#![allow(clippy::multiple_inherent_impl)]
+use super::{Cell, CellCtx as _};
+use crate::{
+  Ctx,
+  expression::{Bdd, LogicBooleanExpression},
+};
+use biodivine_lib_bdd::BddVariable;
+
+/// The result of [`Cell::pin_symmetry`].
+#[derive(Debug, Clone, Default)]
+#[derive(PartialEq, Eq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct PinSymmetry {
+  /// Groups of functionally symmetric input pins.
+  ///
+  /// The pins in the same group can be swapped with each other
+  /// without changing any logic function of the cell.
+  /// Each group contains at least two pins, and is sorted by name.
+  pub equal: Vec<Vec<String>>,
+  /// Pairs of input pins that can be swapped when both of them are inverted,
+  /// that is `f(.., a, .., b, ..) == f(.., !b, .., !a, ..)`.
+  pub opposite: Vec<(String, String)>,
+  /// The declared `pin_equal` / `pin_opposite` relations that
+  /// disagree with the logic functions.
+  pub mismatch: Vec<PinSymmetryMismatch>,
+}
+
+impl PinSymmetry {
+  /// Whether the declared `pin_equal` / `pin_opposite` agree with the logic functions
+  #[must_use]
+  #[inline]
+  pub const fn is_consistent(&self) -> bool {
+    self.mismatch.is_empty()
+  }
+}
+
+/// A declared `pin_equal` / `pin_opposite` relation that disagree with the logic functions.
+#[derive(Debug, Clone)]
+#[derive(PartialEq, Eq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub enum PinSymmetryMismatch {
+  /// The pin is declared but can not be found in the cell
+  UnknownPin(String),
+  /// The two pins are declared equal, but they are not
+  NotEqual(String, String),
+  /// The two pins are declared opposite, but they are not
+  NotOpposite(String, String),
+}
+
+/// `f(a=0, b=1) == f(a=1, b=0)`
+#[inline]
+fn is_symmetric(bdds: &[&Bdd], a: BddVariable, b: BddVariable) -> bool {
+  bdds
+    .iter()
+    .all(|f| f.restrict(&[(a, false), (b, true)]) == f.restrict(&[(a, true), (b, false)]))
+}
+
+/// `f(a=0, b=0) == f(a=1, b=1)`
+#[inline]
+fn is_skew_symmetric(bdds: &[&Bdd], a: BddVariable, b: BddVariable) -> bool {
+  bdds
+    .iter()
+    .all(|f| f.restrict(&[(a, false), (b, false)]) == f.restrict(&[(a, true), (b, true)]))
+}
+
+impl<C: 'static + Ctx> Cell<C> {
+  /// All the logic functions of this cell, including
+  /// pin `function` / `three_state`, and the `ff` / `latch` (bank) groups.
+  #[inline]
+  #[must_use]
+  pub fn logic_functions(&self) -> Vec<&LogicBooleanExpression> {
+    self
+      .pin
+      .iter()
+      .flat_map(|pin| [pin.function.as_ref(), pin.three_state.as_ref()])
+      .chain(self.ff.iter().flat_map(|ff| {
+        [
+          ff.clear.as_ref(),
+          ff.preset.as_ref(),
+          ff.clocked_on.as_ref(),
+          ff.clocked_on_also.as_ref(),
+          ff.next_state.as_ref(),
+        ]
+      }))
+      .chain(self.ff_bank.iter().flat_map(|ff| {
+        [
+          ff.clear.as_ref(),
+          ff.preset.as_ref(),
+          ff.clocked_on.as_ref(),
+          ff.clocked_on_also.as_ref(),
+          ff.next_state.as_ref(),
+        ]
+      }))
+      .chain(self.latch.iter().flat_map(|latch| {
+        [
+          latch.clear.as_ref(),
+          latch.preset.as_ref(),
+          latch.enable.as_ref(),
+          latch.enable_also.as_ref(),
+          latch.data_in.as_ref(),
+        ]
+      }))
+      .chain(self.latch_bank.iter().flat_map(|latch| {
+        [
+          latch.clear.as_ref(),
+          latch.preset.as_ref(),
+          latch.enable.as_ref(),
+          latch.enable_also.as_ref(),
+          latch.data_in.as_ref(),
+        ]
+      }))
+      .flatten()
+      .collect()
+  }
+  /// Compute the groups of functionally symmetric (swappable) input pins
+  /// from the logic functions, and check them with the declared
+  /// [`pin_equal`](Cell::pin_equal) and [`pin_opposite`](Cell::pin_opposite).
+  ///
+  /// A pin with `function` is treated as output, and the declared relation is checked
+  /// by comparing the functions. Otherwise the pin is treated as input, and the
+  /// declared relation is checked by swapping the two pins in all logic functions.
+  #[must_use]
+  #[inline]
+  pub fn pin_symmetry(&self) -> PinSymmetry {
+    let variables = self.extra_ctx.logic_variables();
+    let functions = self.logic_functions();
+    let bdds: Vec<&Bdd> = functions.iter().map(|f| &f.bdd).collect();
+    let mut inputs: Vec<(&str, BddVariable)> = self
+      .pin
+      .iter()
+      .filter(|pin| pin.function.is_none())
+      .filter_map(|pin| {
+        variables
+          .var_by_name(&pin.name)
+          .filter(|var| bdds.iter().any(|bdd| bdd.support_set_contains(var)))
+          .map(|var| (pin.name.as_str(), var))
+      })
+      .collect();
+    inputs.sort_unstable_by_key(|(name, _)| *name);
+    let mut groups: Vec<Vec<(&str, BddVariable)>> = Vec::new();
+    for (name, var) in &inputs {
+      if let Some(group) = groups.iter_mut().find(|group| {
+        group
+          .first()
+          .is_some_and(|(_, first)| is_symmetric(&bdds, *first, *var))
+      }) {
+        group.push((name, *var));
+      } else {
+        groups.push(vec![(name, *var)]);
+      }
+    }
+    let opposite = inputs
+      .iter()
+      .enumerate()
+      .flat_map(|(i, (name1, var1))| {
+        inputs
+          .iter()
+          .skip(i)
+          .skip(1)
+          .filter(|(_, var2)| is_skew_symmetric(&bdds, *var1, *var2))
+          .map(|(name2, _)| ((*name1).to_owned(), (*name2).to_owned()))
+      })
+      .collect();
+    PinSymmetry {
+      equal: groups
+        .into_iter()
+        .filter(|group| group.len() >= 2)
+        .map(|group| group.into_iter().map(|(name, _)| name.to_owned()).collect())
+        .collect(),
+      opposite,
+      mismatch: self.declared_pin_mismatch(&bdds),
+    }
+  }
+  fn declared_pin_mismatch(&self, bdds: &[&Bdd]) -> Vec<PinSymmetryMismatch> {
+    /// Either the output function, or the input variable
+    #[expect(variant_size_differences)]
+    enum Node<'a> {
+      Output(&'a Bdd),
+      Input(BddVariable),
+    }
+    let variables = self.extra_ctx.logic_variables();
+    let mut mismatch = Vec::new();
+    let mut find_node = |name: &str| {
+      let node = self.pin.get(name).and_then(|pin| {
+        pin.function.as_ref().map_or_else(
+          || variables.var_by_name(name).map(Node::Input),
+          |function| Some(Node::Output(&function.bdd)),
+        )
+      });
+      if node.is_none() {
+        mismatch.push(PinSymmetryMismatch::UnknownPin(name.to_owned()));
+      }
+      node
+    };
+    let equal_lists: Vec<Vec<(&str, Node<'_>)>> = self
+      .pin_equal
+      .iter()
+      .map(|pin_equal| {
+        pin_equal
+          .name_list
+          .inner
+          .iter()
+          .filter_map(|name| find_node(name).map(|node| (name.as_str(), node)))
+          .collect()
+      })
+      .collect();
+    let opposite_lists = self.pin_opposite.as_ref().map(|pin_opposite| {
+      let list1: Vec<_> = pin_opposite
+        .name_list1
+        .inner
+        .iter()
+        .filter_map(|name| find_node(name).map(|node| (name.as_str(), node)))
+        .collect();
+      let list2: Vec<_> = pin_opposite
+        .name_list2
+        .inner
+        .iter()
+        .filter_map(|name| find_node(name).map(|node| (name.as_str(), node)))
+        .collect();
+      (list1, list2)
+    });
+    let is_equal = |node1: &Node<'_>, node2: &Node<'_>| match (node1, node2) {
+      (Node::Output(f1), Node::Output(f2)) => f1 == f2,
+      (Node::Input(v1), Node::Input(v2)) => is_symmetric(bdds, *v1, *v2),
+      _ => false,
+    };
+    let is_opposite = |node1: &Node<'_>, node2: &Node<'_>| match (node1, node2) {
+      (Node::Output(f1), Node::Output(f2)) => f1.not() == **f2,
+      (Node::Input(v1), Node::Input(v2)) => is_skew_symmetric(bdds, *v1, *v2),
+      _ => false,
+    };
+    let check_equal = |list: &[(&str, Node<'_>)], res: &mut Vec<_>| {
+      if let Some((first_name, first_node)) = list.first() {
+        for (name, node) in list.iter().skip(1) {
+          if !is_equal(first_node, node) {
+            res.push(PinSymmetryMismatch::NotEqual(
+              (*first_name).to_owned(),
+              (*name).to_owned(),
+            ));
+          }
+        }
+      }
+    };
+    for list in &equal_lists {
+      check_equal(list, &mut mismatch);
+    }
+    if let Some((list1, list2)) = &opposite_lists {
+      check_equal(list1, &mut mismatch);
+      check_equal(list2, &mut mismatch);
+      for (name1, node1) in list1 {
+        for (name2, node2) in list2 {
+          if !is_opposite(node1, node2) {
+            mismatch.push(PinSymmetryMismatch::NotOpposite(
+              (*name1).to_owned(),
+              (*name2).to_owned(),
+            ));
+          }
+        }
+      }
+    }
+    mismatch
+  }
+}
@@ -868,3 +868,689 @@ liberty_db::cell::Model (cellA) {
 }"#,
   );
 }
+
+#[test]
+fn pin_symmetry() {
+  use super::PinSymmetryMismatch;
+  let cell = crate::ast::test_parse_fmt::<Cell<DefaultCtx>>(
+    r#"(AOI22) {
+    pin_equal ("A2 A1");
+    pin_equal ("B1 A1");
+    pin_opposite ("ZN", "Z");
+    pin(A1) { direction : input; }
+    pin(A2) { direction : input; }
+    pin(B1) { direction : input; }
+    pin(B2) { direction : input; }
+    pin(ZN) {
+        direction : output;
+        function : "!(A1&A2 | B1&B2)";
+    }
+    pin(Z) {
+        direction : output;
+        function : "A1&A2 | B1&B2";
+    }
+}"#,
+    r#"
+liberty_db::cell::Cell (AOI22) {
+| pin_equal ("A1 A2");
+| pin_equal ("A1 B1");
+| pin_opposite (ZN, Z);
+| pin (A1) {
+| | direction : input;
+| }
+| pin (A2) {
+| | direction : input;
+| }
+| pin (B1) {
+| | direction : input;
+| }
+| pin (B2) {
+| | direction : input;
+| }
+| pin (Z) {
+| | direction : output;
+| | function : "A1*A2+B1*B2";
+| }
+| pin (ZN) {
+| | direction : output;
+| | function : "!(A1*A2+B1*B2)";
+| }
+}"#,
+  );
+  let symmetry = cell.pin_symmetry();
+  assert_eq!(
+    symmetry.equal,
+    vec![vec!["A1".to_owned(), "A2".to_owned()], vec!["B1".to_owned(), "B2".to_owned()]]
+  );
+  assert!(symmetry.opposite.is_empty());
+  assert_eq!(
+    symmetry.mismatch,
+    vec![PinSymmetryMismatch::NotEqual("A1".to_owned(), "B1".to_owned())]
+  );
+  let cell = crate::ast::test_parse_fmt::<Cell<DefaultCtx>>(
+    r#"(XNOR2) {
+    pin_opposite ("A", "B");
+    pin(A) { direction : input; }
+    pin(B) { direction : input; }
+    pin(ZN) {
+        direction : output;
+        function : "!(A^B)";
+    }
+}"#,
+    r#"
+liberty_db::cell::Cell (XNOR2) {
+| pin_opposite (A, B);
+| pin (A) {
+| | direction : input;
+| }
+| pin (B) {
+| | direction : input;
+| }
+| pin (ZN) {
+| | direction : output;
+| | function : "!(A^B)";
+| }
+}"#,
+  );
+  let symmetry = cell.pin_symmetry();
+  assert_eq!(symmetry.equal, vec![vec!["A".to_owned(), "B".to_owned()]]);
+  assert_eq!(symmetry.opposite, vec![("A".to_owned(), "B".to_owned())]);
+  assert!(symmetry.is_consistent());
+}
+
+#[test]
+fn classify() {
+  use super::{FunctionClass, MappedPin};
+  use crate::Library;
+  let library = Library::<DefaultCtx>::parse_lib(
+    r#"library (classify) {
+  cell (FILL) {}
+  cell (TIE) {
+    pin (HI) { direction : output; function : "1"; }
+    pin (LO) { direction : output; function : "0"; }
+  }
+  cell (NR2) {
+    pin (A1) { direction : input; }
+    pin (A2) { direction : input; }
+    pin (ZN) { direction : output; function : "!(A1+A2)"; }
+  }
+  cell (AN2B) {
+    pin (A) { direction : input; }
+    pin (B) { direction : input; }
+    pin (Z) { direction : output; function : "!A&B"; }
+  }
+  cell (MX2) {
+    pin (I0) { direction : input; }
+    pin (I1) { direction : input; }
+    pin (S) { direction : input; }
+    pin (Z) { direction : output; function : "I0&!S | I1&S"; }
+  }
+  cell (FA) {
+    pin (A) { direction : input; }
+    pin (B) { direction : input; }
+    pin (CI) { direction : input; }
+    pin (CO) { direction : output; function : "A&B | CI&(A+B)"; }
+    pin (S) { direction : output; function : "A^B^CI"; }
+  }
+  cell (HA) {
+    pin (A) { direction : input; }
+    pin (B) { direction : input; }
+    pin (CO) { direction : output; function : "A&B"; }
+    pin (S) { direction : output; function : "A^B"; }
+  }
+  cell (HAB) {
+    pin (A) { direction : input; }
+    pin (B) { direction : input; }
+    pin (CO) { direction : output; function : "!A&B"; }
+    pin (S) { direction : output; function : "A^B"; }
+  }
+  cell (DFFR) {
+    ff (IQ, IQN) {
+      next_state : "D";
+      clocked_on : "CK";
+      clear : "!RN";
+    }
+    pin (D) { direction : input; }
+    pin (CK) { direction : input; clock : true; }
+    pin (RN) { direction : input; }
+    pin (Q) { direction : output; function : "IQ"; }
+  }
+}"#,
+    None,
+  )
+  .unwrap();
+  let class = |name: &str| library.cell.get(name).unwrap().classify();
+  assert_eq!(class("FILL").class, FunctionClass::Filler);
+  assert_eq!(class("TIE").class, FunctionClass::TieHighLow);
+  let nr2 = class("NR2");
+  assert_eq!(nr2.class, FunctionClass::Nor(2));
+  assert!(!nr2.outputs[0].output_inverted);
+  let an2b = class("AN2B");
+  assert_eq!(an2b.class, FunctionClass::And(2));
+  assert_eq!(
+    an2b.outputs[0].inputs,
+    vec![
+      MappedPin { pin: "A".to_owned(), inverted: true },
+      MappedPin { pin: "B".to_owned(), inverted: false }
+    ]
+  );
+  let mx2 = class("MX2");
+  assert_eq!(mx2.class, FunctionClass::Mux2);
+  assert_eq!(
+    mx2.outputs[0]
+      .inputs
+      .iter()
+      .map(|p| p.pin.as_str())
+      .collect::<Vec<_>>(),
+    vec!["I0", "I1", "S"]
+  );
+  assert_eq!(class("FA").class, FunctionClass::FullAdder);
+  assert_eq!(class("HA").class, FunctionClass::HalfAdder);
+  // the carry with an inverted input
+  assert_eq!(class("HAB").class, FunctionClass::Unknown);
+  let dffr = class("DFFR");
+  assert_eq!(dffr.class, FunctionClass::FlipFlop { clear: true, preset: false });
+  assert_eq!(dffr.class.to_string(), "DFFR");
+  assert_eq!(dffr.outputs[0].class, FunctionClass::Buf);
+}
+
+#[test]
+fn statetable_evaluate() {
+  use super::{Statetable, StatetableError};
+  use crate::expression::logic::{State, Static};
+  let dff = crate::ast::test_parse::<Statetable<DefaultCtx>>(
+    r#"("D CP CDN", IQ) {
+      table : "- -  L : - : L ,\
+               H R  H : - : H ,\
+               L R  H : - : L ,\
+               - ~R H : - : N ";
+    }"#,
+  );
+  let eval = |inputs: [State; 3], current: Static| dff.evaluate(&inputs, &[current]);
+  assert_eq!(eval([State::H, State::LH, State::H], Static::L), Ok(vec![Static::H]));
+  assert_eq!(eval([State::L, State::H, State::H], Static::H), Ok(vec![Static::H]));
+  assert_eq!(eval([State::H, State::HL, State::HL], Static::H), Ok(vec![Static::L]));
+  // the present level of `CDN` is H
+  assert_eq!(eval([State::H, State::LH, State::LH], Static::L), Ok(vec![Static::H]));
+  assert_eq!(eval([State::X, State::LH, State::H], Static::L), Ok(vec![Static::X]));
+  assert_eq!(eval([State::H, State::X, State::H], Static::L), Ok(vec![Static::X]));
+  assert_eq!(eval([State::H, State::X, State::H], Static::H), Ok(vec![Static::H]));
+  assert_eq!(eval([State::L, State::H, State::H], Static::Z), Ok(vec![Static::X]));
+  assert_eq!(
+    dff.evaluate(&[State::H], &[Static::L]),
+    Err(StatetableError::InputLength { expected: 3, found: 1 })
+  );
+  assert!(dff.check().is_empty());
+
+  let latch = crate::ast::test_parse::<Statetable<DefaultCtx>>(
+    r#"("D G", IQ) {
+      table : "L/H H : - : L/H ,\
+               -   L : - : N ";
+    }"#,
+  );
+  assert_eq!(latch.evaluate(&[State::H, State::H], &[Static::L]), Ok(vec![Static::H]));
+  assert_eq!(latch.evaluate(&[State::L, State::LH], &[Static::H]), Ok(vec![Static::L]));
+  assert_eq!(latch.evaluate(&[State::L, State::L], &[Static::H]), Ok(vec![Static::H]));
+  assert!(latch.check().is_empty());
+
+  let broken = crate::ast::test_parse::<Statetable<DefaultCtx>>(
+    r#"("D CP", IQ) {
+      table : "H R : - : H ,\
+               - R : L : L ,\
+               L R : - : L ";
+    }"#,
+  );
+  assert_eq!(
+    broken.evaluate(&[State::H, State::LH], &[Static::L]),
+    Err(StatetableError::Conflict(0, 1))
+  );
+  assert_eq!(
+    broken.evaluate(&[State::H, State::H], &[Static::L]),
+    Err(StatetableError::Incomplete)
+  );
+  let issues = broken.check();
+  assert!(
+    issues
+      .iter()
+      .any(|issue| issue.error == StatetableError::Conflict(0, 1)
+        && issue.inputs == [State::H, State::LH]
+        && issue.current == [Static::L])
+  );
+  assert_eq!(
+    issues
+      .iter()
+      .filter(|issue| issue.error == StatetableError::Incomplete)
+      .count(),
+    // 3 non-rising `CP` * 4 `D` * 2 `IQ`
+    24
+  );
+}
+#[test]
+fn sequential_statetable() {
+  use super::{CellCtx as _, Statetable};
+  use crate::library::Library;
+  let library = Library::<DefaultCtx>::parse_lib(
+    r#"library (test) {
+      cell (DFFRS) {
+        ff (IQ, IQN) {
+          clocked_on : "CP";
+          next_state : "D";
+          clear : "!CDN";
+          preset : "!SDN";
+          clear_preset_var1 : L;
+          clear_preset_var2 : H;
+        }
+        pin (D) { direction : input; }
+        pin (CP) { direction : input; }
+        pin (CDN) { direction : input; }
+        pin (SDN) { direction : input; }
+        pin (Q) { direction : output; function : "IQ"; }
+      }
+      cell (DFFNE) {
+        ff (IQ, IQN) {
+          clocked_on : "!CPN";
+          next_state : "(E D) + (!E IQ)";
+        }
+        pin (D) { direction : input; }
+        pin (E) { direction : input; }
+        pin (CPN) { direction : input; }
+        pin (Q) { direction : output; function : "IQ"; }
+      }
+      cell (DFFDE) {
+        ff (IQ, IQN) {
+          clocked_on : "CP1";
+          clocked_on_also : "CP2";
+          next_state : "D";
+        }
+        pin (D) { direction : input; }
+        pin (CP1) { direction : input; }
+        pin (CP2) { direction : input; }
+        pin (Q) { direction : output; function : "IQ"; }
+      }
+      cell (LATR) {
+        latch (IQ, IQN) {
+          enable : "G";
+          data_in : "D";
+          clear : "!RN";
+        }
+        pin (D) { direction : input; }
+        pin (G) { direction : input; }
+        pin (RN) { direction : input; }
+        pin (Q) { direction : output; function : "IQ"; }
+      }
+    }"#,
+    None,
+  )
+  .unwrap();
+  for name in ["DFFRS", "DFFNE", "DFFDE"] {
+    let cell = library.cell.get(name).unwrap();
+    let variables = cell.extra_ctx.logic_variables();
+    let ff = cell.ff.iter().next().unwrap();
+    let statetable = ff.to_statetable().unwrap();
+    assert!(statetable.check().is_empty(), "{name}");
+    let synthesized = statetable.to_ff(variables).unwrap();
+    assert_eq!(synthesized.to_statetable().unwrap().table, statetable.table, "{name}");
+    assert!(statetable.to_latch(variables).is_none(), "{name}");
+    if name == "DFFNE" {
+      let clocked_on = synthesized.clocked_on.unwrap();
+      assert_eq!(clocked_on.expr.to_string(), "!CPN");
+    }
+  }
+  let cell = library.cell.get("LATR").unwrap();
+  let variables = cell.extra_ctx.logic_variables();
+  let latch = cell.latch.iter().next().unwrap();
+  let statetable = latch.to_statetable().unwrap();
+  assert!(statetable.check().is_empty());
+  let synthesized = statetable.to_latch(variables).unwrap();
+  assert_eq!(synthesized.to_statetable().unwrap().table, statetable.table);
+  assert!(statetable.to_ff(variables).is_none());
+  assert_eq!(synthesized.enable.unwrap().expr.to_string(), "G");
+  assert_eq!(synthesized.clear.unwrap().expr.to_string(), "!RN");
+
+  // `Q2` is not the inverse of `Q1`
+  let holding = crate::ast::test_parse::<Statetable<DefaultCtx>>(
+    r#"("D CP", "Q1 Q2") {
+      table : "- ~R : - - : N N ,\
+               L R  : - - : L N ,\
+               H R  : - - : H N ";
+    }"#,
+  );
+  assert!(holding.check().is_empty());
+  let variables = crate::expression::BddVariableSet::new(&["D", "CP", "Q1", "Q2"]);
+  assert!(holding.to_ff(&variables).is_none());
+  assert!(holding.to_latch(&variables).is_none());
+}
+
+#[test]
+fn internal_energy() {
+  use crate::{
+    expression::logic::Edge,
+    internal_power::{InternalPowerError, SwitchingEvent},
+    library::Library,
+  };
+  let library = Library::<DefaultCtx>::parse_lib(
+    r#"library (test) {
+      power_lut_template (energy_2d) {
+        variable_1 : total_output_net_capacitance;
+        variable_2 : input_transition_time;
+        index_1 ("1, 2");
+        index_2 ("10, 20");
+      }
+      power_lut_template (energy_3d) {
+        variable_1 : input_transition_time;
+        variable_2 : total_output_net_capacitance;
+        variable_3 : equal_or_opposite_output_net_capacitance;
+        index_1 ("10, 20");
+        index_2 ("1, 2");
+        index_3 ("1, 2");
+      }
+      cell (NAND2) {
+        pin (A) {
+          direction : input;
+          internal_power () {
+            related_pg_pin : VDD;
+            rise_power (scalar) { values ("0.5"); }
+          }
+        }
+        pin (B) { direction : input; }
+        pin (Y) {
+          direction : output;
+          function : "!(A&B)";
+          internal_power () {
+            related_pin : "A";
+            related_pg_pin : VDD;
+            when : "B";
+            rise_power (energy_2d) {
+              index_1 ("1, 2");
+              index_2 ("10, 20");
+              values ("1, 2", "3, 4");
+            }
+            fall_power (energy_2d) {
+              index_1 ("1, 2");
+              index_2 ("10, 20");
+              values ("5, 6", "7, 8");
+            }
+          }
+          internal_power () {
+            related_pin : "A";
+            related_pg_pin : VDD;
+            rise_power (energy_2d) {
+              index_1 ("1, 2");
+              index_2 ("10, 20");
+              values ("10, 20", "30, 40");
+            }
+          }
+          internal_power () {
+            related_pin : "A B";
+            related_pg_pin : VSS;
+            power (energy_2d) {
+              index_1 ("1, 2");
+              index_2 ("10, 20");
+              values ("0.1, 0.2", "0.3, 0.4");
+            }
+          }
+        }
+      }
+      cell (DFF) {
+        pin (CP) { direction : input; }
+        pin (QN) { direction : output; }
+        pin (Q) {
+          direction : output;
+          internal_power () {
+            related_pin : "CP";
+            rise_power (energy_3d) {
+              index_1 ("10, 20");
+              index_2 ("1, 2");
+              index_3 ("1, 2");
+              values ("10, 20", "30, 40", "50, 60", "70, 80");
+            }
+          }
+          internal_power () {
+            related_pin : "CP";
+            equal_or_opposite_output : "QN";
+            rise_power (energy_3d) {
+              index_1 ("10, 20");
+              index_2 ("1, 2");
+              index_3 ("1, 2");
+              values ("1, 2", "3, 4", "5, 6", "7, 8");
+            }
+          }
+        }
+      }
+    }"#,
+    None,
+  )
+  .unwrap();
+  let cell = library.cell.get("NAND2").unwrap();
+  let energy = |pin: &str, related_pin, edge, state: &[(&str, bool)]| {
+    let event = SwitchingEvent {
+      related_pin,
+      edge,
+      slew: 15.0,
+      load: 1.5,
+      opposite_load: 0.0,
+      opposite_pins: &[],
+      state,
+    };
+    cell
+      .internal_energy(pin, &event)
+      .unwrap()
+      .into_iter()
+      .map(|rail| (rail.related_pg_pin, rail.energy, rail.conditional))
+      .collect::<Vec<_>>()
+  };
+  // the `when` group holds
+  assert_eq!(
+    energy("Y", Some("A"), Edge::R, &[("B", true)]),
+    [("VDD".to_owned(), 2.5, true), ("VSS".to_owned(), 0.25, false)]
+  );
+  // the default group, when `when` not holds or unknown
+  for state in [&[("B", false)][..], &[]] {
+    assert_eq!(
+      energy("Y", Some("A"), Edge::R, state),
+      [("VDD".to_owned(), 25.0, false), ("VSS".to_owned(), 0.25, false)]
+    );
+  }
+  assert_eq!(
+    energy("Y", Some("A"), Edge::F, &[("B", true), ("A", false)]),
+    [("VDD".to_owned(), 6.5, true), ("VSS".to_owned(), 0.25, false)]
+  );
+  // no default `fall_power`
+  assert_eq!(
+    energy("Y", Some("A"), Edge::F, &[("B", false)]),
+    [("VSS".to_owned(), 0.25, false)]
+  );
+  assert_eq!(energy("Y", Some("B"), Edge::R, &[]), [("VSS".to_owned(), 0.25, false)]);
+  assert_eq!(energy("A", None, Edge::R, &[]), [("VDD".to_owned(), 0.5, false)]);
+  assert!(energy("A", None, Edge::F, &[]).is_empty());
+  assert_eq!(
+    cell.internal_energy(
+      "Z",
+      &SwitchingEvent {
+        related_pin: None,
+        edge: Edge::R,
+        slew: 0.0,
+        load: 0.0,
+        opposite_load: 0.0,
+        opposite_pins: &[],
+        state: &[],
+      }
+    ),
+    Err(InternalPowerError::NoPin("Z".to_owned()))
+  );
+  // equal_or_opposite_output
+  let cell = library.cell.get("DFF").unwrap();
+  let energy = |slew, load, opposite_load, opposite_pins| {
+    let event = SwitchingEvent {
+      related_pin: Some("CP"),
+      edge: Edge::R,
+      slew,
+      load,
+      opposite_load,
+      opposite_pins,
+      state: &[],
+    };
+    let energies = cell.internal_energy("Q", &event).unwrap();
+    assert_eq!(energies.len(), 1);
+    energies[0].energy
+  };
+  assert!((energy(10.0, 1.0, 2.0, &["QN"]) - 2.0).abs() < 1e-12);
+  assert!((energy(20.0, 2.0, 1.5, &["QN"]) - 7.5).abs() < 1e-12);
+  // the group without `equal_or_opposite_output` when QN does not switch
+  assert!((energy(10.0, 1.0, 2.0, &[]) - 20.0).abs() < 1e-12);
+  assert!((energy(20.0, 2.0, 1.5, &[]) - 75.0).abs() < 1e-12);
+  assert!(library.to_string().contains("equal_or_opposite_output : QN;"));
+}
+
+#[test]
+fn average_power() {
+  use super::{CellCtx as _, JointProbability, PinActivity, SignalProbability};
+  use crate::{internal_power::InternalPowerError, library::Library};
+  let library = Library::<DefaultCtx>::parse_lib(
+    r#"library (test) {
+      default_cell_leakage_power : 3;
+      cell (NAND2) {
+        cell_leakage_power : 5;
+        pin (A) { direction : input; }
+        pin (B) { direction : input; }
+        pin (Y) {
+          direction : output;
+          function : "!(A&B)";
+          internal_power () {
+            related_pin : "A";
+            related_pg_pin : VDD;
+            when : "B";
+            rise_power (scalar) { values ("1"); }
+            fall_power (scalar) { values ("3"); }
+          }
+          internal_power () {
+            related_pin : "A";
+            related_pg_pin : VDD;
+            power (scalar) { values ("10"); }
+          }
+        }
+        leakage_power () {
+          related_pg_pin : VDD;
+          when : "!A&!B";
+          value : 1;
+        }
+        leakage_power () {
+          related_pg_pin : VDD;
+          when : "A&!B";
+          value : 2;
+        }
+        leakage_power () {
+          related_pg_pin : VDD;
+          when : "!A&B";
+          value : 3;
+        }
+        leakage_power () {
+          related_pg_pin : VDD;
+          value : 4;
+        }
+        leakage_power () {
+          related_pg_pin : VSS;
+          when : "A";
+          value : 10;
+        }
+      }
+      cell (AND2) {
+        cell_leakage_power : -1;
+        pin (A) { direction : input; }
+        pin (B) { direction : input; }
+        pin (Y) { direction : output; function : "A&B"; }
+        leakage_power () {
+          related_pg_pin : VDD;
+          when : "A&B";
+          value : 2;
+        }
+        leakage_power () {
+          related_pg_pin : VSS;
+          when : "!A&!B";
+          value : 1;
+        }
+      }
+    }"#,
+    None,
+  )
+  .unwrap();
+  let cell = library.cell.get("NAND2").unwrap();
+  let variables = cell.extra_ctx.logic_variables();
+  let independent = SignalProbability {
+    high: [("A".to_owned(), 0.2), ("B".to_owned(), 0.7)].into_iter().collect(),
+    correlated: Vec::new(),
+  };
+  let approx = |a: f64, b: f64| (a - b).abs() < 1e-12;
+  let bdd = variables.eval_expression_string("A | B");
+  assert!(approx(independent.probability(&bdd, variables), 0.76));
+  assert!(approx(SignalProbability::default().probability(&bdd, variables), 0.75));
+  let leakage = |probability: &SignalProbability| {
+    cell
+      .leakage_estimate(probability, &library)
+      .rails
+      .into_iter()
+      .map(|rail| (rail.related_pg_pin, rail.power, rail.uncovered))
+      .collect::<Vec<_>>()
+  };
+  // VDD: 0.24 * 1 + 0.06 * 2 + 0.56 * 3 + 0.14 * 4
+  // VSS: 0.2 * 10, the rest is uncovered without the group without `when`
+  let rails = leakage(&independent);
+  assert_eq!(rails.len(), 2);
+  for ((name, power, uncovered), expected) in
+    rails.iter().zip([("VDD", 2.6, 0.14), ("VSS", 2.0, 0.8)])
+  {
+    assert_eq!(name, expected.0);
+    assert!(approx(*power, expected.1), "{name}: {power}");
+    assert!(approx(*uncovered, expected.2), "{name}: {uncovered}");
+  }
+  // A and B are always equal
+  let correlated = SignalProbability {
+    correlated: vec![JointProbability {
+      pins: vec!["A".to_owned(), "B".to_owned()],
+      states: vec![(vec![true, true], 1.0), (vec![false, false], 1.0)],
+    }],
+    ..SignalProbability::default()
+  };
+  assert!(approx(
+    correlated.probability(&variables.eval_expression_string("A ^ B"), variables),
+    0.0
+  ));
+  let rails = leakage(&correlated);
+  assert!(approx(rails[0].1, 2.5));
+  assert!(approx(rails[1].1, 5.0));
+  // the group without `when` describes all the states
+  let estimate = cell.leakage_estimate(&independent, &library);
+  assert!(approx(estimate.default, 0.0) && approx(estimate.uncovered, 0.0));
+  // no group describes 1 - 0.14 - 0.24 of the states, which take
+  // default_cell_leakage_power once, since cell_leakage_power is negative
+  let and2 = library.cell.get("AND2").unwrap();
+  let estimate = and2.leakage_estimate(&independent, &library);
+  assert!(approx(estimate.uncovered, 0.62) && approx(estimate.default, 1.86));
+  assert!(
+    approx(estimate.rails[0].power, 0.28) && approx(estimate.rails[0].uncovered, 0.86)
+  );
+  assert!(
+    approx(estimate.rails[1].power, 0.24) && approx(estimate.rails[1].uncovered, 0.76)
+  );
+  let power = and2.average_power(&independent, &[], &library).unwrap();
+  assert!(approx(power.default_leakage, 1.86) && approx(power.total, 2.38));
+  // rise 0.7 * 1 + 0.3 * 10, fall 0.7 * 3 + 0.3 * 10
+  let activity = PinActivity {
+    pin: "Y",
+    related_pin: Some("A"),
+    toggle_rate: 2.0,
+    slew: 0.0,
+    load: 0.0,
+    opposite_load: 0.0,
+    opposite_pins: &[],
+  };
+  let power = cell.average_power(&independent, &[activity], &library).unwrap();
+  assert_eq!(power.rails.len(), 2);
+  assert!(approx(power.rails[0].internal, 8.8));
+  assert!(approx(power.rails[1].internal, 0.0));
+  assert!(approx(power.total, 13.4));
+  assert_eq!(
+    cell.average_power(&independent, &[PinActivity { pin: "Z", ..activity }], &library),
+    Err(InternalPowerError::NoPin("Z".to_owned()))
+  );
+}
@@ -4,6 +4,7 @@
 //! </script>
 mod latch_ff;
 pub mod logic;
+pub mod npn;
 mod parser;
 use crate::{
   Ctx, DefaultCtx,
@@ -449,8 +450,8 @@ mod test {
   impl From<BooleanExpression> for BddBooleanExpression {
     #[inline]
     fn from(value: BooleanExpression) -> Self {
-      let node_set = value.expr.support_set();
-      let mut node_set: Vec<&str> = node_set.iter().map(String::as_str).collect();
+      let support_set = value.expr.support_set();
+      let mut node_set: Vec<&str> = support_set.iter().map(String::as_str).collect();
       node_set.sort_unstable();
       let variables = BddVariableSet::new(&node_set);
       Self {
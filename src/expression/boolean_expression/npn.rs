@@ -0,0 +1,214 @@
+//! NPN (input Negation, input Permutation, output Negation) equivalence
+//! of small boolean functions, based on truth tables.
+use super::Bdd;
+use biodivine_lib_bdd::{BddValuation, BddVariable};
+use itertools::Itertools as _;
+
+/// The max number of inputs supported by [`TruthTable`]
+pub const MAX_INPUTS: usize = 6;
+
+/// Truth table of a boolean function with at most [`MAX_INPUTS`] inputs.
+///
+/// The `m`-th bit is the output value of minterm `m`,
+/// where the value of `i`-th input is the `i`-th bit of `m`.
+#[derive(Debug, Clone, Copy)]
+#[derive(Hash, PartialEq, Eq)]
+#[derive(Ord, PartialOrd)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct TruthTable {
+  bits: u64,
+  inputs: usize,
+}
+
+/// A NPN transform, see [`TruthTable::transform`]
+#[derive(Debug, Clone, Default)]
+#[derive(Hash, PartialEq, Eq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct NpnTransform {
+  /// The `i`-th input of the original function is connected to the
+  /// `permutation[i]`-th input of the transformed function
+  pub permutation: Vec<usize>,
+  /// Whether the `i`-th input of the original function is inverted
+  pub input_inverted: Vec<bool>,
+  /// Whether the output is inverted
+  pub output_inverted: bool,
+}
+
+impl NpnTransform {
+  /// The identity transform of `inputs` inputs
+  #[must_use]
+  #[inline]
+  pub fn identity(inputs: usize) -> Self {
+    Self {
+      permutation: (0..inputs).collect(),
+      input_inverted: vec![false; inputs],
+      output_inverted: false,
+    }
+  }
+  /// Number of the inverted inputs and output
+  #[must_use]
+  #[inline]
+  pub fn inversions(&self) -> usize {
+    self
+      .input_inverted
+      .iter()
+      .chain([&self.output_inverted])
+      .filter(|inverted| **inverted)
+      .count()
+  }
+}
+
+impl TruthTable {
+  #[expect(clippy::arithmetic_side_effects)]
+  #[inline]
+  const fn mask(inputs: usize) -> u64 {
+    if inputs >= MAX_INPUTS { u64::MAX } else { (1 << (1 << inputs)) - 1 }
+  }
+  /// Build truth table from `f(minterm)`, return `None` when `inputs > MAX_INPUTS`
+  #[inline]
+  pub fn new<F: FnMut(usize) -> bool>(inputs: usize, mut f: F) -> Option<Self> {
+    (inputs <= MAX_INPUTS).then(|| Self {
+      bits: (0..Self::minterms(inputs))
+        .filter(|minterm| f(*minterm))
+        .fold(0, |bits, minterm| bits | (1 << minterm)),
+      inputs,
+    })
+  }
+  /// Build truth table from a BDD, with the given input variables order.
+  ///
+  /// All the variables outside `inputs` are set to `false`,
+  /// return `None` when `inputs.len() > MAX_INPUTS`
+  #[must_use]
+  #[inline]
+  pub fn from_bdd(bdd: &Bdd, inputs: &[BddVariable]) -> Option<Self> {
+    let mut valuation = BddValuation::all_false(bdd.num_vars());
+    Self::new(inputs.len(), |minterm| {
+      for (i, var) in inputs.iter().enumerate() {
+        valuation.set_value(*var, Self::bit(minterm, i));
+      }
+      bdd.eval_in(&valuation)
+    })
+  }
+  #[inline]
+  const fn minterms(inputs: usize) -> usize {
+    1 << inputs
+  }
+  #[inline]
+  const fn bit(minterm: usize, i: usize) -> bool {
+    (minterm >> i) & 1 == 1
+  }
+  /// Number of inputs
+  #[must_use]
+  #[inline]
+  pub const fn inputs(&self) -> usize {
+    self.inputs
+  }
+  /// The raw bits
+  #[must_use]
+  #[inline]
+  pub const fn bits(&self) -> u64 {
+    self.bits
+  }
+  /// The output value of `minterm`
+  #[must_use]
+  #[inline]
+  pub const fn value(&self, minterm: usize) -> bool {
+    (self.bits >> minterm) & 1 == 1
+  }
+  /// Whether the function is constant, return its value
+  #[must_use]
+  #[inline]
+  pub const fn as_const(&self) -> Option<bool> {
+    let mask = Self::mask(self.inputs);
+    if self.bits & mask == 0 {
+      Some(false)
+    } else if self.bits & mask == mask {
+      Some(true)
+    } else {
+      None
+    }
+  }
+  /// Apply the NPN transform, the new function is
+  ///
+  /// `g(x) = f(y) ^ output_inverted`, where `y[i] = x[permutation[i]] ^ input_inverted[i]`
+  #[must_use]
+  #[inline]
+  pub fn transform(&self, t: &NpnTransform) -> Self {
+    Self {
+      bits: (0..Self::minterms(self.inputs))
+        .filter(|minterm| {
+          let y = t
+            .permutation
+            .iter()
+            .zip(&t.input_inverted)
+            .enumerate()
+            .filter(|(_, (p, inverted))| Self::bit(*minterm, **p) != **inverted)
+            .fold(0, |y, (i, _)| y | (1 << i));
+          self.value(y) != t.output_inverted
+        })
+        .fold(0, |bits, minterm| bits | (1 << minterm)),
+      inputs: self.inputs,
+    }
+  }
+  /// All the NPN transforms of `inputs` inputs
+  #[inline]
+  fn all_transforms(inputs: usize) -> impl Iterator<Item = NpnTransform> {
+    (0..inputs).permutations(inputs).flat_map(move |permutation| {
+      (0..Self::minterms(inputs)).flat_map(move |neg| {
+        let perm = permutation.clone();
+        [false, true].into_iter().map(move |output_inverted| NpnTransform {
+          permutation: perm.clone(),
+          input_inverted: (0..inputs).map(|i| Self::bit(neg, i)).collect(),
+          output_inverted,
+        })
+      })
+    })
+  }
+  /// The NPN canonical form, which is the minimum truth table over all NPN transforms.
+  ///
+  /// Return the canonical form and the transform `t`,
+  /// such that `self.transform(&t) == canonical`
+  #[must_use]
+  #[inline]
+  pub fn npn_canonical(&self) -> (Self, NpnTransform) {
+    Self::all_transforms(self.inputs)
+      .map(|t| (self.transform(&t), t))
+      .min_by_key(|(canonical, t)| (canonical.bits, t.inversions()))
+      .unwrap_or_else(|| (*self, NpnTransform::identity(self.inputs)))
+  }
+  /// Find the NPN transform `t` with least inversions,
+  /// such that `reference.transform(&t) == self`
+  #[must_use]
+  #[inline]
+  pub fn npn_match(&self, reference: &Self) -> Option<NpnTransform> {
+    if self.inputs != reference.inputs {
+      return None;
+    }
+    Self::all_transforms(self.inputs)
+      .filter(|t| reference.transform(t) == *self)
+      .min_by_key(NpnTransform::inversions)
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  #[test]
+  fn npn_canonical() {
+    let nand2 = TruthTable::new(2, |m| m != 0b11).unwrap();
+    let nor2 = TruthTable::new(2, |m| m == 0b00).unwrap();
+    let xor2 = TruthTable::new(2, |m| m == 0b01 || m == 0b10).unwrap();
+    assert_eq!(nand2.npn_canonical().0, nor2.npn_canonical().0);
+    assert_ne!(nand2.npn_canonical().0, xor2.npn_canonical().0);
+    let (canonical, t) = xor2.npn_canonical();
+    assert_eq!(xor2.transform(&t), canonical);
+    let t = nor2.npn_match(&nand2).unwrap();
+    assert_eq!(nand2.transform(&t), nor2);
+    assert_eq!(t.inversions(), 3);
+    // A & !B, with C unused
+    let and_not = TruthTable::new(3, |m| m & 0b011 == 0b001).unwrap();
+    let and2 = TruthTable::new(2, |m| m == 0b11).unwrap();
+    assert_eq!(and_not.npn_match(&and2), None);
+    assert_eq!(TruthTable::new(2, |_| true).unwrap().as_const(), Some(true));
+  }
+}
@@ -146,7 +146,7 @@ enum Token {
   SingleOp(SingleOp),
   BinaryOp(BinaryOp),
   Node(Expr),
-  Tokens(Vec<Token>),
+  Tokens(Vec<Self>),
 }
 
 #[inline]
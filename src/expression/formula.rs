@@ -19,11 +19,11 @@ use crate::{
 #[derive(Debug, Clone, PartialEq)]
 #[derive(serde::Serialize, serde::Deserialize)]
 pub enum FormulaExpr {
-  Add(Box<FormulaExpr>, Box<FormulaExpr>),
-  Sub(Box<FormulaExpr>, Box<FormulaExpr>),
-  Mul(Box<FormulaExpr>, Box<FormulaExpr>),
-  Div(Box<FormulaExpr>, Box<FormulaExpr>),
-  Neg(Box<FormulaExpr>),
+  Add(Box<Self>, Box<Self>),
+  Sub(Box<Self>, Box<Self>),
+  Mul(Box<Self>, Box<Self>),
+  Div(Box<Self>, Box<Self>),
+  Neg(Box<Self>),
   Num(f64),
   Var(String),
 }
@@ -341,7 +341,7 @@ enum Token<'s> {
   Div,
   Num(f64),
   Var(&'s str),
-  Paren(Vec<Token<'s>>),
+  Paren(Vec<Self>),
 }
 
 fn tokens(i: &str) -> IResult<&str, Vec<Token<'_>>> {
@@ -0,0 +1,224 @@
+//! Evaluation of the internal energy of a switching event,
+//! see [`Cell::internal_energy`].
+#![allow(clippy::multiple_inherent_impl)]
+use super::InternalPower;
+use crate::{
+  Ctx,
+  cell::{Cell, CellCtx as _},
+  expression::{BddVariableSet, logic},
+  pin::Pin,
+  table::{Lookup, LookupConfig, LookupError, TableLookUp, Variable},
+};
+
+/// A switching event of a pin, see [`Pin::internal_energy`]
+#[derive(Debug, Clone, Copy)]
+pub struct SwitchingEvent<'a> {
+  /// The pin causing the transition, i.e., the `related_pin` of the groups.
+  /// `None` for the transition of the pin itself, e.g., an input pin,
+  /// which selects the groups without `related_pin`
+  pub related_pin: Option<&'a str>,
+  /// The transition of the pin, [`logic::Edge::R`] selects `rise_power`
+  pub edge: logic::Edge,
+  /// The input transition time
+  pub slew: f64,
+  /// The total output net capacitance of the pin
+  pub load: f64,
+  /// The net capacitance of the `equal_or_opposite_output` pins,
+  /// only used by the tables with that variable
+  pub opposite_load: f64,
+  /// The other output pins switching in this event, which select the groups
+  /// by `equal_or_opposite_output`, see [`InternalPower::is_opposite`]
+  pub opposite_pins: &'a [&'a str],
+  /// The levels of pins to evaluate `when`, the pins not given are unknown
+  pub state: &'a [(&'a str, bool)],
+}
+
+/// The internal energy of one power rail, see [`Pin::internal_energy`]
+#[derive(Debug, Clone)]
+#[derive(PartialEq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct RailEnergy {
+  /// The `related_pg_pin` of the group, empty for the groups without it
+  pub related_pg_pin: String,
+  /// The energy looked up from the `rise_power` / `fall_power` / `power` table
+  pub energy: f64,
+  /// Whether the energy comes from a group with `when`
+  pub conditional: bool,
+  /// Whether the lookup is out of the table range and extrapolated
+  pub extrapolated: bool,
+}
+
+/// The error of [`Cell::internal_energy`]
+#[derive(Debug, Clone, thiserror::Error, PartialEq)]
+pub enum InternalPowerError {
+  /// The pin is not found in the cell
+  #[error("pin {0} is not found")]
+  NoPin(String),
+  /// The table lookup fails
+  #[error(transparent)]
+  Lookup(#[from] LookupError),
+}
+
+/// The selected groups of one `related_pg_pin`
+struct Rail<'a, C: 'static + Ctx> {
+  name: String,
+  /// The first group whose `when` holds
+  conditional: Option<&'a InternalPower<C>>,
+  /// The first group without `when`
+  default: Option<&'a InternalPower<C>>,
+}
+
+impl<C: 'static + Ctx> InternalPower<C> {
+  /// Whether the `related_pin` of this group covers `related_pin`,
+  /// or this group has no `related_pin` for `None`
+  #[inline]
+  #[must_use]
+  pub fn is_related(&self, related_pin: Option<&str>) -> bool {
+    related_pin.map_or(self.related_pin.inner.is_empty(), |pin| {
+      self.related_pin.inner.contains(pin)
+    })
+  }
+  /// Whether the `equal_or_opposite_output` of this group names exactly
+  /// `opposite_pins`, the other outputs switching in the event.
+  /// So the groups without it are selected when no other output switches
+  #[inline]
+  #[must_use]
+  pub fn is_opposite(&self, opposite_pins: &[&str]) -> bool {
+    let outputs = &self.equal_or_opposite_output.inner;
+    opposite_pins.iter().all(|pin| outputs.contains(*pin))
+      && outputs.iter().all(|pin| opposite_pins.contains(&pin.as_str()))
+  }
+  /// Whether `when` holds for all the unknown pins under `state`,
+  /// i.e., it is `true` after restricting the known pins.
+  /// The groups without `when` always hold.
+  #[inline]
+  #[must_use]
+  pub fn when_holds(&self, state: &[(&str, bool)], variables: &BddVariableSet) -> bool {
+    self.when.as_ref().is_none_or(|when| {
+      let known: Vec<_> = state
+        .iter()
+        .filter_map(|(name, level)| variables.var_by_name(name).map(|var| (var, *level)))
+        .collect();
+      when.bdd.restrict(&known).is_true()
+    })
+  }
+  /// The table of `edge`, `rise_power` / `fall_power`, or `power` without it
+  #[inline]
+  #[must_use]
+  pub fn table(&self, edge: logic::Edge) -> Option<&TableLookUp<C>> {
+    match edge {
+      logic::Edge::R => self.rise_power.as_ref(),
+      logic::Edge::F => self.fall_power.as_ref(),
+    }
+    .or(self.power.as_ref())
+  }
+  /// The energy of `event` looked up from [`InternalPower::table`],
+  /// `None` without the table of the edge.
+  ///
+  /// The table is looked up by the variables of its `power_lut_template`,
+  /// e.g., `equal_or_opposite_output_net_capacitance` takes `opposite_load`.
+  /// Without template, `index_1` is slew, `index_2` is load,
+  /// and `index_3` is the opposite load.
+  #[inline]
+  pub fn lookup_energy(
+    &self,
+    event: &SwitchingEvent<'_>,
+  ) -> Result<Option<Lookup<f64>>, LookupError> {
+    self
+      .table(event.edge)
+      .map(|table| {
+        table.lookup_variables(
+          &[
+            Variable::INPUT_TRANSITION_TIME,
+            Variable::TOTAL_OUTPUT_NET_CAPACITANCE,
+            Variable::EQUAL_OR_OPPOSITE_OUTPUT_NET_CAPACITANCE,
+          ],
+          |variable| match variable {
+            Some(Variable::TOTAL_OUTPUT_NET_CAPACITANCE) => event.load,
+            Some(Variable::EQUAL_OR_OPPOSITE_OUTPUT_NET_CAPACITANCE) => {
+              event.opposite_load
+            }
+            _ => event.slew,
+          },
+          LookupConfig::default(),
+        )
+      })
+      .transpose()
+  }
+}
+
+impl<C: 'static + Ctx> Pin<C> {
+  /// The internal energy of `event` on this pin, per `related_pg_pin`,
+  /// in the order of the groups.
+  ///
+  /// The groups are selected by [`InternalPower::is_related`],
+  /// [`InternalPower::is_opposite`] and the table of the edge. Of each rail, the first group whose `when` holds under the
+  /// state is used, or the group without `when` as the default when none holds.
+  /// The tables are looked up by [`InternalPower::lookup_energy`].
+  #[inline]
+  pub fn internal_energy(
+    &self,
+    event: &SwitchingEvent<'_>,
+    variables: &BddVariableSet,
+  ) -> Result<Vec<RailEnergy>, LookupError> {
+    let mut rails: Vec<Rail<'_, C>> = Vec::new();
+    for group in &self.internal_power {
+      if !group.is_related(event.related_pin)
+        || !group.is_opposite(event.opposite_pins)
+        || group.table(event.edge).is_none()
+      {
+        continue;
+      }
+      let name = group.related_pg_pin.to_string();
+      if !rails.iter().any(|rail| rail.name == name) {
+        rails.push(Rail {
+          name: name.clone(),
+          conditional: None,
+          default: None,
+        });
+      }
+      if let Some(rail) = rails.iter_mut().find(|rail| rail.name == name) {
+        if group.when.is_none() {
+          _ = rail.default.get_or_insert(group);
+        } else if rail.conditional.is_none() && group.when_holds(event.state, variables) {
+          rail.conditional = Some(group);
+        } else {
+          // the default group, or the `when` not holds
+        }
+      }
+    }
+    let mut energies = Vec::with_capacity(rails.len());
+    for Rail { name: related_pg_pin, conditional, default } in rails {
+      let Some(group) = conditional.or(default) else {
+        continue;
+      };
+      if let Some(lookup) = group.lookup_energy(event)? {
+        energies.push(RailEnergy {
+          related_pg_pin,
+          energy: lookup.value,
+          conditional: conditional.is_some(),
+          extrapolated: lookup.extrapolated,
+        });
+      }
+    }
+    Ok(energies)
+  }
+}
+
+impl<C: 'static + Ctx> Cell<C> {
+  /// The internal energy of `event` on `pin`, a pin of this cell or of its buses,
+  /// see [`Pin::internal_energy`]
+  #[inline]
+  pub fn internal_energy(
+    &self,
+    pin: &str,
+    event: &SwitchingEvent<'_>,
+  ) -> Result<Vec<RailEnergy>, InternalPowerError> {
+    let found = self
+      .pin
+      .get(pin)
+      .or_else(|| self.bus.iter().find_map(|bus| bus.pin.get(pin)))
+      .ok_or_else(|| InternalPowerError::NoPin(pin.to_owned()))?;
+    Ok(found.internal_energy(event, self.extra_ctx.logic_variables())?)
+  }
+}
@@ -5,6 +5,8 @@ use crate::{
   expression::LogicBooleanExpression,
   table::TableLookUp,
 };
+mod energy;
+pub use energy::{InternalPowerError, RailEnergy, SwitchingEvent};
 
 #[derive(Debug, Clone)]
 #[derive(liberty_macros::Group)]
@@ -21,7 +23,12 @@ pub struct InternalPower<C: 'static + Ctx> {
   #[liberty(attributes)]
   pub attributes: Attributes,
   // NOTICE: Simple Attributes
-  // equal_or_opposite_output
+  /// The `equal_or_opposite_output` attribute names the output pins that switch
+  /// together with this pin, and the `equal_or_opposite_output_net_capacitance`
+  /// variable of the power tables takes their load
+  #[id]
+  #[liberty(simple)]
+  pub equal_or_opposite_output: WordSet,
   // falling_together_group
   // power_level
   #[id]
@@ -134,6 +134,7 @@
     clippy::use_debug,
     clippy::question_mark_used,
     clippy::used_underscore_binding,
+    clippy::doc_paragraphs_missing_punctuation,
 )]
 #![warn(
     clippy::manual_map,
@@ -159,7 +160,6 @@
     unused_results,
     clippy::trivially_copy_pass_by_ref,
     clippy::shadow_unrelated,
-    clippy::default_numeric_fallback,
     clippy::unreadable_literal,
     clippy::type_complexity,
     clippy::unwrap_used,
@@ -176,7 +176,6 @@
     clippy::arithmetic_side_effects,
     clippy::field_reassign_with_default,
     clippy::undocumented_unsafe_blocks,
-    clippy::or_fun_call,
   )
 )]
 #[expect(unused_imports)]
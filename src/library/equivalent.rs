@@ -0,0 +1,477 @@
+//! Find the functionally equivalent cells between two libraries,
+//! e.g., to migrate a design from one foundry library to another.
+#![allow(clippy::multiple_inherent_impl)]
+use super::Library;
+use crate::{
+  Ctx,
+  cell::{Cell, CellCtx as _, Sequential, Statetable},
+  expression::{
+    Bdd, BddVariableSet, Expr, LogicBooleanExpression,
+    logic::{State, Static},
+  },
+  pin::Direction,
+};
+use itertools::Itertools as _;
+use std::collections::HashMap;
+
+/// The max number of pin mappings tried for one pair of cells,
+/// see [`Cell::equivalent_pin_map`]
+pub const MAX_PIN_MAPPINGS: usize = 1 << 16;
+
+/// A value of the cell in this library, and the one in the other library.
+#[derive(Debug, Clone, Copy, Default)]
+#[derive(PartialEq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct Comparison {
+  /// The value in this library
+  pub this: Option<f64>,
+  /// The value in the other library
+  pub other: Option<f64>,
+}
+
+impl Comparison {
+  /// `other / this`
+  #[must_use]
+  #[inline]
+  #[expect(clippy::float_arithmetic)]
+  pub fn ratio(&self) -> Option<f64> {
+    self.this.zip(self.other).map(|(this, other)| other / this)
+  }
+}
+
+/// The matched pin of [`EquivalentCell`]
+#[derive(Debug, Clone)]
+#[derive(PartialEq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct PinMatch {
+  /// The pin name in this library
+  pub pin: String,
+  /// The pin name in the other library
+  pub other_pin: String,
+  /// The pin `capacitance`
+  pub capacitance: Comparison,
+}
+
+/// A candidate of equivalent cell, see [`Library::equivalent_cells`]
+#[derive(Debug, Clone)]
+#[derive(PartialEq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct EquivalentCell {
+  /// The cell name in this library
+  pub cell: String,
+  /// The cell name in the other library
+  pub other_cell: String,
+  /// The pin correspondence, in the pin order of this cell
+  pub pin_map: Vec<PinMatch>,
+  /// The cell `area`
+  pub area: Comparison,
+  /// The representative delay, that is the mean of all
+  /// `cell_rise` / `cell_fall` table values among all timing arcs
+  pub delay: Comparison,
+}
+
+/// What kind of node it is, the nodes can only be mapped to the same kind
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NodeKind {
+  Pin { direction: Option<Direction>, function: bool, three_state: bool },
+  FF(usize),
+  Latch(usize),
+}
+
+fn kind(group: &dyn Sequential) -> NodeKind {
+  if group.is_latch() {
+    NodeKind::Latch(group.bank_bits())
+  } else {
+    NodeKind::FF(group.bank_bits())
+  }
+}
+
+/// A pin, or the `variable1` / `variable2` pair of a sequential group
+#[derive(Debug)]
+struct Node<'a> {
+  names: Vec<&'a str>,
+  kind: NodeKind,
+  /// Mapping invariant signature
+  signature: Vec<(u64, u64)>,
+}
+
+impl Node<'_> {
+  fn same_class(&self, other: &Self) -> bool {
+    self.kind == other.kind && self.signature == other.signature
+  }
+}
+
+/// The fraction of satisfying assignments, which is invariant under variable renaming
+#[expect(clippy::float_arithmetic)]
+fn density(bdd: &Bdd) -> u64 {
+  (bdd.cardinality() / 2.0_f64.powi(i32::from(bdd.num_vars()))).to_bits()
+}
+
+/// Rename the variables, return `None` when any variable is not in `map`
+fn rename(expr: &Expr, map: &HashMap<&str, &str>) -> Option<Expr> {
+  let boxed = |e: &Expr| rename(e, map).map(Box::new);
+  Some(match expr {
+    Expr::Const(b) => Expr::Const(*b),
+    Expr::Variable(name) => Expr::Variable((*map.get(name.as_str())?).to_owned()),
+    Expr::Not(e) => Expr::Not(boxed(e)?),
+    Expr::And(e1, e2) => Expr::And(boxed(e1)?, boxed(e2)?),
+    Expr::Or(e1, e2) => Expr::Or(boxed(e1)?, boxed(e2)?),
+    Expr::Xor(e1, e2) => Expr::Xor(boxed(e1)?, boxed(e2)?),
+    Expr::Imp(e1, e2) => Expr::Imp(boxed(e1)?, boxed(e2)?),
+    Expr::Iff(e1, e2) => Expr::Iff(boxed(e1)?, boxed(e2)?),
+    Expr::Cond(e1, e2, e3) => Expr::Cond(boxed(e1)?, boxed(e2)?, boxed(e3)?),
+  })
+}
+
+/// Whether `function` is `other_function` after renaming the variables with `map`
+fn function_eq(
+  function: Option<&LogicBooleanExpression>,
+  other_function: Option<&LogicBooleanExpression>,
+  map: &HashMap<&str, &str>,
+  variables: &BddVariableSet,
+) -> bool {
+  match (function, other_function) {
+    (None, None) => true,
+    (Some(f), Some(g)) => rename(&f.expr, map)
+      .and_then(|expr| variables.safe_eval_expression(&expr))
+      .is_some_and(|bdd| bdd == g.bdd),
+    _ => false,
+  }
+}
+
+fn sequential_eq(
+  groups: &[&dyn Sequential],
+  other_groups: &[&dyn Sequential],
+  map: &HashMap<&str, &str>,
+  variables: &BddVariableSet,
+) -> bool {
+  groups.iter().all(|group| {
+    let [name1, name2] = group.variables();
+    other_groups
+      .iter()
+      .find(|other| {
+        [map.get(name1), map.get(name2)] == other.variables().each_ref().map(Some)
+      })
+      .is_some_and(|other| {
+        kind(*group) == kind(*other)
+          && group.clear_preset_var() == other.clear_preset_var()
+          && group
+            .functions()
+            .into_iter()
+            .zip(other.functions())
+            .all(|(f, g)| function_eq(f, g, map, variables))
+      })
+  })
+}
+
+/// Place the `values` of nodes at their `columns` in the other nodes
+fn reorder<T: Copy>(values: &[T], columns: &[usize]) -> Option<Vec<T>> {
+  (0..columns.len())
+    .map(|column| {
+      let i = columns.iter().position(|c| *c == column)?;
+      values.get(i).copied()
+    })
+    .collect()
+}
+
+/// Compare the statetables by exhaustively evaluating both under the node mapping `map`,
+/// with all the inputs values `L`, `H`, `R` and `F`, and all the current values `L` and `H`,
+/// see [`Statetable::check`]. The cases failing in both tables are regarded as equal.
+fn statetable_eq<C: 'static + Ctx, C2: 'static + Ctx>(
+  statetable: Option<&Statetable<C>>,
+  other_statetable: Option<&Statetable<C2>>,
+  map: &HashMap<&str, &str>,
+) -> bool {
+  let Some((this, other)) = statetable.zip(other_statetable) else {
+    return statetable.is_none() && other_statetable.is_none();
+  };
+  let columns = |nodes: &[String], other_nodes: &[String]| -> Option<Vec<usize>> {
+    (nodes.len() == other_nodes.len())
+      .then(|| {
+        nodes
+          .iter()
+          .map(|node| {
+            let mapped = map.get(node.as_str())?;
+            other_nodes.iter().position(|other_node| other_node == mapped)
+          })
+          .collect()
+      })
+      .flatten()
+  };
+  let (Some(input_columns), Some(internal_columns)) = (
+    columns(&this.input_nodes, &other.input_nodes),
+    columns(&this.internal_nodes, &other.internal_nodes),
+  ) else {
+    return false;
+  };
+  let input_states = [State::L, State::H, State::LH, State::HL];
+  let current_states = [Static::L, Static::H];
+  this
+    .input_nodes
+    .iter()
+    .map(|_| input_states.iter().copied())
+    .multi_cartesian_product()
+    .cartesian_product(
+      this
+        .internal_nodes
+        .iter()
+        .map(|_| current_states.iter().copied())
+        .multi_cartesian_product(),
+    )
+    .all(|(inputs, current)| {
+      let (Some(other_inputs), Some(other_current)) =
+        (reorder(&inputs, &input_columns), reorder(&current, &internal_columns))
+      else {
+        return false;
+      };
+      let next = this.evaluate(&inputs, &current).ok();
+      let other_next = other.evaluate(&other_inputs, &other_current).ok().map(|values| {
+        internal_columns
+          .iter()
+          .map(|i| values.get(*i).copied())
+          .collect::<Option<Vec<_>>>()
+      });
+      match (next, other_next) {
+        (None, None) => true,
+        (Some(values), Some(other_values)) => other_values.is_some_and(|v| v == values),
+        (Some(_), None) | (None, Some(_)) => false,
+      }
+    })
+}
+
+#[expect(
+  clippy::as_conversions,
+  clippy::float_arithmetic,
+  clippy::cast_precision_loss,
+  clippy::arithmetic_side_effects
+)]
+fn representative_delay<C: 'static + Ctx>(cell: &Cell<C>) -> Option<f64> {
+  let (sum, n) = cell
+    .pin
+    .iter()
+    .flat_map(|pin| pin.timing.iter())
+    .flat_map(|timing| [timing.cell_rise.as_ref(), timing.cell_fall.as_ref()])
+    .flatten()
+    .flat_map(|table| table.values.iter())
+    .fold((0.0, 0_usize), |(sum, n), value| (sum + value, n + 1));
+  (n != 0).then(|| sum / n as f64)
+}
+
+impl<C: 'static + Ctx> Cell<C> {
+  fn sequentials(&self) -> Vec<&dyn Sequential> {
+    self
+      .ff
+      .iter()
+      .map(|ff| -> &dyn Sequential { ff })
+      .chain(self.ff_bank.iter().map(|ff| -> &dyn Sequential { ff }))
+      .chain(self.latch.iter().map(|latch| -> &dyn Sequential { latch }))
+      .chain(self.latch_bank.iter().map(|latch| -> &dyn Sequential { latch }))
+      .collect()
+  }
+  /// All the nodes to be mapped, with their mapping invariant signature
+  fn equivalent_nodes(&self) -> Vec<Node<'_>> {
+    let variables = self.extra_ctx.logic_variables();
+    let bdds: Vec<&Bdd> = self.logic_functions().into_iter().map(|f| &f.bdd).collect();
+    let signature = |names: &[&str]| {
+      let mut signature: Vec<(u64, u64)> = names
+        .iter()
+        .filter_map(|name| variables.var_by_name(name))
+        .flat_map(|var| {
+          bdds.iter().map(move |bdd| {
+            (
+              density(&bdd.var_restrict(var, false)),
+              density(&bdd.var_restrict(var, true)),
+            )
+          })
+        })
+        .collect();
+      signature.sort_unstable();
+      signature
+    };
+    self
+      .pin
+      .iter()
+      .map(|pin| {
+        let mut pin_signature = signature(&[pin.name.as_str()]);
+        if let Some(function) = &pin.function {
+          pin_signature.push((
+            density(&function.bdd),
+            pin.three_state.as_ref().map_or(u64::MAX, |f| density(&f.bdd)),
+          ));
+        }
+        Node {
+          names: vec![pin.name.as_str()],
+          kind: NodeKind::Pin {
+            direction: pin.direction,
+            function: pin.function.is_some(),
+            three_state: pin.three_state.is_some(),
+          },
+          signature: pin_signature,
+        }
+      })
+      .chain(self.sequentials().into_iter().map(|group| {
+        let names = group.variables();
+        Node {
+          names: names.to_vec(),
+          kind: kind(group),
+          signature: signature(&names),
+        }
+      }))
+      .collect()
+  }
+  /// Whether this cell is equivalent to `other` under the names mapping
+  fn is_equivalent_under<C2: 'static + Ctx>(
+    &self,
+    other: &Cell<C2>,
+    map: &HashMap<&str, &str>,
+  ) -> bool {
+    let variables = other.extra_ctx.logic_variables();
+    self.pin.iter().all(|pin| {
+      map
+        .get(pin.name.as_str())
+        .and_then(|other_pin| other.pin.get(*other_pin))
+        .is_some_and(|other_pin| {
+          pin.direction == other_pin.direction
+            && function_eq(
+              pin.function.as_ref(),
+              other_pin.function.as_ref(),
+              map,
+              variables,
+            )
+            && function_eq(
+              pin.three_state.as_ref(),
+              other_pin.three_state.as_ref(),
+              map,
+              variables,
+            )
+        })
+    }) && sequential_eq(&self.sequentials(), &other.sequentials(), map, variables)
+      && statetable_eq(self.statetable.as_ref(), other.statetable.as_ref(), map)
+  }
+  /// Find the pin correspondence to make this cell functionally equivalent to `other`,
+  /// which considers the pin `direction`, `function` and `three_state`,
+  /// and the sequential behavior of `ff` / `ff_bank` / `latch` / `latch_bank` / `statetable`.
+  ///
+  /// Return the `(pin, other_pin)` pairs in the pin order of this cell,
+  /// or `None` when there is no such mapping.
+  /// The cells with `bus` or `bundle` are not supported, and at most
+  /// [`MAX_PIN_MAPPINGS`] candidate mappings are tried.
+  #[must_use]
+  #[inline]
+  pub fn equivalent_pin_map<C2: 'static + Ctx>(
+    &self,
+    other: &Cell<C2>,
+  ) -> Option<Vec<(String, String)>> {
+    if !(self.bus.is_empty()
+      && self.bundle.is_empty()
+      && other.bus.is_empty()
+      && other.bundle.is_empty())
+      || self.pin.len() != other.pin.len()
+      || self.statetable.is_some() != other.statetable.is_some()
+    {
+      return None;
+    }
+    let nodes = self.equivalent_nodes();
+    let other_nodes = other.equivalent_nodes();
+    if nodes.len() != other_nodes.len() {
+      return None;
+    }
+    // group the nodes into classes, only the nodes in the same class can be mapped
+    let mut classes: Vec<(Vec<&Node<'_>>, Vec<&Node<'_>>)> = Vec::new();
+    for node in &nodes {
+      if let Some((class, _)) = classes
+        .iter_mut()
+        .find(|(class, _)| class.first().is_some_and(|first| first.same_class(node)))
+      {
+        class.push(node);
+      } else {
+        classes.push((vec![node], Vec::new()));
+      }
+    }
+    for other_node in &other_nodes {
+      let (_, other_class) = classes.iter_mut().find(|(class, _)| {
+        class.first().is_some_and(|first| first.same_class(other_node))
+      })?;
+      other_class.push(other_node);
+    }
+    if classes
+      .iter()
+      .any(|(class, other_class)| class.len() != other_class.len())
+    {
+      return None;
+    }
+    classes
+      .iter()
+      .map(|(_, other_class)| other_class.iter().permutations(other_class.len()))
+      .multi_cartesian_product()
+      .take(MAX_PIN_MAPPINGS)
+      .map(|permutations| {
+        classes
+          .iter()
+          .zip(permutations)
+          .flat_map(|((class, _), permutation)| class.iter().zip(permutation))
+          .flat_map(|(node, other_node)| {
+            node.names.iter().copied().zip(other_node.names.iter().copied())
+          })
+          .collect::<HashMap<&str, &str>>()
+      })
+      .find(|map| self.is_equivalent_under(other, map))
+      .map(|map| {
+        self
+          .pin
+          .iter()
+          .filter_map(|pin| {
+            map
+              .get(pin.name.as_str())
+              .map(|other_pin| (pin.name.clone(), (*other_pin).to_owned()))
+          })
+          .collect()
+      })
+  }
+}
+
+impl<C: 'static + Ctx> Library<C> {
+  /// For each cell in this library, find the cells in `other` library with
+  /// the same function and a full pin mapping, see [`Cell::equivalent_pin_map`].
+  ///
+  /// Each candidate contains the pin correspondence, and the comparison of
+  /// `area`, input pin `capacitance` and representative delay.
+  #[must_use]
+  #[inline]
+  pub fn equivalent_cells<C2: 'static + Ctx>(
+    &self,
+    other: &Library<C2>,
+  ) -> Vec<EquivalentCell> {
+    self
+      .cell
+      .iter()
+      .flat_map(|cell| {
+        other.cell.iter().filter_map(move |other_cell| {
+          cell.equivalent_pin_map(other_cell).map(|pin_map| EquivalentCell {
+            cell: cell.name.clone(),
+            other_cell: other_cell.name.clone(),
+            pin_map: pin_map
+              .into_iter()
+              .map(|(pin, other_pin)| PinMatch {
+                capacitance: Comparison {
+                  this: cell.pin.get(pin.as_str()).and_then(|p| p.capacitance),
+                  other: other_cell
+                    .pin
+                    .get(other_pin.as_str())
+                    .and_then(|p| p.capacitance),
+                },
+                pin,
+                other_pin,
+              })
+              .collect(),
+            area: Comparison { this: cell.area, other: other_cell.area },
+            delay: Comparison {
+              this: representative_delay(cell),
+              other: representative_delay(other_cell),
+            },
+          })
+        })
+      })
+      .collect()
+  }
+}
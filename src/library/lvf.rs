@@ -0,0 +1,154 @@
+//! Rewrite the LVF tables of a library between the moments form and
+//! the early/late sigma form, see [`Library::convert_lvf`].
+#![allow(clippy::multiple_inherent_impl)]
+use super::{
+  Library,
+  table_check::{AnyTable, visit_tables_mut},
+};
+use crate::{
+  Ctx,
+  timing::{LVFEarlyLate, LVFMoments},
+};
+use core::iter::zip;
+
+/// The LVF form of timing tables, see [`Library::convert_lvf`]
+#[derive(Debug, Clone, Copy)]
+#[derive(Hash, PartialEq, Eq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub enum LvfForm {
+  /// `ocv_mean_shift_*`, `ocv_std_dev_*` and `ocv_skewness_*`
+  Moments,
+  /// `ocv_sigma_*` with `sigma_type` of `early` and `late`
+  EarlyLate,
+}
+
+/// The conversion of one table, see [`Library::convert_lvf`]
+#[derive(Debug, Clone)]
+#[derive(PartialEq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct LvfConversion {
+  /// The path of table, e.g., `cell(INV)/pin(Y)/timing(A)[0]/cell_rise`
+  pub path: String,
+  /// The indices of points failed to convert, the table is kept unchanged
+  /// when it is not empty
+  pub failed: Vec<usize>,
+  /// The maximum absolute difference between the source values and the ones
+  /// converted back, i.e., of `mean`, `std_dev` and `skewness` from moments,
+  /// or of `early_sigma` and `late_sigma` from early/late.
+  /// From moments, the way back takes the nominal value as the mean,
+  /// so it is at least the largest mean shift lost by the conversion
+  pub round_trip_error: f64,
+}
+
+impl LvfConversion {
+  /// Whether all the points are converted
+  #[inline]
+  #[must_use]
+  pub const fn converted(&self) -> bool {
+    self.failed.is_empty()
+  }
+}
+
+/// Convert the moments of each point with its `nominal` value,
+/// return the converted ones or the failed indices, and the round-trip error
+#[expect(clippy::float_arithmetic)]
+fn moments_to_early_late(
+  nominal: &[f64],
+  moments: &[LVFMoments],
+) -> (Result<Vec<LVFEarlyLate>, Vec<usize>>, f64) {
+  let mut converted = Vec::with_capacity(moments.len());
+  let mut failed = Vec::new();
+  let mut error = 0.0_f64;
+  for (i, (value, moment)) in zip(nominal, moments).enumerate() {
+    match moment.to_early_late(*value) {
+      Some(early_late) => {
+        // the way back takes the nominal value as the mean, as `early_late_to_moments`
+        if let Some(back) = early_late.to_moments(*value, *value) {
+          error = error
+            .max((back.mean - moment.mean).abs())
+            .max((back.std_dev - moment.std_dev).abs())
+            .max((back.skewness - moment.skewness).abs());
+        }
+        converted.push(early_late);
+      }
+      None => failed.push(i),
+    }
+  }
+  (if failed.is_empty() { Ok(converted) } else { Err(failed) }, error)
+}
+
+/// Convert the sigmas of each point with its `nominal` value as the mean,
+/// return the converted ones or the failed indices, and the round-trip error
+#[expect(clippy::float_arithmetic)]
+fn early_late_to_moments(
+  nominal: &[f64],
+  early_late: &[LVFEarlyLate],
+) -> (Result<Vec<LVFMoments>, Vec<usize>>, f64) {
+  let mut converted = Vec::with_capacity(early_late.len());
+  let mut failed = Vec::new();
+  let mut error = 0.0_f64;
+  for (i, (value, sigma)) in zip(nominal, early_late).enumerate() {
+    match sigma.to_moments(*value, *value) {
+      Some(moments) => {
+        if let Some(back) = moments.to_early_late(*value) {
+          error = error
+            .max((back.early_sigma - sigma.early_sigma).abs())
+            .max((back.late_sigma - sigma.late_sigma).abs());
+        }
+        converted.push(moments);
+      }
+      None => failed.push(i),
+    }
+  }
+  (if failed.is_empty() { Ok(converted) } else { Err(failed) }, error)
+}
+
+impl<C: 'static + Ctx> Library<C> {
+  /// Rewrite the LVF data of all timing tables into the form `to`, the tables
+  /// without the data of the other form are skipped.
+  ///
+  /// Each point is converted by [`LVFMoments::to_early_late`] with its nominal
+  /// value, or by [`LVFEarlyLate::to_moments`] with its nominal value as the
+  /// mean, since the sigmas carry no mean shift. When any point fails, the
+  /// table is kept unchanged.
+  ///
+  /// So converting moments to early/late and back is lossy by design: the mean
+  /// shift is dropped and absorbed by the skewness, which is reported by
+  /// [`LvfConversion::round_trip_error`].
+  ///
+  /// Return the converted tables, in the order of cells and pins.
+  #[inline]
+  pub fn convert_lvf(&mut self, to: LvfForm) -> Vec<LvfConversion> {
+    let mut conversions = Vec::new();
+    visit_tables_mut(self, |path, _, _, table| {
+      let AnyTable::Timing(t) = table else {
+        return;
+      };
+      let (failed, round_trip_error) = match to {
+        LvfForm::EarlyLate if !t.lvf_moments_values.is_empty() => {
+          match moments_to_early_late(&t.values, &t.lvf_moments_values) {
+            (Ok(converted), error) => {
+              t.lvf_early_late_values = converted;
+              t.lvf_moments_values.clear();
+              (Vec::new(), error)
+            }
+            (Err(failed), error) => (failed, error),
+          }
+        }
+        LvfForm::Moments if !t.lvf_early_late_values.is_empty() => {
+          match early_late_to_moments(&t.values, &t.lvf_early_late_values) {
+            (Ok(converted), error) => {
+              t.lvf_moments_values = converted;
+              t.lvf_early_late_values.clear();
+              (Vec::new(), error)
+            }
+            (Err(failed), error) => (failed, error),
+          }
+        }
+        LvfForm::EarlyLate | LvfForm::Moments => return,
+      };
+      conversions.push(LvfConversion { path, failed, round_trip_error });
+    });
+    conversions
+  }
+}
@@ -2,7 +2,14 @@
 //! IFRAME('https://zao111222333.github.io/liberty-db/2020.09/reference_manual.html');
 //! </script>
 
+mod equivalent;
 mod items;
+mod lvf;
+mod monte_carlo;
+#[cfg(feature = "lut_template")]
+mod nldm;
+mod ocv;
+mod table_check;
 mod test;
 use crate::{
   Ctx,
@@ -18,8 +25,15 @@ use crate::{
 };
 use alloc::borrow::Cow;
 use core::fmt::{self, Write as _};
+pub use equivalent::{Comparison, EquivalentCell, MAX_PIN_MAPPINGS, PinMatch};
 pub use items::*;
+pub use lvf::{LvfConversion, LvfForm};
+pub use monte_carlo::{MonteCarloMismatch, MonteCarloMismatchKind};
+#[cfg(feature = "lut_template")]
+pub use nldm::NldmReport;
+pub use ocv::{AocvTable, OcvDerates, OcvReference, PocvCoefficient};
 use std::path::Path;
+pub use table_check::TableIssue;
 
 /// The first line of the library group statement names the library.
 ///
@@ -993,6 +1007,24 @@ impl<C: 'static + Ctx> GroupFn<C> for Library<C> {
           (lut.name.clone(), Arc::new(lut))
         })
         .collect();
+      scope.noise_lut_template = builder
+        .noise_lut_template
+        .iter()
+        .map(|_lut| {
+          let lut =
+            <TableTemple<C> as ParsingBuilder<C>>::build(_lut.clone(), &mut empty_scope);
+          (lut.name.clone(), Arc::new(lut))
+        })
+        .collect();
+      scope.noise_propagation_template = builder
+        .propagation_lut_template
+        .iter()
+        .map(|_lut| {
+          let lut =
+            <TableTemple<C> as ParsingBuilder<C>>::build(_lut.clone(), &mut empty_scope);
+          (lut.name.clone(), Arc::new(lut))
+        })
+        .collect();
       scope.current_template = builder
         .output_current_template
         .iter()
@@ -1014,6 +1046,24 @@ impl<C: 'static + Ctx> GroupFn<C> for Library<C> {
           (lut.name.clone(), Arc::new(lut))
         })
         .collect();
+      scope.poly_template = builder
+        .poly_template
+        .iter()
+        .map(|_lut| {
+          let lut =
+            <PolyTemplate<C> as ParsingBuilder<C>>::build(_lut.clone(), &mut empty_scope);
+          (lut.name.clone(), Arc::new(lut))
+        })
+        .collect();
+      scope.power_poly_template = builder
+        .power_poly_template
+        .iter()
+        .map(|_lut| {
+          let lut =
+            <PolyTemplate<C> as ParsingBuilder<C>>::build(_lut.clone(), &mut empty_scope);
+          (lut.name.clone(), Arc::new(lut))
+        })
+        .collect();
     }
   }
 }
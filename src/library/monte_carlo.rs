@@ -0,0 +1,120 @@
+//! Aggregate the libraries of Monte-Carlo runs into the LVF moments of
+//! the nominal library, see [`Library::aggregate_monte_carlo`].
+#![allow(clippy::multiple_inherent_impl)]
+use super::{
+  Library,
+  table_check::{AnyTable, visit_tables, visit_tables_mut},
+};
+use crate::{Ctx, timing::LVFMoments};
+use core::convert::Infallible;
+use std::collections::{HashMap, HashSet};
+
+/// The `index_1`, `index_2` and `values` of a timing table in a run
+type Samples = (Vec<f64>, Vec<f64>, Vec<f64>);
+
+/// The structural mismatch of a Monte-Carlo run, see [`MonteCarloMismatch`]
+#[derive(Debug, Clone, Copy)]
+#[derive(Hash, PartialEq, Eq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub enum MonteCarloMismatchKind {
+  /// The table of nominal library is missing in the run
+  Missing,
+  /// The table of the run is not in the nominal library
+  Extra,
+  /// The `index_1` / `index_2` or the number of values differs from the nominal one
+  Index,
+}
+
+/// A table of a Monte-Carlo run that mismatches the nominal library,
+/// see [`Library::aggregate_monte_carlo`]
+#[derive(Debug, Clone)]
+#[derive(PartialEq, Eq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct MonteCarloMismatch {
+  /// The path of table, e.g., `cell(INV)/pin(Y)/timing(A)[0]/cell_rise`
+  pub path: String,
+  /// The index of run in `runs`
+  pub run: usize,
+  /// The kind of mismatch
+  pub kind: MonteCarloMismatchKind,
+}
+
+impl<C: 'static + Ctx> Library<C> {
+  /// Aggregate the Monte-Carlo `runs` into this nominal library.
+  ///
+  /// Each timing table is matched with the tables of the same path in the runs,
+  /// and its `lvf_moments_values` is replaced by the per-point
+  /// [`LVFMoments::estimate`] over the matched ones, so that the
+  /// `ocv_mean_shift_*`, `ocv_std_dev_*` and `ocv_skewness_*` tables are written
+  /// with it. The values of this library are kept as the nominal ones.
+  ///
+  /// The mismatched tables are excluded from the statistics, and returned in
+  /// the order of tables then runs; the extra tables of runs come last.
+  #[inline]
+  pub fn aggregate_monte_carlo(&mut self, runs: &[Self]) -> Vec<MonteCarloMismatch> {
+    let run_tables: Vec<HashMap<String, Samples>> = runs
+      .iter()
+      .map(|run| {
+        let mut tables = HashMap::new();
+        visit_tables(run, |path, _, _, table| {
+          if let AnyTable::Timing(t) = table {
+            _ = tables
+              .insert(path, (t.index_1.clone(), t.index_2.clone(), t.values.clone()));
+          }
+        });
+        tables
+      })
+      .collect();
+    let mut mismatches = Vec::new();
+    let mut nominal_paths = HashSet::new();
+    visit_tables_mut(self, |path, _, _, table| {
+      let AnyTable::Timing(nominal) = table else {
+        return;
+      };
+      let mut matched = Vec::with_capacity(run_tables.len());
+      for (run, tables) in run_tables.iter().enumerate() {
+        let kind = match tables.get(&path) {
+          None => MonteCarloMismatchKind::Missing,
+          Some((index_1, index_2, values))
+            if *index_1 != nominal.index_1
+              || *index_2 != nominal.index_2
+              || values.len() != nominal.values.len() =>
+          {
+            MonteCarloMismatchKind::Index
+          }
+          Some((_, _, values)) => {
+            matched.push(values);
+            continue;
+          }
+        };
+        mismatches.push(MonteCarloMismatch { path: path.clone(), run, kind });
+      }
+      if !matched.is_empty() {
+        nominal.lvf_moments_values = (0..nominal.values.len())
+          .map(|i| {
+            LVFMoments::estimate(
+              matched
+                .iter()
+                .filter_map(|values| values.get(i).copied().map(Ok::<_, Infallible>)),
+            )
+            .ok()
+            .flatten()
+            .unwrap_or_default()
+          })
+          .collect();
+      }
+      _ = nominal_paths.insert(path);
+    });
+    for (run, tables) in run_tables.iter().enumerate() {
+      let mut extra: Vec<_> =
+        tables.keys().filter(|path| !nominal_paths.contains(*path)).collect();
+      extra.sort_unstable();
+      mismatches.extend(extra.into_iter().map(|path| MonteCarloMismatch {
+        path: path.clone(),
+        run,
+        kind: MonteCarloMismatchKind::Extra,
+      }));
+    }
+    mismatches
+  }
+}
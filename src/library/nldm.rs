@@ -0,0 +1,200 @@
+//! Derive the NLDM timing tables of a library from its CCS waveforms,
+//! see [`Library::nldm_from_ccs`].
+#![allow(clippy::multiple_inherent_impl)]
+use super::{Library, VoltageMap};
+use crate::{
+  Ctx, MutSetExt as _,
+  ast::LibertySet,
+  cell::PgPin,
+  table::{CcsNldm, NldmError, NldmTable, TableCtx as _, TableTemple, TimingThresholds},
+  timing::{Timing, TimingTableLookUp},
+};
+use alloc::sync::Arc;
+
+/// One table of [`Library::nldm_from_ccs`]
+#[derive(Debug, Clone)]
+#[derive(PartialEq)]
+pub struct NldmReport {
+  /// The path of table, e.g., `cell(INV)/pin(Y)/timing(A)[0]/cell_rise`
+  pub path: String,
+  /// Whether the table is added, i.e., there was no such table
+  pub added: bool,
+  /// The maximum absolute difference between the existing table and the derived one
+  pub deviation: Option<f64>,
+  /// The error of derivation, the table is unchanged
+  pub error: Option<NldmError>,
+}
+
+/// Fill the missing table with the derived one, keep its template in `templates`,
+/// and report it
+fn apply<C: 'static + Ctx>(
+  path: String,
+  field: &mut Option<TimingTableLookUp<C>>,
+  result: Result<NldmTable<C>, NldmError>,
+  templates: &mut Vec<Arc<TableTemple<C>>>,
+) -> NldmReport {
+  match result {
+    Ok(NldmTable { table, deviation }) => {
+      let added = field.is_none();
+      if added {
+        templates.extend(table.extra_ctx.lut_template().clone());
+        *field = Some(table);
+      }
+      NldmReport { path, added, deviation, error: None }
+    }
+    Err(error) => NldmReport {
+      path,
+      added: false,
+      deviation: None,
+      error: Some(error),
+    },
+  }
+}
+
+/// The supply voltage of the output with `related_power_pin` and `related_ground_pin`
+/// in the cell with `pg_pin`, see [`Library::nldm_from_ccs`]
+fn supply_voltage<C: 'static + Ctx>(
+  voltage_map: &LibertySet<VoltageMap>,
+  nom_voltage: Option<f64>,
+  pg_pin: &LibertySet<PgPin<C>>,
+  related_power_pin: &str,
+  related_ground_pin: &str,
+) -> Result<f64, NldmError> {
+  let pg_voltage = |name: &str| {
+    voltage_map
+      .get(pg_pin.get(name)?.voltage_name.as_str())
+      .map(|map| map.voltage)
+  };
+  let high = pg_voltage(related_power_pin)
+    .or(nom_voltage)
+    .ok_or(NldmError::NoVoltage)?;
+  #[expect(clippy::float_arithmetic)]
+  Ok(high - pg_voltage(related_ground_pin).unwrap_or(0.0))
+}
+
+/// Add the new `template` to `templates`, unless there is one of the same name
+fn add_template<C: 'static + Ctx>(
+  templates: &mut LibertySet<TableTemple<C>>,
+  template: Arc<TableTemple<C>>,
+) {
+  if templates.get(template.name.as_str()).is_none() {
+    _ = templates.insert(Arc::unwrap_or_clone(template));
+  }
+}
+
+/// The derivation of all the tables of `timing` failed by `error`
+fn failed<C: 'static + Ctx>(timing: &Timing<C>, error: &NldmError) -> CcsNldm<C> {
+  let result = |ccs: bool| ccs.then(|| Err(error.clone()));
+  let (rise, fall) =
+    (timing.output_current_rise.is_some(), timing.output_current_fall.is_some());
+  CcsNldm {
+    cell_rise: result(rise),
+    cell_fall: result(fall),
+    rise_transition: result(rise),
+    fall_transition: result(fall),
+  }
+}
+
+/// Derive the tables of the timing arcs in `$group` of the cell with `$pg_pin`,
+/// with the closure `$derive` on the supply voltage given by `$rail`
+macro_rules! derive_timing {
+  ($group:expr, $pg_pin:expr, $prefix:expr, $rail:ident, $derive:ident, $reports:ident, $templates:ident) => {
+    let vdd = $rail($pg_pin, &$group.related_power_pin, &$group.related_ground_pin);
+    for (i, timing) in $group.timing.iter_mut().enumerate() {
+      let prefix = format!("{}/timing({})[{i}]", $prefix, timing.related_pin);
+      let derived: CcsNldm<C> = $derive(&timing, &vdd);
+      let tables = [
+        ("cell_rise", &mut timing.cell_rise, derived.cell_rise),
+        ("cell_fall", &mut timing.cell_fall, derived.cell_fall),
+        ("rise_transition", &mut timing.rise_transition, derived.rise_transition),
+        ("fall_transition", &mut timing.fall_transition, derived.fall_transition),
+      ];
+      for (key, field, result) in tables {
+        if let Some(derived_table) = result {
+          $reports.push(apply(
+            format!("{prefix}/{key}"),
+            field,
+            derived_table,
+            &mut $templates,
+          ));
+        }
+      }
+    }
+  };
+}
+
+/// Derive the tables of the pins in `$pins`
+macro_rules! derive_pins {
+  ($pins:expr, $pg_pin:expr, $prefix:expr, $rail:ident, $derive:ident, $reports:ident, $templates:ident) => {
+    for pin in $pins.iter_mut() {
+      let prefix = format!("{}/pin({})", $prefix, pin.name);
+      derive_timing!(pin, $pg_pin, prefix, $rail, $derive, $reports, $templates);
+    }
+  };
+}
+
+impl<C: 'static + Ctx> Library<C> {
+  /// Derive the NLDM `cell_rise/fall` and `rise/fall_transition` of all the timing
+  /// arcs with CCS `output_current_rise/fall`, by [`Timing::nldm_from_ccs`]
+  /// with the library thresholds.
+  ///
+  /// The supply voltage of an output pin is the `voltage_map` of the `voltage_name`
+  /// of its `related_power_pin`, or the `nom_voltage`, less the one of its
+  /// `related_ground_pin`, or 0, like [`Pin::noise_rails`](crate::pin::Pin::noise_rails).
+  ///
+  /// The missing tables are added on the grid of the CCS vectors of the arc,
+  /// or of the `lu_table_template` named `template` when it is given,
+  /// and the new templates of the CCS grids are added to `lu_table_template`.
+  /// The existing tables are kept, and their maximum deviation
+  /// from the derived ones is reported.
+  ///
+  /// # Errors
+  /// Return [`NldmError::NoTemplate`] when `template` is not found
+  #[inline]
+  pub fn nldm_from_ccs(
+    &mut self,
+    template: Option<&str>,
+  ) -> Result<Vec<NldmReport>, NldmError> {
+    let lut_template = template
+      .map(|name| {
+        self
+          .lu_table_template
+          .get(name)
+          .map(|found| Arc::new(found.clone()))
+          .ok_or_else(|| NldmError::NoTemplate(name.to_owned()))
+      })
+      .transpose()?;
+    let (rise, fall) = (TimingThresholds::rise(self), TimingThresholds::fall(self));
+    let derive = |timing: &Timing<C>, supply: &Result<f64, NldmError>| {
+      supply.as_ref().map_or_else(
+        |error| failed(timing, error),
+        |vdd| timing.nldm_from_ccs(lut_template.as_ref(), *vdd, &rise, &fall),
+      )
+    };
+    let (voltage_map, nom_voltage) = (&self.voltage_map, self.nom_voltage);
+    let rail = |pg_pin: &LibertySet<PgPin<C>>, power: &str, ground: &str| {
+      supply_voltage(voltage_map, nom_voltage, pg_pin, power, ground)
+    };
+    let mut reports = Vec::new();
+    let mut templates = Vec::new();
+    for cell in self.cell.iter_mut() {
+      let prefix = format!("cell({})", cell.name);
+      let pg_pin = &cell.pg_pin;
+      derive_pins!(cell.pin, pg_pin, prefix, rail, derive, reports, templates);
+      for bus in cell.bus.iter_mut() {
+        let bus_prefix = format!("{prefix}/bus({})", bus.name);
+        derive_timing!(bus, pg_pin, bus_prefix, rail, derive, reports, templates);
+        derive_pins!(bus.pin, pg_pin, bus_prefix, rail, derive, reports, templates);
+      }
+      for bundle in cell.bundle.iter_mut() {
+        let bundle_prefix = format!("{prefix}/bundle({})", bundle.name);
+        derive_timing!(bundle, pg_pin, bundle_prefix, rail, derive, reports, templates);
+        derive_pins!(bundle.pin, pg_pin, bundle_prefix, rail, derive, reports, templates);
+      }
+    }
+    for new_template in templates {
+      add_template(&mut self.lu_table_template, new_template);
+    }
+    Ok(reports)
+  }
+}
@@ -0,0 +1,239 @@
+//! Derive the POCV coefficients and AOCV derate tables of a library from its
+//! LVF data, and write them as side files, see [`Library::ocv_derates`].
+#![allow(clippy::multiple_inherent_impl)]
+use super::{
+  Library,
+  table_check::{AnyTable, visit_tables},
+};
+use crate::{Ctx, expression::logic, table::LookupConfig, timing::TimingTableLookUp};
+use alloc::collections::BTreeMap;
+use core::fmt::Write as _;
+
+/// The reference point to sample the LVF data, see [`Library::ocv_derates`]
+#[derive(Debug, Clone, Copy, Default)]
+#[derive(PartialEq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub enum OcvReference {
+  /// The middle points of `index_1` and `index_2`, i.e., the middle index value,
+  /// or the mean of the two middle ones for an even length
+  #[default]
+  Center,
+  /// The `(idx1, idx2)` point, e.g., the input transition and the output load
+  Point(f64, f64),
+}
+
+/// The POCV coefficient of a `cell_rise` / `cell_fall` table,
+/// see [`Library::ocv_derates`]
+#[derive(Debug, Clone)]
+#[derive(PartialEq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct PocvCoefficient {
+  /// The name of cell
+  pub cell: String,
+  /// The name of output pin
+  pub pin: String,
+  /// The `related_pin` of timing arc
+  pub related_pin: String,
+  /// The output edge, [`logic::Edge::R`] for `cell_rise`
+  pub edge: logic::Edge,
+  /// The nominal delay at the reference point
+  pub nominal: f64,
+  /// `early_sigma / nominal`
+  pub early: f64,
+  /// `late_sigma / nominal`
+  pub late: f64,
+}
+
+/// The AOCV derate table of a cell, see [`OcvDerates::aocv`]
+#[derive(Debug, Clone)]
+#[derive(PartialEq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct AocvTable {
+  /// The name of cell
+  pub cell: String,
+  /// The output edge
+  pub edge: logic::Edge,
+  /// The logic depths
+  pub depth: Vec<usize>,
+  /// The early derates (`<= 1`) of each depth
+  pub early: Vec<f64>,
+  /// The late derates (`>= 1`) of each depth
+  pub late: Vec<f64>,
+}
+
+/// The POCV coefficients of a library, see [`Library::ocv_derates`]
+#[derive(Debug, Clone)]
+#[derive(PartialEq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct OcvDerates {
+  /// The name of library, used in `object_spec` of side files
+  pub library: String,
+  /// The coefficients of timing arcs, in the order of cells and pins
+  pub coefficients: Vec<PocvCoefficient>,
+}
+
+impl OcvDerates {
+  /// The worst (largest) early and late coefficients over the arcs of each
+  /// cell and output edge, in the order of cell names
+  #[inline]
+  #[must_use]
+  pub fn cell_coefficients(&self) -> BTreeMap<(&str, logic::Edge), (f64, f64)> {
+    let mut cells = BTreeMap::<_, (f64, f64)>::new();
+    for c in &self.coefficients {
+      let worst = cells.entry((c.cell.as_str(), c.edge)).or_default();
+      *worst = (worst.0.max(c.early), worst.1.max(c.late));
+    }
+    cells
+  }
+  /// The AOCV derate tables of cells at logic `depths`, from
+  /// [`OcvDerates::cell_coefficients`] and the standard normal variable `z`,
+  /// e.g., `3.0` for the 3-sigma derates.
+  ///
+  /// The variations of `N` identical stages are independent, so the relative
+  /// sigma of the path is `coefficient / √N`, i.e.,
+  /// `late = 1 + z * late_coefficient / √N` and
+  /// `early = max(0, 1 - z * early_coefficient / √N)`.
+  /// The zero depths are ignored.
+  #[inline]
+  #[must_use]
+  #[expect(clippy::float_arithmetic, clippy::as_conversions, clippy::cast_precision_loss)]
+  pub fn aocv(&self, depths: &[usize], z: f64) -> Vec<AocvTable> {
+    let depth: Vec<usize> = depths.iter().copied().filter(|d| *d > 0).collect();
+    self
+      .cell_coefficients()
+      .into_iter()
+      .map(|((cell, edge), (early, late))| {
+        let derate = |coefficient: f64, sign: f64| {
+          depth
+            .iter()
+            .map(|n| (sign * z * coefficient).mul_add(1.0 / (*n as f64).sqrt(), 1.0))
+            .collect::<Vec<_>>()
+        };
+        AocvTable {
+          cell: cell.to_owned(),
+          edge,
+          depth: depth.clone(),
+          early: derate(early, -1.0).into_iter().map(|d| d.max(0.0)).collect(),
+          late: derate(late, 1.0),
+        }
+      })
+      .collect()
+  }
+  /// The POCV side file in the `PrimeTime` format, one entry per cell, output
+  /// edge and derate type with the coefficients of [`OcvDerates::cell_coefficients`]
+  #[inline]
+  #[must_use]
+  pub fn pocv_side_file(&self) -> String {
+    let mut s = String::from("version: 4.0\n");
+    for ((cell, edge), (early, late)) in self.cell_coefficients() {
+      for (derate_type, coefficient) in [("early", early), ("late", late)] {
+        _ = write!(
+          s,
+          "\nocvm_type: pocvm\nobject_type: lib_cell\nrf_type: {}\ndelay_type: cell\nderate_type: {derate_type}\nobject_spec: {}/{cell}\ncoefficient: {coefficient}\n",
+          rf_type(edge),
+          self.library,
+        );
+      }
+    }
+    s
+  }
+  /// The AOCV side file in the `PrimeTime` format of [`OcvDerates::aocv`],
+  /// one depth-based table per cell, output edge and derate type
+  #[inline]
+  #[must_use]
+  pub fn aocv_side_file(&self, depths: &[usize], z: f64) -> String {
+    let join = |values: &[f64]| {
+      values.iter().map(ToString::to_string).collect::<Vec<_>>().join(" ")
+    };
+    let mut s = String::from("version: 4.0\n");
+    for table in self.aocv(depths, z) {
+      let depth = table.depth.iter().map(ToString::to_string).collect::<Vec<_>>();
+      for (derate_type, derates) in [("early", &table.early), ("late", &table.late)] {
+        _ = write!(
+          s,
+          "\nocvm_type: aocvm\nobject_type: lib_cell\nrf_type: {}\ndelay_type: cell\nderate_type: {derate_type}\npath_type: clock data\nobject_spec: {}/{}\ndepth: {}\ntable: {}\n",
+          rf_type(table.edge),
+          self.library,
+          table.cell,
+          depth.join(" "),
+          join(derates),
+        );
+      }
+    }
+    s
+  }
+}
+
+/// The `rf_type` of side files
+const fn rf_type(edge: logic::Edge) -> &'static str {
+  match edge {
+    logic::Edge::R => "rise",
+    logic::Edge::F => "fall",
+  }
+}
+
+/// The POCV coefficient of `table` at `reference`, `None` without valid LVF data
+#[expect(clippy::float_arithmetic)]
+fn coefficient<C: 'static + Ctx>(
+  table: &TimingTableLookUp<C>,
+  reference: OcvReference,
+) -> Option<(f64, f64, f64)> {
+  let (idx1, idx2) = match reference {
+    OcvReference::Center => {
+      // the same point for an odd length
+      let center = |index: &[f64]| {
+        let lower = index.get(index.len().saturating_sub(1) >> 1).copied().unwrap_or(0.0);
+        let upper = index.get(index.len() >> 1).copied().unwrap_or(0.0);
+        f64::midpoint(lower, upper)
+      };
+      (center(&table.index_1), center(&table.index_2))
+    }
+    OcvReference::Point(idx1, idx2) => (idx1, idx2),
+  };
+  let config = LookupConfig::default();
+  let nominal = table.lookup_with(idx1, idx2, config).ok()?.value;
+  let distribution = table.lvf_distribution(idx1, idx2, config).ok()?;
+  if nominal <= 0.0 || nominal.is_nan() {
+    return None;
+  }
+  let early = (nominal - distribution.quantile(-3.0)) / 3.0;
+  let late = (distribution.quantile(3.0) - nominal) / 3.0;
+  Some((nominal, early.max(0.0) / nominal, late.max(0.0) / nominal))
+}
+
+impl<C: 'static + Ctx> Library<C> {
+  /// The POCV coefficients of all the `cell_rise` / `cell_fall` tables with
+  /// LVF data, i.e., `sigma / nominal` at the `reference` point.
+  ///
+  /// The sigmas are the ±3σ quantiles of [`TimingTableLookUp::lvf_distribution`]
+  /// divided by 3, so the LVF moments and the early/late sigmas are both supported.
+  /// The tables without valid LVF data or with non-positive nominal delay are
+  /// skipped, see [`Library::check_tables`] for their defects.
+  #[inline]
+  #[must_use]
+  pub fn ocv_derates(&self, reference: OcvReference) -> OcvDerates {
+    let mut coefficients = Vec::new();
+    visit_tables(self, |_, key, owner, table| {
+      let AnyTable::Timing(t) = table else {
+        return;
+      };
+      let edge = match key {
+        "cell_rise" => logic::Edge::R,
+        "cell_fall" => logic::Edge::F,
+        _ => return,
+      };
+      if let Some((nominal, early, late)) = coefficient(t, reference) {
+        coefficients.push(PocvCoefficient {
+          cell: owner.cell.to_owned(),
+          pin: owner.pin.to_owned(),
+          related_pin: owner.related_pin.to_owned(),
+          edge,
+          nominal,
+          early,
+          late,
+        });
+      }
+    });
+    OcvDerates { library: self.name.join(" "), coefficients }
+  }
+}
@@ -0,0 +1,251 @@
+//! Check all the lookup tables in a library, and repair their values,
+//! see [`TableDefect`] for the defects.
+#![allow(clippy::multiple_inherent_impl)]
+use super::Library;
+use crate::{
+  Ctx, Item, MutSetExt as _,
+  cell::Cell,
+  table::{TableCheck, TableDefect, TableLookUp, TableRepair},
+  timing::TimingTableLookUp,
+};
+
+/// The defects of one table, see [`Library::check_tables`]
+#[derive(Debug, Clone)]
+#[derive(PartialEq, Eq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct TableIssue {
+  /// The path of table, e.g., `cell(INV)/pin(Y)/timing(A)[0]/cell_rise`
+  pub path: String,
+  /// The defects found before repair
+  pub defects: Vec<TableDefect>,
+  /// Whether any value is changed by [`Library::repair_tables`]
+  pub repaired: bool,
+  /// The defects remaining after repair
+  pub remaining: Vec<TableDefect>,
+}
+
+/// A table to visit
+pub(super) enum AnyTable<Timing, Table> {
+  Timing(Timing),
+  Table(Table),
+}
+
+/// The owners of a table to visit
+#[derive(Clone, Copy)]
+#[expect(clippy::field_scoped_visibility_modifiers)]
+pub(super) struct TableOwner<'a> {
+  /// The name of cell
+  pub(super) cell: &'a str,
+  /// The name of pin, bus or bundle
+  pub(super) pin: &'a str,
+  /// The `related_pin` of the `timing` / `internal_power` group
+  pub(super) related_pin: &'a str,
+}
+
+/// Visit all the groups in `$set`
+macro_rules! visit_groups {
+  ($iter:ident, $set:expr, $group:ident, $body:block) => {
+    for $group in $set.$iter() $body
+  };
+}
+
+/// Visit all the tables of pins in `$pins`
+macro_rules! visit_pins {
+  ($iter:ident, $pins:expr, $prefix:expr, $cell:expr, $visit:ident) => {
+    for pin in $pins.$iter() {
+      let pin_name = pin.name.clone();
+      let prefix = format!("{}/pin({pin_name})", $prefix);
+      visit_timing_power!($iter, pin, prefix, $cell, &pin_name, $visit);
+    }
+  };
+}
+
+/// Visit the tables of `timing` and `internal_power` in `$pin`
+macro_rules! visit_timing_power {
+  ($iter:ident, $pin:expr, $prefix:expr, $cell:expr, $pin_name:expr, $visit:ident) => {
+    visit_timing!($iter, $pin, $prefix, $cell, $pin_name, $visit);
+    for (i, power) in $pin.internal_power.$iter().enumerate() {
+      let related_pin = power.related_pin.to_string();
+      let prefix = format!("{}/internal_power({related_pin})[{i}]", $prefix);
+      let owner = TableOwner {
+        cell: $cell,
+        pin: $pin_name,
+        related_pin: &related_pin,
+      };
+      visit_fields!(
+        $iter, power, prefix, owner, $visit, Table, rise_power, fall_power, power
+      );
+    }
+  };
+}
+
+/// Visit the tables of `timing` in `$pin`
+macro_rules! visit_timing {
+  ($iter:ident, $pin:expr, $prefix:expr, $cell:expr, $pin_name:expr, $visit:ident) => {
+    for (i, timing) in $pin.timing.$iter().enumerate() {
+      let related_pin = timing.related_pin.to_string();
+      let prefix = format!("{}/timing({related_pin})[{i}]", $prefix);
+      let owner = TableOwner {
+        cell: $cell,
+        pin: $pin_name,
+        related_pin: &related_pin,
+      };
+      visit_fields!(
+        $iter,
+        timing,
+        prefix,
+        owner,
+        $visit,
+        Timing,
+        cell_rise,
+        cell_fall,
+        rise_transition,
+        fall_transition,
+        rise_constraint,
+        fall_constraint,
+        retaining_rise,
+        retaining_fall,
+        retain_rise_slew,
+        retain_fall_slew
+      );
+    }
+  };
+}
+
+/// Visit the `Option` table fields
+macro_rules! visit_fields {
+  (iter, $group:expr, $prefix:expr, $owner:expr, $visit:ident, $kind:ident, $($field:ident),+) => {
+    visit_fields!(@visit [$((stringify!($field), &$group.$field)),+], $prefix, $owner, $visit, $kind)
+  };
+  (iter_mut, $group:expr, $prefix:expr, $owner:expr, $visit:ident, $kind:ident, $($field:ident),+) => {
+    visit_fields!(@visit [$((stringify!($field), &mut $group.$field)),+], $prefix, $owner, $visit, $kind)
+  };
+  (@visit $fields:expr, $prefix:expr, $owner:expr, $visit:ident, $kind:ident) => {
+    for (key, field) in $fields {
+      if let Some(table) = field {
+        $visit(format!("{}/{key}", $prefix), key, $owner, AnyTable::$kind(table));
+      }
+    }
+  };
+}
+
+duplicate::duplicate! {
+  [
+    visit_tables       visit_buses       visit_bundles       iter       reference(T)  cell_type;
+    [visit_tables]     [visit_buses]     [visit_bundles]     [iter]     [&T]          [Cell<C>];
+    [visit_tables_mut] [visit_buses_mut] [visit_bundles_mut] [iter_mut] [&mut T]
+      [<Cell<C> as Item>::IdReadonlyItem];
+  ]
+  /// Visit all the tables of cells, with the path, the group name and the owners of table
+  pub(super) fn visit_tables<C: 'static + Ctx, F>(
+    library: reference([Library<C>]),
+    mut visit: F,
+  )
+  where
+    F: FnMut(
+      String,
+      &str,
+      TableOwner<'_>,
+      AnyTable<reference([TimingTableLookUp<C>]), reference([TableLookUp<C>])>,
+    ),
+  {
+    visit_groups!(iter, library.cell, cell, {
+      let cell_name = cell.name.clone();
+      let prefix = format!("cell({cell_name})");
+      visit_pins!(iter, cell.pin, prefix, &cell_name, visit);
+      visit_buses(&prefix, cell, &mut visit);
+      visit_bundles(&prefix, cell, &mut visit);
+    });
+  }
+  /// Visit the tables of buses
+  fn visit_buses<C: 'static + Ctx, F>(prefix: &str, cell: reference([cell_type]), visit: &mut F)
+  where
+    F: FnMut(
+      String,
+      &str,
+      TableOwner<'_>,
+      AnyTable<reference([TimingTableLookUp<C>]), reference([TableLookUp<C>])>,
+    ),
+  {
+    let cell_name = cell.name.clone();
+    visit_groups!(iter, cell.bus, bus, {
+      let bus_name = bus.name.clone();
+      let bus_prefix = format!("{prefix}/bus({bus_name})");
+      visit_timing_power!(iter, bus, bus_prefix, &cell_name, &bus_name, visit);
+      visit_pins!(iter, bus.pin, bus_prefix, &cell_name, visit);
+    });
+  }
+  /// Visit the tables of bundles
+  fn visit_bundles<C: 'static + Ctx, F>(prefix: &str, cell: reference([cell_type]), visit: &mut F)
+  where
+    F: FnMut(
+      String,
+      &str,
+      TableOwner<'_>,
+      AnyTable<reference([TimingTableLookUp<C>]), reference([TableLookUp<C>])>,
+    ),
+  {
+    let cell_name = cell.name.clone();
+    visit_groups!(iter, cell.bundle, bundle, {
+      let bundle_name = bundle.name.clone();
+      let bundle_prefix = format!("{prefix}/bundle({bundle_name})");
+      visit_timing!(iter, bundle, bundle_prefix, &cell_name, &bundle_name, visit);
+      visit_pins!(iter, bundle.pin, bundle_prefix, &cell_name, visit);
+    });
+  }
+}
+
+impl<C: 'static + Ctx> Library<C> {
+  /// Check all the `timing` and `internal_power` tables of pins, buses and bundles,
+  /// the expected properties of each table are decided by [`TableCheck::for_table`].
+  ///
+  /// Return the tables with defects, in the order of cells and pins.
+  #[must_use]
+  #[inline]
+  pub fn check_tables(&self) -> Vec<TableIssue> {
+    let mut issues = Vec::new();
+    visit_tables(self, |path, key, _, table| {
+      let check = TableCheck::for_table(key);
+      let defects = match table {
+        AnyTable::Timing(t) => t.check(check),
+        AnyTable::Table(t) => t.check(check),
+      };
+      if !defects.is_empty() {
+        issues.push(TableIssue {
+          path,
+          remaining: defects.clone(),
+          defects,
+          repaired: false,
+        });
+      }
+    });
+    issues
+  }
+  /// Check all the tables like [`Library::check_tables`],
+  /// and repair the defected ones with the strategies in `repair`.
+  ///
+  /// Return the tables with defects before repair, and the remaining defects.
+  #[inline]
+  pub fn repair_tables(&mut self, repair: TableRepair) -> Vec<TableIssue> {
+    let mut issues = Vec::new();
+    visit_tables_mut(self, |path, key, _, table| {
+      let check = TableCheck::for_table(key);
+      let (defects, repaired, remaining) = match table {
+        AnyTable::Timing(t) => {
+          let defects = t.check(check);
+          let repaired = !defects.is_empty() && t.repair(check, repair);
+          (defects, repaired, t.check(check))
+        }
+        AnyTable::Table(t) => {
+          let defects = t.check(check);
+          let repaired = !defects.is_empty() && t.repair(check, repair);
+          (defects, repaired, t.check(check))
+        }
+      };
+      if !defects.is_empty() {
+        issues.push(TableIssue { path, defects, repaired, remaining });
+      }
+    });
+    issues
+  }
+}
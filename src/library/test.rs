@@ -5,6 +5,8 @@ use crate::{
   DefaultCtx,
   ast::{AttriValues, SimpleDefined},
   common::f64_eq,
+  expression::logic,
+  table,
 };
 
 use super::*;
@@ -418,3 +420,979 @@ library (test) {
   assert_eq!(bus_type_ctx.bit_to, 3);
   assert_eq!(bus_type_ctx.bit_width, 4);
 }
+
+#[test]
+fn equivalent_cells() {
+  let library = Library::<DefaultCtx>::parse_lib(
+    r#"library (lib_a) {
+  cell (NAND2) {
+    area : 2.0;
+    pin (A1) { direction : input; capacitance : 0.002; }
+    pin (A2) { direction : input; capacitance : 0.003; }
+    pin (ZN) {
+      direction : output;
+      function : "!(A1&A2)";
+      timing () {
+        related_pin : "A1";
+        cell_rise (scalar) { values ("0.1"); }
+        cell_fall (scalar) { values ("0.3"); }
+      }
+    }
+  }
+  cell (DFFR) {
+    ff (IQ, IQN) {
+      next_state : "D";
+      clocked_on : "CP";
+      clear : "!CDN";
+    }
+    pin (D) { direction : input; }
+    pin (CP) { direction : input; clock : true; }
+    pin (CDN) { direction : input; }
+    pin (Q) { direction : output; function : "IQ"; }
+  }
+  cell (LATST) {
+    statetable ("D E", IQ) {
+      table : "H H : - : H ,\
+               L H : - : L ,\
+               - L : - : N ";
+    }
+    pin (D) { direction : input; }
+    pin (E) { direction : input; }
+    pin (IQ) { direction : internal; internal_node : IQ; }
+    pin (Q) { direction : output; function : "IQ"; }
+  }
+}"#,
+    None,
+  )
+  .unwrap();
+  let other = Library::<DefaultCtx>::parse_lib(
+    r#"library (lib_b) {
+  cell (ND2) {
+    area : 3.0;
+    pin (A) { direction : input; capacitance : 0.004; }
+    pin (B) { direction : input; capacitance : 0.001; }
+    pin (Y) {
+      direction : output;
+      function : "!(B*A)";
+      timing () {
+        related_pin : "A";
+        cell_rise (scalar) { values ("0.4"); }
+      }
+    }
+  }
+  cell (NR2) {
+    pin (A) { direction : input; }
+    pin (B) { direction : input; }
+    pin (Y) { direction : output; function : "!(A+B)"; }
+  }
+  cell (DFFRN) {
+    ff (S0, S1) {
+      next_state : "DIN";
+      clocked_on : "CK";
+      clear : "!RN";
+    }
+    pin (CK) { direction : input; clock : true; }
+    pin (DIN) { direction : input; }
+    pin (RN) { direction : input; }
+    pin (QO) { direction : output; function : "S0"; }
+  }
+  cell (DFFS) {
+    ff (S0, S1) {
+      next_state : "DIN";
+      clocked_on : "CK";
+      preset : "!SN";
+    }
+    pin (CK) { direction : input; clock : true; }
+    pin (DIN) { direction : input; }
+    pin (SN) { direction : input; }
+    pin (QO) { direction : output; function : "S0"; }
+  }
+  cell (LAT) {
+    statetable ("G DIN", Q0) {
+      table : "H H : - : H ,\
+               H L : - : L ,\
+               L H : - : N ,\
+               L L : L/H : L/H ";
+    }
+    pin (DIN) { direction : input; }
+    pin (G) { direction : input; }
+    pin (Q0) { direction : internal; internal_node : Q0; }
+    pin (Z) { direction : output; function : "Q0"; }
+  }
+  cell (LATN) {
+    statetable ("G DIN", Q0) {
+      table : "L H : - : H ,\
+               L L : - : L ,\
+               H - : - : N ";
+    }
+    pin (DIN) { direction : input; }
+    pin (G) { direction : input; }
+    pin (Q0) { direction : internal; internal_node : Q0; }
+    pin (Z) { direction : output; function : "Q0"; }
+  }
+}"#,
+    None,
+  )
+  .unwrap();
+  let equivalent = library.equivalent_cells(&other);
+  let pairs: Vec<_> = equivalent
+    .iter()
+    .map(|eq| (eq.cell.as_str(), eq.other_cell.as_str()))
+    .collect();
+  assert_eq!(pairs, vec![("DFFR", "DFFRN"), ("LATST", "LAT"), ("NAND2", "ND2")]);
+  let map = |eq: &EquivalentCell| -> Vec<(String, String)> {
+    eq.pin_map
+      .iter()
+      .map(|m| (m.pin.clone(), m.other_pin.clone()))
+      .collect()
+  };
+  let to_owned = |v: &[(&str, &str)]| -> Vec<(String, String)> {
+    v.iter().map(|(a, b)| ((*a).to_owned(), (*b).to_owned())).collect()
+  };
+  let nand2 = &equivalent[2];
+  assert_eq!(map(nand2).len(), 3);
+  assert_eq!(nand2.area, Comparison { this: Some(2.0), other: Some(3.0) });
+  assert_eq!(nand2.area.ratio(), Some(1.5));
+  assert!(f64_eq(nand2.delay.this.unwrap(), 0.2));
+  assert!(f64_eq(nand2.delay.other.unwrap(), 0.4));
+  assert!(nand2.pin_map.iter().any(|m| m.pin == "ZN"
+    && m.other_pin == "Y"
+    && m.capacitance == Comparison::default()));
+  let mut dffr = map(&equivalent[0]);
+  dffr.sort_unstable();
+  assert_eq!(dffr, to_owned(&[("CDN", "RN"), ("CP", "CK"), ("D", "DIN"), ("Q", "QO")]));
+  let mut latst = map(&equivalent[1]);
+  latst.sort_unstable();
+  assert_eq!(latst, to_owned(&[("D", "DIN"), ("E", "G"), ("IQ", "Q0"), ("Q", "Z")]));
+}
+
+#[test]
+fn check_tables() {
+  let mut library = Library::<DefaultCtx>::parse_lib(
+    r#"
+library(check) {
+  lu_table_template(delay_2x3) {
+    variable_1 : input_net_transition;
+    variable_2 : total_output_net_capacitance;
+    index_1 ("0.1, 0.2");
+    index_2 ("0.1, 0.2, 0.4");
+  }
+  power_lut_template(power_2x3) {
+    variable_1 : input_transition_time;
+    variable_2 : total_output_net_capacitance;
+    index_1 ("0.1, 0.2");
+    index_2 ("0.1, 0.2, 0.4");
+  }
+  cell (INV) {
+    pin (A) { direction : input; }
+    pin (Y) {
+      direction : output;
+      function : "!A";
+      timing () {
+        related_pin : "A";
+        cell_rise (delay_2x3) {
+          values ("1.0, 1.5, 1.4", "1.2, 1.8, 2.4");
+        }
+        rise_transition (delay_2x3) {
+          values ("-0.1, 0.5, 0.9", "0.2, 0.6, 1.0");
+        }
+        cell_fall (delay_2x3) {
+          index_2 ("0.1, 0.4, 0.2");
+          values ("1.0, 1.5, 2.0", "1.2, 1.8, 2.4");
+        }
+        fall_transition (delay_2x3) {
+          values ("0.1, 0.5, 0.9", "0.2, 0.6, 1.0");
+        }
+      }
+      internal_power () {
+        related_pin : "A";
+        rise_power (power_2x3) {
+          values ("0.1, 0.2, nan", "0.2, 0.3, 0.4");
+        }
+      }
+    }
+  }
+}"#,
+    None,
+  )
+  .unwrap();
+  let issues = library.check_tables();
+  let summary: Vec<_> = issues
+    .iter()
+    .map(|issue| (issue.path.as_str(), issue.defects.clone()))
+    .collect();
+  assert_eq!(
+    summary,
+    [
+      (
+        "cell(INV)/pin(Y)/timing(A)[0]/cell_rise",
+        vec![table::TableDefect::NonMonotonic { axis: 2, count: 1 }]
+      ),
+      (
+        "cell(INV)/pin(Y)/timing(A)[0]/cell_fall",
+        vec![table::TableDefect::UnsortedIndex(2)]
+      ),
+      (
+        "cell(INV)/pin(Y)/timing(A)[0]/rise_transition",
+        vec![table::TableDefect::NegativeValue(1)]
+      ),
+      (
+        "cell(INV)/pin(Y)/internal_power(A)[0]/rise_power",
+        vec![table::TableDefect::NonFiniteValue(1)]
+      ),
+    ]
+  );
+  let issues = library.repair_tables(table::TableRepair { clamp: true, monotone: true });
+  let summary: Vec<_> = issues
+    .iter()
+    .map(|issue| (issue.repaired, issue.remaining.is_empty()))
+    .collect();
+  assert_eq!(summary, [(true, true), (false, false), (true, true), (false, false)]);
+  let timing = library
+    .cell
+    .get("INV")
+    .unwrap()
+    .pin
+    .get("Y")
+    .unwrap()
+    .timing
+    .iter()
+    .next()
+    .unwrap();
+  let cell_rise = &timing.cell_rise.as_ref().unwrap().values;
+  assert!(f64_eq(cell_rise[1], 1.45) && f64_eq(cell_rise[2], 1.45));
+  assert!(f64_eq(timing.rise_transition.as_ref().unwrap().values[0], 0.0));
+}
+
+#[test]
+#[cfg(feature = "lut_template")]
+fn nldm_from_ccs() {
+  let mut library = Library::<DefaultCtx>::parse_lib(
+    r#"
+library(ccs) {
+  nom_voltage : 2.0;
+  voltage_map (VDD, 1.0);
+  voltage_map (VSS, 0.0);
+  lu_table_template(delay_2x1) {
+    variable_1 : total_output_net_capacitance;
+    variable_2 : input_net_transition;
+    index_1 ("1.0, 2.0");
+    index_2 ("0.1");
+  }
+  output_current_template(ccs_template) {
+    variable_1 : input_net_transition;
+    variable_2 : total_output_net_capacitance;
+    variable_3 : time;
+  }
+  cell (INV) {
+    pg_pin (VDD) { voltage_name : VDD; pg_type : primary_power; }
+    pg_pin (VSS) { voltage_name : VSS; pg_type : primary_ground; }
+    pin (A) { direction : input; }
+    pin (Y) {
+      direction : output;
+      function : "!A";
+      related_power_pin : VDD;
+      related_ground_pin : VSS;
+      timing () {
+        related_pin : "A";
+        cell_rise (delay_2x1) {
+          values ("0.55", "1.0");
+        }
+        output_current_rise () {
+          vector (ccs_template) {
+            reference_time : 0.05;
+            index_1 ("0.1");
+            index_2 ("1.0");
+            index_3 ("0.1, 1.1");
+            values ("1.0, 1.0");
+          }
+          vector (ccs_template) {
+            reference_time : 0.05;
+            index_1 ("0.1");
+            index_2 ("2.0");
+            index_3 ("0.1, 2.1");
+            values ("1.0, 1.0");
+          }
+        }
+      }
+    }
+  }
+}"#,
+    None,
+  )
+  .unwrap();
+  assert_eq!(
+    library.nldm_from_ccs(Some("delay_3x3")),
+    Err(table::NldmError::NoTemplate("delay_3x3".to_owned()))
+  );
+  // without template, the table is added on the grid of the CCS vectors
+  let mut ccs_grid = library.clone();
+  let reports = ccs_grid.nldm_from_ccs(None).unwrap();
+  assert!(reports.iter().all(|report| report.error.is_none()));
+  let timing = ccs_grid
+    .cell
+    .get("INV")
+    .unwrap()
+    .pin
+    .get("Y")
+    .unwrap()
+    .timing
+    .iter()
+    .next()
+    .unwrap();
+  let rise_transition = timing.rise_transition.as_ref().unwrap();
+  assert_eq!(rise_transition.name, "ccs_delay_1x2");
+  assert_eq!(
+    (rise_transition.index_1.as_slice(), rise_transition.index_2.as_slice()),
+    ([0.1].as_slice(), [1.0, 2.0].as_slice())
+  );
+  assert!(
+    f64_eq(rise_transition.values[0], 0.6) && f64_eq(rise_transition.values[1], 1.2)
+  );
+  let template = ccs_grid.lu_table_template.get("ccs_delay_1x2").unwrap();
+  assert_eq!(template.variable_1, Some(table::Variable::INPUT_NET_TRANSITION));
+  let reports = library.nldm_from_ccs(Some("delay_2x1")).unwrap();
+  let summary: Vec<_> = reports
+    .iter()
+    .map(|report| (report.path.as_str(), report.added, report.error.clone()))
+    .collect();
+  assert_eq!(
+    summary,
+    [
+      ("cell(INV)/pin(Y)/timing(A)[0]/cell_rise", false, None),
+      ("cell(INV)/pin(Y)/timing(A)[0]/rise_transition", true, None),
+    ]
+  );
+  assert!(f64_eq(reports[0].deviation.unwrap(), 0.05));
+  assert_eq!(reports[1].deviation, None);
+  let timing = library
+    .cell
+    .get("INV")
+    .unwrap()
+    .pin
+    .get("Y")
+    .unwrap()
+    .timing
+    .iter()
+    .next()
+    .unwrap();
+  let cell_rise = timing.cell_rise.as_ref().unwrap();
+  assert!(f64_eq(cell_rise.values[1], 1.0));
+  let rise_transition = timing.rise_transition.as_ref().unwrap();
+  assert_eq!(rise_transition.name, "delay_2x1");
+  assert_eq!(rise_transition.index_1, [1.0, 2.0]);
+  assert!(
+    f64_eq(rise_transition.values[0], 0.6) && f64_eq(rise_transition.values[1], 1.2)
+  );
+  assert!(timing.cell_fall.is_none());
+}
+
+#[test]
+fn noise_check() {
+  use crate::pin::{Glitch, NoiseError, NoiseModel, NoiseRegion};
+  let library = Library::<DefaultCtx>::parse_lib(
+    include_str!("../../dev/tech/test_suite/inverter_nldm.lib"),
+    None,
+  )
+  .unwrap();
+  let cell = library.cell.get("INV").unwrap();
+  let pin = cell.pin.get("A").unwrap();
+  let rails = pin.noise_rails(cell, &library).unwrap();
+  assert!(f64_eq(rails.high, 1.6) && f64_eq(rails.low, 0.0));
+  let glitch = |steady_high, peak, width, load| Glitch { steady_high, peak, width, load };
+  // the table of Y related to A, with the indices of `my_noise_reject`
+  let check = pin.noise_check(cell, &glitch(false, 0.6, 1.0, 0.3), &rails).unwrap();
+  assert_eq!((check.region, check.model), (NoiseRegion::Low, NoiseModel::Table));
+  assert!(f64_eq(check.height, 0.6) && f64_eq(check.max_height, 0.8));
+  assert!(!check.extrapolated && check.passed());
+  let check = pin.noise_check(cell, &glitch(true, 0.6, 0.05, 0.0), &rails).unwrap();
+  assert_eq!((check.region, check.model), (NoiseRegion::High, NoiseModel::Table));
+  assert!(f64_eq(check.max_height, 1.4) && check.passed());
+  // the tighter one of table and hyperbolic
+  let check = pin.noise_check(cell, &glitch(true, 0.6, 2.0, 0.0), &rails).unwrap();
+  assert_eq!(check.model, NoiseModel::Hyperbolic);
+  assert!(f64_eq(check.height, 1.0) && f64_eq(check.max_height, 0.3 + 0.9 / 1.9));
+  assert!(!check.passed());
+  // `my_noise_reject_outside_rail`, clamped
+  let check = pin.noise_check(cell, &glitch(true, 1.8, 0.005, 5.0), &rails).unwrap();
+  assert_eq!((check.region, check.model), (NoiseRegion::AboveHigh, NoiseModel::Table));
+  assert!(f64_eq(check.max_height, 0.5) && check.extrapolated);
+  assert!(f64_eq(check.margin, 0.3) && check.passed());
+  // no arc is related to the output pin
+  let output = cell.pin.get("Y").unwrap();
+  assert_eq!(
+    output.noise_check(cell, &glitch(false, 0.2, 1.0, 0.1), &rails),
+    Err(NoiseError::NoModel(NoiseRegion::Low))
+  );
+}
+
+#[test]
+fn propagated_noise() {
+  use crate::pin::{NoiseError, NoiseRegion};
+  let library = Library::<DefaultCtx>::parse_lib(
+    r#"
+library(noise) {
+  propagation_lut_template(prop_template) {
+    variable_1 : input_noise_width;
+    variable_2 : input_noise_height;
+    variable_3 : total_output_net_capacitance;
+    index_1 ("1.0, 2.0");
+    index_2 ("0.2, 0.4");
+    index_3 ("0.1, 0.2");
+  }
+  lu_table_template(ccsn_prop_template) {
+    variable_1 : input_noise_height;
+    variable_2 : input_noise_width;
+    variable_3 : total_output_net_capacitance;
+    variable_4 : time;
+  }
+  cell (INV) {
+    pin (A) {
+      direction : input;
+      ccsn_first_stage () {
+        is_needed : true;
+        is_inverting : true;
+        stage_type : both;
+        propagated_noise_high () {
+          vector (ccsn_prop_template) {
+            index_1 ("0.4");
+            index_2 ("1.0");
+            index_3 ("0.1");
+            index_4 ("0.0, 1.0, 2.0");
+            values ("1.0, 0.8, 1.0");
+          }
+          vector (ccsn_prop_template) {
+            index_1 ("0.4");
+            index_2 ("3.0");
+            index_3 ("0.1");
+            index_4 ("0.0, 3.0, 6.0");
+            values ("1.0, 0.6, 1.0");
+          }
+          vector (ccsn_prop_template) {
+            index_1 ("0.6");
+            index_2 ("2.0");
+            index_3 ("0.1");
+            index_4 ("0.0, 2.0, 4.0");
+            values ("1.0, 0.4, 1.0");
+          }
+        }
+      }
+    }
+    pin (Y) {
+      direction : output;
+      function : "!A";
+      timing () {
+        related_pin : "A";
+        propagated_noise_height_high (prop_template) {
+          values ("0.01, 0.02", "0.03, 0.04", "0.05, 0.06", "0.07, 0.08");
+        }
+        propagated_noise_width_high (prop_template) {
+          values ("1.0, 1.0", "1.0, 1.0", "2.0, 2.0", "2.0, 2.0");
+        }
+        propagated_noise_peak_time_ratio_high (prop_template) {
+          values ("0.5, 0.5", "0.5, 0.5", "0.5, 0.5", "0.5, 0.5");
+        }
+        propogated_noise_height_low (prop_template) {
+          values ("0.1, 0.1", "0.1, 0.1", "0.1, 0.1", "0.1, 0.1");
+        }
+        propogated_noise_width_low (prop_template) {
+          values ("3.0, 3.0", "3.0, 3.0", "3.0, 3.0", "3.0, 3.0");
+        }
+      }
+    }
+  }
+}"#,
+    None,
+  )
+  .unwrap();
+  let cell = library.cell.get("INV").unwrap();
+  let timing = cell.pin.get("Y").unwrap().timing.iter().next().unwrap();
+  // the indices come from propagation_lut_template
+  let noise = timing.propagated_noise(NoiseRegion::High, 0.3, 1.5, 0.15).unwrap();
+  assert!(f64_eq(noise.height, 0.045) && f64_eq(noise.width, 1.5));
+  assert!(f64_eq(noise.peak_time.unwrap(), 0.75) && !noise.extrapolated);
+  let noise = timing.propagated_noise(NoiseRegion::High, 0.4, 2.0, 0.2).unwrap();
+  assert!(f64_eq(noise.height, 0.08));
+  // the misspelled tables, without peak_time_ratio
+  assert!(timing.propagated_noise_tables(NoiseRegion::Low).height.is_some());
+  let noise = timing.propagated_noise(NoiseRegion::Low, 0.3, 1.5, 0.3).unwrap();
+  assert!(f64_eq(noise.height, 0.1) && f64_eq(noise.width, 3.0));
+  assert!(noise.peak_time.is_none() && noise.extrapolated);
+  assert_eq!(
+    timing.propagated_noise(NoiseRegion::AboveHigh, 0.3, 1.5, 0.15),
+    Err(NoiseError::NoModel(NoiseRegion::AboveHigh))
+  );
+  // the CCSN vectors, at the vector
+  let stage = cell.pin.get("A").unwrap().ccsn_first_stage.iter().next().unwrap();
+  let noise = stage.propagated_noise(NoiseRegion::High, 0.4, 1.0, 0.1).unwrap();
+  assert!(f64_eq(noise.height, 0.2) && f64_eq(noise.width, 1.0));
+  assert!(f64_eq(noise.peak_time.unwrap(), 0.5) && !noise.extrapolated);
+  // between the widths of the same height
+  let noise = stage.propagated_noise(NoiseRegion::High, 0.4, 2.0, 0.1).unwrap();
+  assert!(f64_eq(noise.height, 0.3) && f64_eq(noise.width, 2.0));
+  assert!(f64_eq(noise.peak_time.unwrap(), 1.0));
+  // between the heights, whose widths are scattered
+  let noise = stage.propagated_noise(NoiseRegion::High, 0.5, 2.0, 0.1).unwrap();
+  assert!(f64_eq(noise.height, 0.45) && f64_eq(noise.width, 2.0));
+  assert!(
+    stage
+      .propagated_noise(NoiseRegion::High, 0.4, 4.0, 0.1)
+      .unwrap()
+      .extrapolated
+  );
+  assert_eq!(
+    stage.propagated_noise(NoiseRegion::Low, 0.4, 1.0, 0.1),
+    Err(NoiseError::NoModel(NoiseRegion::Low))
+  );
+}
+
+#[test]
+fn receiver_load() {
+  use crate::ccsn::{ReceiverError, ReceiverModel};
+  let mut library = Library::<DefaultCtx>::parse_lib(
+    r#"
+library(receiver) {
+  input_threshold_pct_rise : 50.0;
+  slew_lower_threshold_pct_rise : 20.0;
+  slew_upper_threshold_pct_rise : 80.0;
+  receiver_capacitance_fall_threshold_pct ("100, 70, 30, 0");
+  lu_table_template(rcv_1d) {
+    variable_1 : input_net_transition;
+    index_1 ("0.1, 0.2");
+  }
+  lu_table_template(rcv_2d) {
+    variable_1 : total_output_net_capacitance;
+    variable_2 : input_net_transition;
+    index_1 ("1.0, 2.0");
+    index_2 ("0.1, 0.2");
+  }
+  cell (NAND) {
+    pin (A) {
+      direction : input;
+      capacitance : 0.01;
+      rise_capacitance : 0.012;
+      receiver_capacitance () {
+        receiver_capacitance1_rise (rcv_1d) {
+          index_1 ("0.1, 0.2");
+          values ("1.0, 2.0");
+        }
+        receiver_capacitance2_rise (rcv_1d) {
+          index_1 ("0.1, 0.2");
+          values ("3.0, 4.0");
+        }
+        receiver_capacitance_fall (rcv_1d) {
+          segment : 0;
+          index_1 ("0.1, 0.2");
+          values ("1.0, 1.0");
+        }
+        receiver_capacitance_fall (rcv_1d) {
+          segment : 1;
+          index_1 ("0.1, 0.2");
+          values ("2.0, 2.0");
+        }
+        receiver_capacitance_fall (rcv_1d) {
+          segment : 2;
+          index_1 ("0.1, 0.2");
+          values ("4.0, 4.0");
+        }
+      }
+    }
+    pin (B) {
+      direction : input;
+      capacitance : 0.01;
+    }
+    pin (C) { direction : input; }
+    pin (Y) {
+      direction : output;
+      function : "!(A&B&C)";
+      timing () {
+        related_pin : "A";
+        receiver_capacitance1_rise (rcv_2d) {
+          index_1 ("1.0, 2.0");
+          index_2 ("0.1, 0.2");
+          values ("1.0, 2.0", "3.0, 4.0");
+        }
+        receiver_capacitance2_rise (rcv_2d) {
+          index_1 ("1.0, 2.0");
+          index_2 ("0.1, 0.2");
+          values ("5.0, 5.0", "5.0, 5.0");
+        }
+      }
+    }
+  }
+}"#,
+    None,
+  )
+  .unwrap();
+  let cell = library.cell.get("NAND").unwrap();
+  let pin = cell.pin.get("A").unwrap();
+  // the two-piece model of pin, split at the input threshold
+  let rise = pin.receiver_load(None, true, 0.15, 0.0, &library).unwrap();
+  assert_eq!(rise.model, ReceiverModel::TwoPiece);
+  assert!(f64_eq(rise.effective, 2.5) && !rise.extrapolated);
+  assert!(f64_eq(rise.at(30.0).unwrap(), 1.5) && f64_eq(rise.at(60.0).unwrap(), 3.5));
+  assert!(rise.at(90.0).is_none());
+  let rise = pin.receiver_load(None, true, 0.3, 0.0, &library).unwrap();
+  assert!(f64_eq(rise.segments[0].capacitance, 2.0) && rise.extrapolated);
+  // the multi-segment model, by the library thresholds
+  let fall = pin.receiver_load(None, false, 0.15, 0.0, &library).unwrap();
+  assert_eq!(fall.model, ReceiverModel::MultiSegment);
+  assert!(f64_eq(fall.effective, 2.3) && f64_eq(fall.at(50.0).unwrap(), 2.0));
+  // the timing-level model, indexed by the output load first
+  let arc = cell.pin.get("Y").unwrap().timing.iter().next().unwrap();
+  let rise = pin.receiver_load(Some(arc), true, 0.1, 2.0, &library).unwrap();
+  assert!(f64_eq(rise.segments[0].capacitance, 3.0) && f64_eq(rise.effective, 4.0));
+  let fall = pin.receiver_load(Some(arc), false, 0.15, 2.0, &library).unwrap();
+  assert_eq!(fall.model, ReceiverModel::MultiSegment);
+  // fall back to the pin capacitance
+  let load = cell
+    .pin
+    .get("B")
+    .unwrap()
+    .receiver_load(None, true, 0.1, 0.0, &library)
+    .unwrap();
+  assert_eq!(load.model, ReceiverModel::Pin);
+  assert!(f64_eq(load.effective, 0.01));
+  assert_eq!(
+    cell
+      .pin
+      .get("C")
+      .unwrap()
+      .receiver_load(None, true, 0.1, 0.0, &library),
+    Err(ReceiverError::NoCapacitance)
+  );
+  _ = library.receiver_capacitance_fall_threshold_pct.pop();
+  let cell = library.cell.get("NAND").unwrap();
+  assert_eq!(
+    cell
+      .pin
+      .get("A")
+      .unwrap()
+      .receiver_load(None, false, 0.1, 0.0, &library),
+    Err(ReceiverError::Segments { expected: 2, found: 3 })
+  );
+}
+
+#[test]
+fn aggregate_monte_carlo() {
+  use crate::timing::LVFMoments;
+  let library = |rise: [f64; 2], fall_index: &str, extra: &str| {
+    Library::<DefaultCtx>::parse_lib(
+      &format!(
+        r#"
+library(mc) {{
+  cell (INV) {{
+    pin (A) {{ direction : input; }}
+    pin (Y) {{
+      direction : output;
+      function : "!A";
+      timing () {{
+        related_pin : "A";
+        cell_rise (scalar) {{
+          index_1 ("0.1, 0.2");
+          values ("{}, {}");
+        }}
+        {fall_index}
+      }}
+    }}
+  }}
+  {extra}
+}}"#,
+        rise[0], rise[1]
+      ),
+      None,
+    )
+    .unwrap()
+  };
+  let fall = |index: &str| {
+    format!(r#"cell_fall (scalar) {{ index_1 ("{index}"); values ("1.0, 2.0"); }}"#)
+  };
+  let mut nominal = library([1.1, 2.1], &fall("0.1, 0.2"), "");
+  let runs = [
+    library([1.0, 2.0], &fall("0.1, 0.2"), ""),
+    library([1.2, 2.0], "", ""),
+    library(
+      [1.6, 2.6],
+      &fall("0.1, 0.3"),
+      r#"cell (BUF) { pin (Y) { timing () { related_pin : "A"; cell_rise (scalar) { values ("1.0"); } } } }"#,
+    ),
+  ];
+  let mismatches: Vec<_> = nominal
+    .aggregate_monte_carlo(&runs)
+    .into_iter()
+    .map(|m| (m.path, m.run, m.kind))
+    .collect();
+  assert_eq!(
+    mismatches,
+    [
+      (
+        "cell(INV)/pin(Y)/timing(A)[0]/cell_fall".to_owned(),
+        1,
+        MonteCarloMismatchKind::Missing
+      ),
+      (
+        "cell(INV)/pin(Y)/timing(A)[0]/cell_fall".to_owned(),
+        2,
+        MonteCarloMismatchKind::Index
+      ),
+      (
+        "cell(BUF)/pin(Y)/timing(A)[0]/cell_rise".to_owned(),
+        2,
+        MonteCarloMismatchKind::Extra
+      ),
+    ]
+  );
+  let timing = nominal
+    .cell
+    .get("INV")
+    .unwrap()
+    .pin
+    .get("Y")
+    .unwrap()
+    .timing
+    .iter()
+    .next()
+    .unwrap();
+  let moments = &timing.cell_rise.as_ref().unwrap().lvf_moments_values;
+  for (moment, samples) in moments.iter().zip([[1.0, 1.2, 1.6], [2.0, 2.0, 2.6]]) {
+    let expected = LVFMoments::estimate(samples.into_iter().map(Ok::<_, ()>))
+      .unwrap()
+      .unwrap();
+    assert!(f64_eq(moment.mean, expected.mean));
+    assert!(f64_eq(moment.std_dev, expected.std_dev));
+    assert!(f64_eq(moment.skewness, expected.skewness));
+  }
+  assert!(f64_eq(moments[0].mean, 3.8 / 3.0));
+  assert!(moments[1].skewness > 0.0);
+  // only the first run is matched for `cell_fall`
+  let fall_moments = &timing.cell_fall.as_ref().unwrap().lvf_moments_values;
+  assert!(fall_moments.iter().all(|m| f64_eq(m.std_dev, 0.0)));
+  let text = nominal.to_string();
+  assert!(text.contains("ocv_mean_shift_cell_rise"));
+  assert!(text.contains("ocv_std_dev_cell_rise"));
+  assert!(text.contains("ocv_skewness_cell_rise"));
+}
+
+#[test]
+fn convert_lvf() {
+  let mut library = Library::<DefaultCtx>::parse_lib(
+    r#"
+library(lvf) {
+  cell (INV) {
+    pin (A) { direction : input; }
+    pin (Y) {
+      direction : output;
+      function : "!A";
+      timing () {
+        related_pin : "A";
+        cell_rise (scalar) {
+          index_1 ("0.1, 0.2");
+          values ("1.0, 2.0");
+        }
+        ocv_mean_shift_cell_rise (scalar) {
+          index_1 ("0.1, 0.2");
+          values ("0.01, 0.02");
+        }
+        ocv_std_dev_cell_rise (scalar) {
+          index_1 ("0.1, 0.2");
+          values ("0.1, 0.2");
+        }
+        ocv_skewness_cell_rise (scalar) {
+          index_1 ("0.1, 0.2");
+          values ("0.3, -0.5");
+        }
+        cell_fall (scalar) {
+          index_1 ("0.1, 0.2");
+          values ("1.0, 2.0");
+        }
+        ocv_sigma_cell_fall (scalar) {
+          sigma_type : early;
+          index_1 ("0.1, 0.2");
+          values ("0.1, 0.2");
+        }
+        ocv_sigma_cell_fall (scalar) {
+          sigma_type : late;
+          index_1 ("0.1, 0.2");
+          values ("0.12, 0.2");
+        }
+        rise_transition (scalar) {
+          index_1 ("0.1, 0.2");
+          values ("1.0, 2.0");
+        }
+        ocv_mean_shift_rise_transition (scalar) {
+          index_1 ("0.1, 0.2");
+          values ("0.0, 0.1");
+        }
+        ocv_std_dev_rise_transition (scalar) {
+          index_1 ("0.1, 0.2");
+          values ("0.1, 0.0");
+        }
+        ocv_skewness_rise_transition (scalar) {
+          index_1 ("0.1, 0.2");
+          values ("0.0, 0.0");
+        }
+      }
+    }
+  }
+}"#,
+    None,
+  )
+  .unwrap();
+  let timing = |library: &Library<DefaultCtx>| {
+    library
+      .cell
+      .get("INV")
+      .unwrap()
+      .pin
+      .get("Y")
+      .unwrap()
+      .timing
+      .iter()
+      .next()
+      .unwrap()
+      .clone()
+  };
+  let before = timing(&library);
+  let conversions = library.convert_lvf(LvfForm::EarlyLate);
+  let summary: Vec<_> = conversions
+    .iter()
+    .map(|c| (c.path.as_str(), c.failed.clone()))
+    .collect();
+  assert_eq!(
+    summary,
+    [
+      ("cell(INV)/pin(Y)/timing(A)[0]/cell_rise", vec![]),
+      // the mean shift without std_dev
+      ("cell(INV)/pin(Y)/timing(A)[0]/rise_transition", vec![1]),
+    ]
+  );
+  // the mean shift is lost on the way back
+  assert!(conversions[0].round_trip_error >= 0.02);
+  let after = timing(&library);
+  let cell_rise = after.cell_rise.as_ref().unwrap();
+  assert!(cell_rise.lvf_moments_values.is_empty());
+  assert_eq!(cell_rise.lvf_early_late_values.len(), 2);
+  assert_eq!(
+    after.rise_transition.as_ref().unwrap().lvf_moments_values,
+    before.rise_transition.as_ref().unwrap().lvf_moments_values
+  );
+  assert_eq!(after.cell_fall.as_ref().unwrap().lvf_early_late_values.len(), 2);
+  assert!(library.to_string().contains("ocv_sigma_cell_rise"));
+  // back to the moments, with the nominal value as mean
+  let conversions = library.convert_lvf(LvfForm::Moments);
+  assert_eq!(conversions.len(), 2);
+  assert!(conversions.iter().all(|c| c.converted() && c.round_trip_error < 1e-9));
+  let after = timing(&library);
+  let moments = &after.cell_rise.as_ref().unwrap().lvf_moments_values;
+  let expected = &before.cell_rise.as_ref().unwrap().lvf_moments_values;
+  for (moment, expected) in moments.iter().zip(expected) {
+    // the mean shift moves into the skewness
+    assert!((moment.std_dev - expected.std_dev).abs() < 0.01);
+  }
+  let fall = &after.cell_fall.as_ref().unwrap().lvf_moments_values;
+  assert!(fall[0].skewness > 0.0 && f64_eq(fall[1].skewness, 0.0));
+  assert!(f64_eq(fall[1].std_dev, 0.2));
+}
+
+#[test]
+fn ocv_derates() {
+  let library = Library::<DefaultCtx>::parse_lib(
+    r#"
+library(lvf) {
+  cell (INV) {
+    pin (A) { direction : input; }
+    pin (Y) {
+      direction : output;
+      function : "!A";
+      timing () {
+        related_pin : "A";
+        cell_rise (scalar) {
+          index_1 ("0.1, 0.2");
+          values ("1.0, 2.0");
+        }
+        ocv_mean_shift_cell_rise (scalar) {
+          index_1 ("0.1, 0.2");
+          values ("0.0, 0.0");
+        }
+        ocv_std_dev_cell_rise (scalar) {
+          index_1 ("0.1, 0.2");
+          values ("0.1, 0.2");
+        }
+        ocv_skewness_cell_rise (scalar) {
+          index_1 ("0.1, 0.2");
+          values ("0.0, 0.0");
+        }
+        cell_fall (scalar) {
+          index_1 ("0.1, 0.2");
+          values ("1.0, 2.0");
+        }
+        ocv_sigma_cell_fall (scalar) {
+          sigma_type : early;
+          index_1 ("0.1, 0.2");
+          values ("0.05, 0.1");
+        }
+        ocv_sigma_cell_fall (scalar) {
+          sigma_type : late;
+          index_1 ("0.1, 0.2");
+          values ("0.1, 0.3");
+        }
+      }
+    }
+  }
+  cell (BUF) {
+    pin (A) { direction : input; }
+    pin (Y) {
+      direction : output;
+      function : "A";
+      timing () {
+        related_pin : "A";
+        cell_rise (scalar) {
+          index_1 ("0.1, 0.2");
+          values ("1.0, 2.0");
+        }
+      }
+    }
+  }
+}"#,
+    None,
+  )
+  .unwrap();
+  let close = |a: f64, b: f64| (a - b).abs() < 1e-9;
+  let derates = library.ocv_derates(OcvReference::Center);
+  assert_eq!(derates.library, "lvf");
+  let summary: Vec<_> = derates
+    .coefficients
+    .iter()
+    .map(|c| (c.cell.as_str(), c.pin.as_str(), c.related_pin.as_str(), c.edge))
+    .collect();
+  assert_eq!(
+    summary,
+    [("INV", "Y", "A", logic::Edge::R), ("INV", "Y", "A", logic::Edge::F)]
+  );
+  let [rise, fall] = derates.coefficients.as_slice() else { panic!() };
+  // the midpoint of the two middle index values
+  assert!(close(rise.nominal, 1.5) && close(rise.early, 0.1) && close(rise.late, 0.1));
+  assert!(close(fall.early, 0.05) && close(fall.late, 0.2 / 1.5));
+  // another reference point
+  let derates_at = library.ocv_derates(OcvReference::Point(0.2, 0.0));
+  assert!(close(derates_at.coefficients[0].nominal, 2.0));
+  assert!(close(derates_at.coefficients[1].late, 0.15));
+  let aocv = derates.aocv(&[0, 1, 4], 3.0);
+  assert_eq!(aocv.len(), 2);
+  let rise = &aocv[0];
+  assert_eq!((rise.cell.as_str(), rise.edge), ("INV", logic::Edge::R));
+  assert_eq!(rise.depth, [1, 4]);
+  assert!(close(rise.early[1], 0.85) && close(rise.late[1], 1.15));
+  let fall = &aocv[1];
+  assert!(close(fall.early[0], 0.85) && close(fall.early[1], 0.925));
+  assert!(close(fall.late[0], 1.4) && close(fall.late[1], 1.2));
+  let pocv = derates.pocv_side_file();
+  assert!(pocv.starts_with("version: 4.0\n"));
+  assert_eq!(pocv.matches("ocvm_type: pocvm").count(), 4);
+  assert!(pocv.contains(
+    "object_type: lib_cell\nrf_type: rise\ndelay_type: cell\nderate_type: late\nobject_spec: lvf/INV\ncoefficient: 0.1"
+  ));
+  let aocv = derates.aocv_side_file(&[1, 4], 3.0);
+  assert_eq!(aocv.matches("ocvm_type: aocvm").count(), 4);
+  assert!(aocv.contains("derate_type: early\npath_type: clock data\nobject_spec: lvf/INV\ndepth: 1 4\ntable: 0.7 0.85"));
+}
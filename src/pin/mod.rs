@@ -19,6 +19,8 @@ mod bus;
 pub use bus::{BusType, BusTypeCtx, SimpleBusType};
 mod items;
 pub use items::*;
+mod noise;
+pub use noise::{Glitch, NoiseCheck, NoiseError, NoiseModel, NoiseRails, NoiseRegion};
 
 #[derive(liberty_macros::Duplicate)]
 #[duplicated(
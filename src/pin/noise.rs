@@ -0,0 +1,268 @@
+//! Noise immunity check of the receiving pin, by the `noise_immunity_*` tables
+//! or the `hyperbolic_noise_*` groups, see [`Pin::noise_check`].
+#![allow(clippy::multiple_inherent_impl)]
+use super::{Direction, HyperbolicNoise, Pin};
+use crate::{
+  Ctx,
+  cell::Cell,
+  library::Library,
+  table::{LookupConfig, LookupError, OutOfRange, TableLookUp, Variable, WaveformError},
+  timing::Timing,
+};
+
+/// The noise region of the glitch
+#[derive(Debug, Clone, Copy)]
+#[derive(Hash, PartialEq, Eq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub enum NoiseRegion {
+  /// Glitch down from the high rail, toward the low one
+  High,
+  /// Glitch up from the low rail, toward the high one
+  Low,
+  /// Glitch up from the high rail, above it
+  AboveHigh,
+  /// Glitch down from the low rail, below it
+  BelowLow,
+}
+
+/// The supply rails of the receiving pin, see [`Pin::noise_rails`]
+#[derive(Debug, Clone, Copy)]
+#[derive(PartialEq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct NoiseRails {
+  /// The voltage of logic 0
+  pub low: f64,
+  /// The voltage of logic 1
+  pub high: f64,
+}
+
+/// The glitch at the receiving pin
+#[derive(Debug, Clone, Copy)]
+#[derive(PartialEq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct Glitch {
+  /// Whether the pin is steady at the high rail, otherwise at the low one
+  pub steady_high: bool,
+  /// The peak voltage of the glitch
+  pub peak: f64,
+  /// The width of the glitch
+  pub width: f64,
+  /// The total output net capacitance
+  pub load: f64,
+}
+
+/// The model used by [`Pin::noise_check`]
+#[derive(Debug, Clone, Copy)]
+#[derive(Hash, PartialEq, Eq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub enum NoiseModel {
+  /// The `noise_immunity_*` table of timing arc
+  Table,
+  /// The `hyperbolic_noise_*` group of pin
+  Hyperbolic,
+}
+
+/// The result of [`Pin::noise_check`]
+#[derive(Debug, Clone, Copy)]
+#[derive(PartialEq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct NoiseCheck {
+  /// The region of the glitch
+  pub region: NoiseRegion,
+  /// The height of the glitch, from the steady rail
+  pub height: f64,
+  /// The maximum height the pin tolerates
+  pub max_height: f64,
+  /// `max_height - height`, negative when the glitch fails
+  pub margin: f64,
+  /// The model giving the `max_height`, the tighter one when both are defined
+  pub model: NoiseModel,
+  /// Whether the table lookup is out of range and clamped
+  pub extrapolated: bool,
+}
+
+impl NoiseCheck {
+  /// Whether the pin tolerates the glitch
+  #[inline]
+  #[must_use]
+  pub const fn passed(&self) -> bool {
+    self.margin >= 0.0
+  }
+}
+
+/// The error of [`Pin::noise_check`] / [`Timing::propagated_noise`]
+#[derive(Debug, Clone, Copy, thiserror::Error, PartialEq)]
+pub enum NoiseError {
+  /// There is no table or group of the region, e.g., neither `noise_immunity_*`
+  /// table nor `hyperbolic_noise_*` group
+  #[error("no noise model of {0:?}")]
+  NoModel(NoiseRegion),
+  /// The high rail is not able to resolve
+  #[error("no voltage of the high rail")]
+  NoRail,
+  /// The table lookup fails
+  #[error(transparent)]
+  Lookup(#[from] LookupError),
+  /// The `propagated_noise_high/low` vectors are not able to interpolate
+  #[error(transparent)]
+  Waveform(#[from] WaveformError),
+  /// The propagated waveform has no glitch
+  #[error("no glitch in the propagated waveform")]
+  NoGlitch,
+}
+
+impl NoiseRegion {
+  /// The region and height of `glitch`, measured from the steady rail
+  #[inline]
+  #[must_use]
+  #[expect(clippy::float_arithmetic)]
+  pub fn of(glitch: &Glitch, rails: &NoiseRails) -> (Self, f64) {
+    if glitch.steady_high {
+      if glitch.peak > rails.high {
+        (Self::AboveHigh, glitch.peak - rails.high)
+      } else {
+        (Self::High, rails.high - glitch.peak)
+      }
+    } else if glitch.peak < rails.low {
+      (Self::BelowLow, rails.low - glitch.peak)
+    } else {
+      (Self::Low, glitch.peak - rails.low)
+    }
+  }
+}
+
+impl<C: 'static + Ctx> HyperbolicNoise<C> {
+  /// The maximum height the pin tolerates with the glitch `width`,
+  /// `height_coefficient + area_coefficient / (width - width_coefficient)`.
+  ///
+  /// It is infinite when the glitch is not wider than `width_coefficient`.
+  #[inline]
+  #[must_use]
+  #[expect(clippy::float_arithmetic)]
+  pub fn max_height(&self, width: f64) -> f64 {
+    if width > self.width_coefficient {
+      self.height_coefficient + self.area_coefficient / (width - self.width_coefficient)
+    } else {
+      f64::INFINITY
+    }
+  }
+}
+
+impl<C: 'static + Ctx> Timing<C> {
+  /// The `noise_immunity_*` table of `region`
+  #[inline]
+  #[must_use]
+  pub const fn noise_immunity(&self, region: NoiseRegion) -> Option<&TableLookUp<C>> {
+    match region {
+      NoiseRegion::High => self.noise_immunity_high.as_ref(),
+      NoiseRegion::Low => self.noise_immunity_low.as_ref(),
+      NoiseRegion::AboveHigh => self.noise_immunity_above_high.as_ref(),
+      NoiseRegion::BelowLow => self.noise_immunity_below_low.as_ref(),
+    }
+  }
+}
+
+impl<C: 'static + Ctx> Pin<C> {
+  /// The `hyperbolic_noise_*` group of `region`
+  #[inline]
+  #[must_use]
+  pub const fn hyperbolic_noise(
+    &self,
+    region: NoiseRegion,
+  ) -> Option<&HyperbolicNoise<C>> {
+    match region {
+      NoiseRegion::High => self.hyperbolic_noise_high.as_ref(),
+      NoiseRegion::Low => self.hyperbolic_noise_low.as_ref(),
+      NoiseRegion::AboveHigh => self.hyperbolic_noise_above_high.as_ref(),
+      NoiseRegion::BelowLow => self.hyperbolic_noise_below_low.as_ref(),
+    }
+  }
+  /// The supply rails of the pin in `cell` of `library`.
+  ///
+  /// The high rail is the `voltage_map` of `input_signal_level`, or of the
+  /// `voltage_name` of `related_power_pin`, or the `nom_voltage`.
+  /// The low rail is the `voltage_map` of the `voltage_name` of
+  /// `related_ground_pin`, or 0.
+  #[inline]
+  pub fn noise_rails(
+    &self,
+    cell: &Cell<C>,
+    library: &Library<C>,
+  ) -> Result<NoiseRails, NoiseError> {
+    let voltage = |name: &str| library.voltage_map.get(name).map(|map| map.voltage);
+    let pg_voltage = |name: &str| voltage(&cell.pg_pin.get(name)?.voltage_name);
+    let high = self
+      .input_signal_level
+      .as_deref()
+      .and_then(voltage)
+      .or_else(|| pg_voltage(&self.related_power_pin))
+      .or(library.nom_voltage)
+      .ok_or(NoiseError::NoRail)?;
+    let low = pg_voltage(&self.related_ground_pin).unwrap_or(0.0);
+    Ok(NoiseRails { low, high })
+  }
+  /// Check whether the pin of `cell` tolerates `glitch` on `rails`,
+  /// see [`Pin::noise_rails`].
+  ///
+  /// The maximum height is looked up in the `noise_immunity_*` table of the
+  /// first timing arc of the output pins (including the bus pins) in `cell`,
+  /// whose `related_pin` contains this pin and that has one for the region,
+  /// with the query clamped into the table range, and is given by the
+  /// `hyperbolic_noise_*` group of the pin.
+  /// The tighter one is used when both are defined.
+  #[inline]
+  #[expect(clippy::float_arithmetic)]
+  pub fn noise_check(
+    &self,
+    cell: &Cell<C>,
+    glitch: &Glitch,
+    rails: &NoiseRails,
+  ) -> Result<NoiseCheck, NoiseError> {
+    let (region, height) = NoiseRegion::of(glitch, rails);
+    let config = LookupConfig {
+      out_of_range: OutOfRange::Clamp,
+      ..LookupConfig::default()
+    };
+    let table = cell
+      .pin
+      .iter()
+      .chain(cell.bus.iter().flat_map(|bus| bus.pin.iter()))
+      .filter(|pin| matches!(pin.direction, Some(Direction::Output | Direction::Inout)))
+      .flat_map(|pin| pin.timing.iter())
+      .filter(|timing| timing.related_pin.inner.contains(self.name.as_str()))
+      .find_map(|timing| timing.noise_immunity(region))
+      .map(|table| {
+        table.lookup_variables(
+          &[Variable::INPUT_NOISE_WIDTH, Variable::TOTAL_OUTPUT_NET_CAPACITANCE],
+          |variable| {
+            if variable == Some(Variable::TOTAL_OUTPUT_NET_CAPACITANCE) {
+              glitch.load
+            } else {
+              glitch.width
+            }
+          },
+          config,
+        )
+      })
+      .transpose()?;
+    let hyperbolic = self
+      .hyperbolic_noise(region)
+      .map(|hyperbolic| hyperbolic.max_height(glitch.width));
+    let (max_height, model, extrapolated) = match (table, hyperbolic) {
+      (Some(lookup), Some(max_height)) if max_height < lookup.value => {
+        (max_height, NoiseModel::Hyperbolic, false)
+      }
+      (Some(lookup), _) => (lookup.value, NoiseModel::Table, lookup.extrapolated),
+      (None, Some(max_height)) => (max_height, NoiseModel::Hyperbolic, false),
+      (None, None) => return Err(NoiseError::NoModel(region)),
+    };
+    Ok(NoiseCheck {
+      region,
+      height,
+      max_height,
+      margin: max_height - height,
+      model,
+      extrapolated,
+    })
+  }
+}
@@ -0,0 +1,521 @@
+//! Element-wise arithmetic of lookup tables, e.g., derating and guard-banding.
+//!
+//! The LVF values of [`TimingTableLookUp`] are propagated by treating each
+//! table point as a random variable, and the two operands as independent.
+#![allow(clippy::multiple_inherent_impl)]
+use super::{Interpolation, TableLookUp};
+use crate::{
+  Ctx,
+  timing::{LVFEarlyLate, LVFMoments, TimingTableLookUp},
+};
+
+/// A table point, with its LVF values
+#[derive(Debug, Clone, Copy)]
+struct Point {
+  value: f64,
+  moments: Option<LVFMoments>,
+  early_late: Option<LVFEarlyLate>,
+}
+
+#[expect(clippy::float_arithmetic)]
+impl Point {
+  /// The moments of a constant
+  const fn constant_moments(value: f64) -> LVFMoments {
+    LVFMoments { mean: value, std_dev: 0.0, skewness: 0.0 }
+  }
+  const CONSTANT_EARLY_LATE: LVFEarlyLate =
+    LVFEarlyLate { early_sigma: 0.0, late_sigma: 0.0 };
+  /// Combine the LVF values, the missing one is treated as constant
+  fn combine<T: Copy, F: FnOnce(T, T) -> T>(
+    lhs: Option<T>,
+    rhs: Option<T>,
+    constant: (T, T),
+    f: F,
+  ) -> Option<T> {
+    if lhs.is_none() && rhs.is_none() {
+      None
+    } else {
+      Some(f(lhs.unwrap_or(constant.0), rhs.unwrap_or(constant.1)))
+    }
+  }
+  /// The third central moment
+  fn third_moment(moments: &LVFMoments) -> f64 {
+    moments.skewness * moments.std_dev.powi(3)
+  }
+  /// Moments from mean, variance and the third central moment
+  fn moments_of(mean: f64, variance: f64, third_moment: f64) -> LVFMoments {
+    let std_dev = variance.max(0.0).sqrt();
+    let skewness = if std_dev > 0.0 { third_moment / std_dev.powi(3) } else { 0.0 };
+    LVFMoments { mean, std_dev, skewness }
+  }
+  /// The sum of independent sigmas
+  fn rss(a: f64, b: f64) -> f64 {
+    a.hypot(b)
+  }
+  /// `self + offset`, only the mean is shifted
+  fn offset(self, offset: f64) -> Self {
+    Self {
+      value: self.value + offset,
+      moments: self.moments.map(|m| LVFMoments {
+        mean: m.mean + offset,
+        std_dev: m.std_dev,
+        skewness: m.skewness,
+      }),
+      early_late: self.early_late,
+    }
+  }
+  /// `self * factor`, the sigma is scaled by `|factor|`, and the skewness
+  /// and early / late sigma are flipped for negative `factor`
+  fn scale(self, factor: f64) -> Self {
+    let negative = factor < 0.0;
+    Self {
+      value: self.value * factor,
+      moments: self.moments.map(|m| LVFMoments {
+        mean: m.mean * factor,
+        std_dev: m.std_dev * factor.abs(),
+        skewness: if factor == 0.0 {
+          0.0
+        } else if negative {
+          -m.skewness
+        } else {
+          m.skewness
+        },
+      }),
+      early_late: self.early_late.map(|el| {
+        let (early_sigma, late_sigma) = if negative {
+          (el.late_sigma, el.early_sigma)
+        } else {
+          (el.early_sigma, el.late_sigma)
+        };
+        LVFEarlyLate {
+          early_sigma: early_sigma * factor.abs(),
+          late_sigma: late_sigma * factor.abs(),
+        }
+      }),
+    }
+  }
+  /// `self + other`, the means, variances and third central moments are added
+  fn add(self, other: Self) -> Self {
+    Self {
+      value: self.value + other.value,
+      moments: Self::combine(
+        self.moments,
+        other.moments,
+        (Self::constant_moments(self.value), Self::constant_moments(other.value)),
+        |a, b| {
+          Self::moments_of(
+            a.mean + b.mean,
+            a.std_dev.mul_add(a.std_dev, b.std_dev * b.std_dev),
+            Self::third_moment(&a) + Self::third_moment(&b),
+          )
+        },
+      ),
+      early_late: Self::combine(
+        self.early_late,
+        other.early_late,
+        (Self::CONSTANT_EARLY_LATE, Self::CONSTANT_EARLY_LATE),
+        |a, b| LVFEarlyLate {
+          early_sigma: Self::rss(a.early_sigma, b.early_sigma),
+          late_sigma: Self::rss(a.late_sigma, b.late_sigma),
+        },
+      ),
+    }
+  }
+  /// `self * other`, with the exact mean and variance of independent product,
+  /// and the first-order approximation of the third central moment and early / late sigma
+  fn mul(self, other: Self) -> Self {
+    Self {
+      value: self.value * other.value,
+      moments: Self::combine(
+        self.moments,
+        other.moments,
+        (Self::constant_moments(self.value), Self::constant_moments(other.value)),
+        |a, b| {
+          let (var_a, var_b) = (a.std_dev * a.std_dev, b.std_dev * b.std_dev);
+          Self::moments_of(
+            a.mean * b.mean,
+            var_a
+              .mul_add(var_b, (b.mean * b.mean).mul_add(var_a, a.mean * a.mean * var_b)),
+            b.mean
+              .powi(3)
+              .mul_add(Self::third_moment(&a), a.mean.powi(3) * Self::third_moment(&b)),
+          )
+        },
+      ),
+      early_late: Self::combine(
+        self.early_late,
+        other.early_late,
+        (Self::CONSTANT_EARLY_LATE, Self::CONSTANT_EARLY_LATE),
+        |a, b| {
+          let (k_a, k_b) = (other.value.abs(), self.value.abs());
+          LVFEarlyLate {
+            early_sigma: Self::rss(a.early_sigma * k_a, b.early_sigma * k_b),
+            late_sigma: Self::rss(a.late_sigma * k_a, b.late_sigma * k_b),
+          }
+        },
+      ),
+    }
+  }
+  /// The point with smaller (`max == false`) or larger nominal value
+  fn select(self, other: Self, max: bool) -> Self {
+    if (self.value < other.value) == max { other } else { self }
+  }
+  /// The constant point, with zero sigma when this point has LVF values
+  fn constant_like(self, value: f64) -> Self {
+    Self {
+      value,
+      moments: self.moments.map(|_| Self::constant_moments(value)),
+      early_late: self.early_late.map(|_| Self::CONSTANT_EARLY_LATE),
+    }
+  }
+}
+
+impl<C: 'static + Ctx> TimingTableLookUp<C> {
+  fn points(&self) -> impl Iterator<Item = Point> + '_ {
+    self.values.iter().enumerate().map(|(i, value)| Point {
+      value: *value,
+      moments: self.lvf_moments_values.get(i).copied(),
+      early_late: self.lvf_early_late_values.get(i).copied(),
+    })
+  }
+  /// The table with the same grid and new points,
+  /// the LVF values are kept only when all points have them
+  fn with_points<I: Iterator<Item = Point>>(&self, new_points: I) -> Self {
+    let points: Vec<Point> = new_points.collect();
+    let mut table = self.clone();
+    table.values = points.iter().map(|point| point.value).collect();
+    table.lvf_moments_values = points
+      .iter()
+      .map(|point| point.moments)
+      .collect::<Option<_>>()
+      .unwrap_or_default();
+    table.lvf_early_late_values = points
+      .iter()
+      .map(|point| point.early_late)
+      .collect::<Option<_>>()
+      .unwrap_or_default();
+    table
+  }
+  /// Apply `f` on the points of both tables, the `other` table is resampled
+  /// onto the grid of this table when their indices differ.
+  /// Return `None` when the tables can not be aligned
+  fn zip_with<F: Fn(Point, Point) -> Point>(&self, other: &Self, f: F) -> Option<Self> {
+    let resampled;
+    let aligned = if self.index_1 == other.index_1 && self.index_2 == other.index_2 {
+      other
+    } else {
+      resampled = other
+        .resample(&self.index_1, &self.index_2, Interpolation::Linear)?
+        .table;
+      &resampled
+    };
+    (self.values.len() == aligned.values.len()).then(|| {
+      self.with_points(self.points().zip(aligned.points()).map(|(a, b)| f(a, b)))
+    })
+  }
+  /// Apply `f` on all the values.
+  /// The LVF values are removed, since they can not be propagated by arbitrary function.
+  #[must_use]
+  #[inline]
+  pub fn map<F: Fn(f64) -> f64>(&self, f: F) -> Self {
+    let mut table = self.clone();
+    table.values.iter_mut().for_each(|value| *value = f(*value));
+    table.lvf_moments_values.clear();
+    table.lvf_early_late_values.clear();
+    table
+  }
+  /// Add `offset` to all values, e.g., the wire-delay offset.
+  /// The LVF mean is shifted, and the sigma is kept.
+  #[must_use]
+  #[inline]
+  pub fn add_scalar(&self, offset: f64) -> Self {
+    self.with_points(self.points().map(|point| point.offset(offset)))
+  }
+  /// Multiply all values by `factor`, e.g., `1.05` for a 5% guard-band.
+  /// The LVF sigma is scaled by `|factor|`.
+  #[must_use]
+  #[inline]
+  pub fn scale(&self, factor: f64) -> Self {
+    self.with_points(self.points().map(|point| point.scale(factor)))
+  }
+  /// The element-wise minimum with `bound`
+  #[must_use]
+  #[inline]
+  pub fn min_scalar(&self, bound: f64) -> Self {
+    self.with_points(
+      self
+        .points()
+        .map(|point| point.select(point.constant_like(bound), false)),
+    )
+  }
+  /// The element-wise maximum with `bound`
+  #[must_use]
+  #[inline]
+  pub fn max_scalar(&self, bound: f64) -> Self {
+    self.with_points(
+      self
+        .points()
+        .map(|point| point.select(point.constant_like(bound), true)),
+    )
+  }
+  /// The element-wise sum, `other` is resampled onto the grid of this table
+  /// when their indices differ.
+  ///
+  /// The LVF moments are added as independent random variables,
+  /// i.e., the variances are added rather than the sigmas.
+  #[must_use]
+  #[inline]
+  pub fn add_table(&self, other: &Self) -> Option<Self> {
+    self.zip_with(other, Point::add)
+  }
+  /// The element-wise difference, see [`TimingTableLookUp::add_table`]
+  #[must_use]
+  #[inline]
+  pub fn sub_table(&self, other: &Self) -> Option<Self> {
+    self.zip_with(other, |a, b| a.add(b.scale(-1.0)))
+  }
+  /// The element-wise product, see [`TimingTableLookUp::add_table`]
+  #[must_use]
+  #[inline]
+  pub fn mul_table(&self, other: &Self) -> Option<Self> {
+    self.zip_with(other, Point::mul)
+  }
+  /// The element-wise minimum, e.g., across corners.
+  /// The LVF values come from the selected table at each point.
+  #[must_use]
+  #[inline]
+  pub fn min_table(&self, other: &Self) -> Option<Self> {
+    self.zip_with(other, |a, b| a.select(b, false))
+  }
+  /// The element-wise maximum, e.g., across corners.
+  /// The LVF values come from the selected table at each point.
+  #[must_use]
+  #[inline]
+  pub fn max_table(&self, other: &Self) -> Option<Self> {
+    self.zip_with(other, |a, b| a.select(b, true))
+  }
+}
+
+impl<C: 'static + Ctx> TableLookUp<C> {
+  /// Apply `f` on the values of both tables, the `other` table is resampled
+  /// onto the grid of this table when their indices differ.
+  /// Return `None` when the tables can not be aligned
+  fn zip_with<F: Fn(f64, f64) -> f64>(&self, other: &Self, f: F) -> Option<Self> {
+    let resampled;
+    let aligned = if self.index_1 == other.index_1
+      && self.index_2 == other.index_2
+      && self.index_3 == other.index_3
+      && self.index_4 == other.index_4
+    {
+      other
+    } else if self.index_4 == other.index_4 {
+      resampled = other
+        .resample(&self.index_1, &self.index_2, &self.index_3, Interpolation::Linear)?
+        .table;
+      &resampled
+    } else {
+      return None;
+    };
+    (self.values.inner.len() == aligned.values.inner.len()).then(|| {
+      let mut table = self.clone();
+      table
+        .values
+        .inner
+        .iter_mut()
+        .zip(&aligned.values.inner)
+        .for_each(|(a, b)| *a = f(*a, *b));
+      table
+    })
+  }
+  /// Apply `f` on all the values
+  #[must_use]
+  #[inline]
+  pub fn map<F: Fn(f64) -> f64>(&self, f: F) -> Self {
+    let mut table = self.clone();
+    table.values.inner.iter_mut().for_each(|value| *value = f(*value));
+    table
+  }
+  /// Add `offset` to all values
+  #[must_use]
+  #[inline]
+  #[expect(clippy::float_arithmetic)]
+  pub fn add_scalar(&self, offset: f64) -> Self {
+    self.map(|value| value + offset)
+  }
+  /// Multiply all values by `factor`
+  #[must_use]
+  #[inline]
+  #[expect(clippy::float_arithmetic)]
+  pub fn scale(&self, factor: f64) -> Self {
+    self.map(|value| value * factor)
+  }
+  /// The element-wise minimum with `bound`
+  #[must_use]
+  #[inline]
+  pub fn min_scalar(&self, bound: f64) -> Self {
+    self.map(|value| value.min(bound))
+  }
+  /// The element-wise maximum with `bound`
+  #[must_use]
+  #[inline]
+  pub fn max_scalar(&self, bound: f64) -> Self {
+    self.map(|value| value.max(bound))
+  }
+  /// The element-wise sum, `other` is resampled onto the grid of this table
+  /// when their indices differ
+  #[must_use]
+  #[inline]
+  #[expect(clippy::float_arithmetic)]
+  pub fn add_table(&self, other: &Self) -> Option<Self> {
+    self.zip_with(other, |a, b| a + b)
+  }
+  /// The element-wise difference, see [`TableLookUp::add_table`]
+  #[must_use]
+  #[inline]
+  #[expect(clippy::float_arithmetic)]
+  pub fn sub_table(&self, other: &Self) -> Option<Self> {
+    self.zip_with(other, |a, b| a - b)
+  }
+  /// The element-wise product, see [`TableLookUp::add_table`]
+  #[must_use]
+  #[inline]
+  #[expect(clippy::float_arithmetic)]
+  pub fn mul_table(&self, other: &Self) -> Option<Self> {
+    self.zip_with(other, |a, b| a * b)
+  }
+  /// The element-wise minimum, see [`TableLookUp::add_table`]
+  #[must_use]
+  #[inline]
+  pub fn min_table(&self, other: &Self) -> Option<Self> {
+    self.zip_with(other, f64::min)
+  }
+  /// The element-wise maximum, see [`TableLookUp::add_table`]
+  #[must_use]
+  #[inline]
+  pub fn max_table(&self, other: &Self) -> Option<Self> {
+    self.zip_with(other, f64::max)
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::{DefaultCtx, ast::test_parse, timing::Timing};
+  fn timing() -> Timing<DefaultCtx> {
+    test_parse::<Timing<DefaultCtx>>(
+      r#"() {
+        cell_rise (delay_template_2x2) {
+          index_1 ("10, 30");
+          index_2 ("30, 50");
+          values ("100, 200", "300, 400");
+        }
+        ocv_mean_shift_cell_rise (delay_template_2x2) {
+          index_1 ("10, 30");
+          index_2 ("30, 50");
+          values ("0, 0", "0, 0");
+        }
+        ocv_std_dev_cell_rise (delay_template_2x2) {
+          index_1 ("10, 30");
+          index_2 ("30, 50");
+          values ("3, 6", "9, 12");
+        }
+        ocv_skewness_cell_rise (delay_template_2x2) {
+          index_1 ("10, 30");
+          index_2 ("30, 50");
+          values ("0.5, 0.5", "0.5, 0.5");
+        }
+        cell_fall (delay_template_3x2) {
+          index_1 ("10, 20, 30");
+          index_2 ("30, 50");
+          values ("10, 20", "20, 30", "30, 40");
+        }
+        ocv_sigma_cell_fall (delay_template_3x2) {
+          sigma_type : early_and_late;
+          index_1 ("10, 20, 30");
+          index_2 ("30, 50");
+          values ("4, 4", "4, 4", "4, 4");
+        }
+      }"#,
+    )
+  }
+  fn assert_close(value: f64, want: f64) {
+    assert!((value - want).abs() < 1e-9, "{value} != {want}");
+  }
+  #[test]
+  fn timing_scalar_arithmetic() {
+    let rise = timing().cell_rise.unwrap();
+    let guard_band = rise.scale(1.05);
+    assert_eq!(guard_band.values, [105.0, 210.0, 315.0, 420.0]);
+    assert_close(guard_band.lvf_moments_values[3].std_dev, 12.6);
+    assert_close(guard_band.lvf_moments_values[3].skewness, 0.5);
+    let negated = rise.scale(-1.0);
+    assert_close(negated.lvf_moments_values[0].std_dev, 3.0);
+    assert_close(negated.lvf_moments_values[0].skewness, -0.5);
+
+    let offset = rise.add_scalar(5.0);
+    assert_eq!(offset.values, [105.0, 205.0, 305.0, 405.0]);
+    assert_close(offset.lvf_moments_values[1].mean, 205.0);
+    assert_close(offset.lvf_moments_values[1].std_dev, 6.0);
+
+    let min = rise.min_scalar(250.0);
+    assert_eq!(min.values, [100.0, 200.0, 250.0, 250.0]);
+    // the bounded points are constant
+    assert_close(min.lvf_moments_values[1].std_dev, 6.0);
+    assert_eq!(
+      min.lvf_moments_values[2],
+      LVFMoments { mean: 250.0, std_dev: 0.0, skewness: 0.0 }
+    );
+    let mapped = rise.map(f64::sqrt);
+    assert_eq!(mapped.values, [10.0, 200.0_f64.sqrt(), 300.0_f64.sqrt(), 20.0]);
+    assert!(mapped.lvf_moments_values.is_empty());
+  }
+  #[test]
+  fn timing_table_arithmetic() {
+    let timing = timing();
+    let rise = timing.cell_rise.unwrap();
+    let fall = timing.cell_fall.unwrap();
+    assert_eq!(fall.lvf_early_late_values.len(), 6);
+    // `cell_fall` is resampled onto the 2x2 grid of `cell_rise`
+    let sum = rise.add_table(&fall).unwrap();
+    assert_eq!(sum.index_1, [10.0, 30.0]);
+    assert_eq!(sum.values, [110.0, 220.0, 330.0, 440.0]);
+    // the variances are added: sqrt(3^2 + 0^2)
+    assert_close(sum.lvf_moments_values[0].std_dev, 3.0);
+    assert_close(sum.lvf_moments_values[0].mean, 110.0);
+    assert_close(sum.lvf_early_late_values[0].late_sigma, 4.0);
+    let both = rise.add_table(&rise).unwrap();
+    assert_close(both.lvf_moments_values[0].std_dev, 3.0 * 2.0_f64.sqrt());
+    assert_close(both.lvf_moments_values[0].skewness, 0.5 / 2.0_f64.sqrt());
+
+    let diff = rise.sub_table(&rise).unwrap();
+    assert_eq!(diff.values, [0.0; 4]);
+    assert_close(diff.lvf_moments_values[0].std_dev, 3.0 * 2.0_f64.sqrt());
+    assert_close(diff.lvf_moments_values[0].skewness, 0.0);
+
+    let max = fall.max_table(&rise.scale(0.1)).unwrap();
+    assert_eq!(max.index_1, [10.0, 20.0, 30.0]);
+    assert_eq!(max.values, [10.0, 20.0, 20.0, 30.0, 30.0, 40.0]);
+    let product = rise.mul_table(&fall).unwrap();
+    assert_eq!(product.values, [1000.0, 4000.0, 9000.0, 16000.0]);
+  }
+  #[test]
+  fn table_arithmetic() {
+    let power = test_parse::<TableLookUp<DefaultCtx>>(
+      r#"(power_template_3) {
+        index_1 ("1, 2, 3");
+        values ("1, 2, 4");
+      }"#,
+    );
+    let other = test_parse::<TableLookUp<DefaultCtx>>(
+      r#"(power_template_2) {
+        index_1 ("1, 3");
+        values ("1, 3");
+      }"#,
+    );
+    assert_eq!(power.scale(2.0).values.inner, [2.0, 4.0, 8.0]);
+    assert_eq!(power.add_scalar(1.0).values.inner, [2.0, 3.0, 5.0]);
+    assert_eq!(power.sub_table(&other).unwrap().values.inner, [0.0, 0.0, 1.0]);
+    assert_eq!(power.max_scalar(2.0).values.inner, [2.0, 2.0, 4.0]);
+    assert_eq!(other.mul_table(&power).unwrap().values.inner, [1.0, 12.0]);
+  }
+}
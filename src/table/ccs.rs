@@ -0,0 +1,731 @@
+//! Expansion of the compact CCS timing tables into the output-current waveforms,
+//! and the compression back, see [`CompactCcsTable::expand`].
+#![allow(clippy::multiple_inherent_impl)]
+use super::{
+  CompactCcsTable, CompactLutTemplate, CompactTableCtx as _, ReferenceTimeVector3D,
+  ReferenceTimeVector3DGrpup, TableCtx as _, TableTemple, Values,
+  VariableTypeCompactLutTemplateIndex12, waveform::interpolate,
+};
+use crate::{
+  Ctx,
+  ast::{Attributes, GroupComments},
+  common::items::IdVector,
+  library::BaseCurves,
+};
+use alloc::sync::Arc;
+
+/// The names of compact CCS timing parameters in `index_3` of `compact_lut_template`
+const PARAMETERS: [&str; 6] =
+  ["init_current", "peak_current", "peak_voltage", "peak_time", "left_id", "right_id"];
+
+/// The parameters of one compact CCS timing waveform
+#[derive(Debug, Clone, Copy, Default)]
+#[derive(PartialEq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct CompactCcsParams {
+  /// The current at the start of waveform
+  pub init_current: f64,
+  /// The current at the peak, negative for the falling output
+  pub peak_current: f64,
+  /// The output voltage at the peak
+  pub peak_voltage: f64,
+  /// The time of the peak
+  pub peak_time: f64,
+  /// The `curve_y` id of the base curve from the start to the peak
+  pub left_id: usize,
+  /// The `curve_y` id of the base curve from the peak to the end
+  pub right_id: usize,
+}
+
+/// The error of [`CompactCcsTable::expand`] / [`ReferenceTimeVector3DGrpup::compress`]
+#[derive(Debug, Clone, thiserror::Error, PartialEq)]
+pub enum CcsError {
+  /// The table does not refer to a `compact_lut_template`
+  #[error("no compact_lut_template")]
+  NoTemplate,
+  /// The parameter is missing in, or unknown to, `index_3` of template
+  #[error("curve parameter {0} is not supported")]
+  Parameter(String),
+  /// The number of values mismatches with the template
+  #[error("expect {expected} values, found {found}")]
+  ValuesLength {
+    /// The number of grid points multiplied by the parameters
+    expected: usize,
+    /// The number of values
+    found: usize,
+  },
+  /// The `curve_y` is not defined, or its length mismatches with `curve_x`
+  #[error("base curve {0} is not valid")]
+  BaseCurve(usize),
+  /// The parameters of the waveform at the grid point are not valid,
+  /// e.g., zero peak current, or the peak voltage out of the swing
+  #[error("the waveform {0} is not valid")]
+  Waveform(usize),
+  /// There is no `vector` at the grid point of template
+  #[error("no vector at index_1 {index_1}, index_2 {index_2}")]
+  MissingVector {
+    /// The input transition
+    index_1: f64,
+    /// The output load
+    index_2: f64,
+  },
+}
+
+/// The result of [`ReferenceTimeVector3DGrpup::compress`]
+#[derive(Debug, Clone)]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(bound = "C::CompactTable: serde::Serialize + serde::de::DeserializeOwned")]
+pub struct CcsCompressed<C: 'static + Ctx> {
+  /// The compact CCS table
+  pub table: CompactCcsTable<C>,
+  /// The maximum current error of each waveform relative to its peak current,
+  /// in the order of the table values
+  pub errors: Vec<f64>,
+  /// The maximum of `errors`
+  pub max_error: f64,
+  /// The ids of `curve_y` added to the base curves
+  pub new_curves: Vec<usize>,
+}
+
+/// The normalized points `(x, y)` of a half curve, including the two ends,
+/// which are added only when `curve_x` does not start at `0` or end at `1`
+fn half_curve<'a>(
+  curve_x: &'a [f64],
+  curve_y: &'a [f64],
+) -> impl Iterator<Item = (f64, f64)> + 'a {
+  let start = curve_x.first().is_none_or(|x| *x > 0.0).then_some((0.0, 1.0));
+  let end = curve_x.last().is_none_or(|x| *x < 1.0).then_some((1.0, 0.0));
+  start
+    .into_iter()
+    .chain(curve_x.iter().copied().zip(curve_y.iter().copied()))
+    .chain(end)
+}
+
+impl CompactCcsParams {
+  /// The output-current waveform `(time, current)` of the base curves `left` and `right`
+  /// on `curve_x`, with the output `load` and the supply voltage `vdd`.
+  ///
+  /// The base curves are the normalized I-V curves of the output.
+  /// From the start to the peak, the output voltage is `x * peak_swing` and the current
+  /// is `peak - (peak - init) * y`. From the peak to the end, the voltage is
+  /// `peak_swing + x * (vdd - peak_swing)` and the current is `peak * y`,
+  /// where the swing is measured from the initial rail.
+  /// The time steps follow the charge balance `load * dV = I * dt`,
+  /// with the peak at `peak_time`, so the total charge is `load * vdd`.
+  /// The current and capacitance are in consistent units,
+  /// e.g., `mA * ns = pF * V`.
+  ///
+  /// Return `None` when the parameters are not valid.
+  #[must_use]
+  #[inline]
+  #[expect(clippy::float_arithmetic)]
+  pub fn waveform(
+    &self,
+    curve_x: &[f64],
+    left: &[f64],
+    right: &[f64],
+    load: f64,
+    vdd: f64,
+  ) -> Option<(Vec<f64>, Vec<f64>)> {
+    let sign = self.peak_current.signum();
+    let (peak, init) = (self.peak_current.abs(), self.init_current * sign);
+    let swing = if sign > 0.0 { self.peak_voltage } else { vdd - self.peak_voltage };
+    if !(peak > 0.0 && load > 0.0 && swing > 0.0 && swing < vdd)
+      || left.len() != curve_x.len()
+      || right.len() != curve_x.len()
+    {
+      return None;
+    }
+    // (voltage, current) from the start to the peak, and from the peak to the end
+    let rising: Vec<(f64, f64)> = half_curve(curve_x, left)
+      .map(|(x, y)| (x * swing, (init - peak).mul_add(y, peak)))
+      .collect();
+    let falling: Vec<(f64, f64)> = half_curve(curve_x, right)
+      .skip(1)
+      .map(|(x, y)| (x.mul_add(vdd - swing, swing), peak * y))
+      .collect();
+    let step = |(v0, i0): (f64, f64), (v1, i1): (f64, f64)| {
+      (i0 + i1 > 0.0 && v1 > v0).then(|| 2.0 * load * (v1 - v0) / (i0 + i1))
+    };
+    let mut times = vec![self.peak_time];
+    for pair in rising.windows(2).rev() {
+      let (&[a, b], Some(t)) = (pair, times.last()) else { return None };
+      times.push(t - step(a, b)?);
+    }
+    times.reverse();
+    let mut last = *rising.last()?;
+    for point in &falling {
+      times.push(times.last()? + step(last, *point)?);
+      last = *point;
+    }
+    let currents = rising
+      .iter()
+      .chain(&falling)
+      .map(|(_, current)| current * sign)
+      .collect();
+    Some((times, currents))
+  }
+  /// Fit the parameters and the base curves `(left, right)` on `curve_x`
+  /// from the waveform `(times, currents)`, the inverse of [`CompactCcsParams::waveform`].
+  /// The ids are left as zero.
+  ///
+  /// Return `None` when the waveform is not valid, e.g., the times are not increasing.
+  #[must_use]
+  #[inline]
+  #[expect(clippy::float_arithmetic)]
+  pub fn fit(
+    times: &[f64],
+    currents: &[f64],
+    curve_x: &[f64],
+    load: f64,
+    vdd: f64,
+  ) -> Option<(Self, Vec<f64>, Vec<f64>)> {
+    if times.len() < 2
+      || times.len() != currents.len()
+      || times.windows(2).any(|w| w.first() >= w.last())
+      || load <= 0.0
+    {
+      return None;
+    }
+    let (p, peak_current) = currents
+      .iter()
+      .copied()
+      .enumerate()
+      .max_by(|(_, a), (_, b)| a.abs().total_cmp(&b.abs()))?;
+    let sign = peak_current.signum();
+    let magnitudes: Vec<f64> = currents.iter().map(|i| i * sign).collect();
+    let (peak, init) = (magnitudes.get(p).copied()?, magnitudes.first().copied()?);
+    // the swing of output voltage by the charge
+    let mut voltages = vec![0.0];
+    for (t, i) in times.windows(2).zip(magnitudes.windows(2)) {
+      let (&[t0, t1], &[i0, i1]) = (t, i) else { return None };
+      voltages.push(voltages.last()? + (i0 + i1) * (t1 - t0) / (2.0 * load));
+    }
+    let swing = voltages.get(p).copied()?;
+    if !(peak > 0.0 && swing > 0.0 && swing < vdd) {
+      return None;
+    }
+    let (rising_voltages, rising_currents) = (voltages.get(..=p)?, magnitudes.get(..=p)?);
+    let (falling_voltages, falling_currents) = (voltages.get(p..)?, magnitudes.get(p..)?);
+    let left = curve_x
+      .iter()
+      .map(|x| {
+        let current = interpolate(rising_voltages, rising_currents, x * swing)?;
+        Some(if peak > init { (peak - current) / (peak - init) } else { 0.0 })
+      })
+      .collect::<Option<_>>()?;
+    let right = curve_x
+      .iter()
+      .map(|x| {
+        interpolate(falling_voltages, falling_currents, x.mul_add(vdd - swing, swing))
+          .map_or(0.0, |current| current / peak)
+      })
+      .collect();
+    let params = Self {
+      init_current: init * sign,
+      peak_current,
+      peak_voltage: if sign > 0.0 { swing } else { vdd - swing },
+      peak_time: times.get(p).copied()?,
+      left_id: 0,
+      right_id: 0,
+    };
+    Some((params, left, right))
+  }
+}
+
+/// The grid points `(slew, load)` of template in the order of values
+fn template_grid<C: 'static + Ctx>(template: &CompactLutTemplate<C>) -> Vec<(f64, f64)> {
+  let load_first = template.variable_1
+    == Some(VariableTypeCompactLutTemplateIndex12::TotalOutputNetCapacitance);
+  template
+    .index_1
+    .iter()
+    .flat_map(|i1| {
+      template
+        .index_2
+        .iter()
+        .map(move |i2| if load_first { (*i2, *i1) } else { (*i1, *i2) })
+    })
+    .collect()
+}
+
+/// The parameter names in `index_3` of template, which may be comma separated
+fn parameter_names<C: 'static + Ctx>(template: &CompactLutTemplate<C>) -> Vec<&str> {
+  template
+    .index_3
+    .iter()
+    .flat_map(|s| s.split(','))
+    .map(str::trim)
+    .filter(|name| !name.is_empty())
+    .collect()
+}
+
+/// The position of each parameter in `index_3` of template
+fn parameter_positions<C: 'static + Ctx>(
+  template: &CompactLutTemplate<C>,
+) -> Result<[usize; 6], CcsError> {
+  let names = parameter_names(template);
+  if let Some(unknown) = names.iter().find(|name| !PARAMETERS.contains(name)) {
+    return Err(CcsError::Parameter((*unknown).to_owned()));
+  }
+  let mut positions = [0; 6];
+  for (position, name) in positions.iter_mut().zip(PARAMETERS) {
+    *position = names
+      .iter()
+      .position(|s| *s == name)
+      .ok_or_else(|| CcsError::Parameter(name.to_owned()))?;
+  }
+  Ok(positions)
+}
+
+/// The `curve_y` of `id`, with the same length as `curve_x`
+fn base_curve<C: 'static + Ctx>(
+  base_curves: &BaseCurves<C>,
+  id: usize,
+) -> Result<&[f64], CcsError> {
+  base_curves
+    .curve_y
+    .iter()
+    .find(|curve| curve.id == id && curve.vec.len() == base_curves.curve_x.len())
+    .map(|curve| curve.vec.as_slice())
+    .ok_or(CcsError::BaseCurve(id))
+}
+
+/// The `curve_y` closest to `curve` within `tolerance`, or a new one
+#[expect(clippy::float_arithmetic)]
+fn match_curve<C: 'static + Ctx>(
+  base_curves: &mut BaseCurves<C>,
+  curve: Vec<f64>,
+  tolerance: f64,
+  new_curves: &mut Vec<usize>,
+) -> usize {
+  let distance = |other: &IdVector| {
+    curve
+      .iter()
+      .zip(&other.vec)
+      .map(|(a, b)| (a - b).abs())
+      .fold(0.0, f64::max)
+  };
+  let closest = base_curves
+    .curve_y
+    .iter()
+    .filter(|other| other.vec.len() == curve.len())
+    .map(|other| (other.id, distance(other)))
+    .min_by(|(_, a), (_, b)| a.total_cmp(b));
+  if let Some((id, d)) = closest
+    && d <= tolerance
+  {
+    return id;
+  }
+  let id = base_curves
+    .curve_y
+    .iter()
+    .map(|other| other.id)
+    .max()
+    .map_or(1, |max| max.saturating_add(1));
+  _ = base_curves.curve_y.insert(IdVector { id, vec: curve });
+  new_curves.push(id);
+  id
+}
+
+impl<C: 'static + Ctx> CompactCcsTable<C> {
+  /// The parameters at the grid points of `compact_lut_template`,
+  /// in the order of values
+  #[inline]
+  #[expect(
+    clippy::as_conversions,
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss
+  )]
+  pub fn params(&self) -> Result<Vec<CompactCcsParams>, CcsError> {
+    let template = self
+      .extra_ctx
+      .compact_lut_template()
+      .as_ref()
+      .ok_or(CcsError::NoTemplate)?;
+    let positions = parameter_positions(template)?;
+    let n = parameter_names(template).len();
+    let expected = template
+      .index_1
+      .len()
+      .saturating_mul(template.index_2.len())
+      .saturating_mul(n);
+    if n == 0 || self.values.inner.len() != expected {
+      return Err(CcsError::ValuesLength { expected, found: self.values.inner.len() });
+    }
+    let id = |v: f64| (v >= 0.0 && v.fract() == 0.0).then_some(v as usize);
+    self
+      .values
+      .inner
+      .chunks_exact(n)
+      .enumerate()
+      .map(|(k, row)| {
+        let [init, peak, voltage, time, left, right] =
+          positions.map(|position| row.get(position).copied().unwrap_or_default());
+        Ok(CompactCcsParams {
+          init_current: init,
+          peak_current: peak,
+          peak_voltage: voltage,
+          peak_time: time,
+          left_id: id(left).ok_or(CcsError::Waveform(k))?,
+          right_id: id(right).ok_or(CcsError::Waveform(k))?,
+        })
+      })
+      .collect()
+  }
+  /// Expand into the `output_current_rise`/`output_current_fall` vectors,
+  /// one per grid point of `compact_lut_template`, by the `base_curves`
+  /// and the supply voltage `vdd`, see [`CompactCcsParams::waveform`].
+  ///
+  /// The vectors refer to `current_template`, with `index_1` the input transition,
+  /// `index_2` the output load, and `reference_time` zero.
+  #[inline]
+  pub fn expand(
+    &self,
+    base_curves: &BaseCurves<C>,
+    vdd: f64,
+    current_template: &Arc<TableTemple<C>>,
+  ) -> Result<ReferenceTimeVector3DGrpup<C>, CcsError> {
+    let template = self
+      .extra_ctx
+      .compact_lut_template()
+      .as_ref()
+      .ok_or(CcsError::NoTemplate)?;
+    let vector = template_grid(template)
+      .into_iter()
+      .zip(self.params()?)
+      .enumerate()
+      .map(|(k, ((slew, load), params))| {
+        let left = base_curve(base_curves, params.left_id)?;
+        let right = base_curve(base_curves, params.right_id)?;
+        let (times, currents) = params
+          .waveform(&base_curves.curve_x, left, right, load, vdd)
+          .ok_or(CcsError::Waveform(k))?;
+        let mut extra_ctx = C::Table::default();
+        extra_ctx.set_lut_template(Some(current_template));
+        Ok(ReferenceTimeVector3D {
+          name: current_template.name.clone(),
+          comments: GroupComments::default(),
+          extra_ctx,
+          attributes: Attributes::default(),
+          reference_time: 0.0,
+          index_1: slew,
+          index_2: vec![load],
+          index_3: times,
+          values: currents,
+        })
+      })
+      .collect::<Result<_, CcsError>>()?;
+    Ok(ReferenceTimeVector3DGrpup {
+      name: None,
+      comments: GroupComments::default(),
+      extra_ctx: C::Other::default(),
+      attributes: Attributes::default(),
+      vector,
+    })
+  }
+}
+
+impl<C: 'static + Ctx> ReferenceTimeVector3DGrpup<C> {
+  /// The vector at `(slew, load)`
+  #[expect(clippy::float_arithmetic)]
+  fn find_vector(&self, slew: f64, load: f64) -> Option<&ReferenceTimeVector3D<C>> {
+    let close = |a: f64, b: f64| (a - b).abs() <= 1e-9 * a.abs().max(b.abs());
+    self.vector.iter().find(|vector| {
+      close(vector.index_1, slew)
+        && vector.index_2.first().is_some_and(|l| close(*l, load))
+    })
+  }
+  /// Compress the vectors into a compact CCS table of `template`, the inverse of
+  /// [`CompactCcsTable::expand`], with `index_1` of vectors the input transition
+  /// and `index_2` the output load.
+  ///
+  /// The fitted half curves reuse the closest `curve_y` of `base_curves` within
+  /// `tolerance` (the maximum difference of normalized current), or are added
+  /// to `base_curves` as new ones. The errors are measured by expanding the
+  /// table again, at the times of the original vectors.
+  #[inline]
+  pub fn compress(
+    &self,
+    template: &Arc<CompactLutTemplate<C>>,
+    base_curves: &mut BaseCurves<C>,
+    vdd: f64,
+    tolerance: f64,
+  ) -> Result<CcsCompressed<C>, CcsError> {
+    let positions = parameter_positions(template)?;
+    let mut inner = Vec::new();
+    let mut errors = Vec::new();
+    let mut new_curves = Vec::new();
+    for (k, (slew, load)) in template_grid(template).into_iter().enumerate() {
+      let vector = self
+        .find_vector(slew, load)
+        .ok_or(CcsError::MissingVector { index_1: slew, index_2: load })?;
+      let (mut params, left, right) = CompactCcsParams::fit(
+        &vector.index_3,
+        &vector.values,
+        &base_curves.curve_x,
+        load,
+        vdd,
+      )
+      .ok_or(CcsError::Waveform(k))?;
+      params.left_id = match_curve(base_curves, left, tolerance, &mut new_curves);
+      params.right_id = match_curve(base_curves, right, tolerance, &mut new_curves);
+      errors.push(
+        waveform_error(base_curves, &params, vector, vdd).ok_or(CcsError::Waveform(k))?,
+      );
+      let mut row = vec![0.0; parameter_names(template).len()];
+      for (position, value) in positions.into_iter().zip(params_row(&params)) {
+        if let Some(v) = row.get_mut(position) {
+          *v = value;
+        }
+      }
+      inner.extend(row);
+    }
+    let mut extra_ctx = C::CompactTable::default();
+    extra_ctx.set_compact_lut_template(Some(template));
+    Ok(CcsCompressed {
+      table: CompactCcsTable {
+        name: template.name.clone(),
+        comments: GroupComments::default(),
+        extra_ctx,
+        attributes: Attributes::default(),
+        base_curves_group: base_curves.name.clone(),
+        values: Values { chunk_size: parameter_names(template).len(), inner },
+      },
+      max_error: errors.iter().copied().fold(0.0, f64::max),
+      errors,
+      new_curves,
+    })
+  }
+}
+
+/// The parameters in the order of [`PARAMETERS`]
+#[expect(clippy::as_conversions, clippy::cast_precision_loss)]
+const fn params_row(params: &CompactCcsParams) -> [f64; 6] {
+  [
+    params.init_current,
+    params.peak_current,
+    params.peak_voltage,
+    params.peak_time,
+    params.left_id as f64,
+    params.right_id as f64,
+  ]
+}
+
+/// The maximum current error of the expanded `params` at the times of `vector`,
+/// relative to the peak current
+#[expect(clippy::float_arithmetic)]
+fn waveform_error<C: 'static + Ctx>(
+  base_curves: &BaseCurves<C>,
+  params: &CompactCcsParams,
+  vector: &ReferenceTimeVector3D<C>,
+  vdd: f64,
+) -> Option<f64> {
+  let left = base_curve(base_curves, params.left_id).ok()?;
+  let right = base_curve(base_curves, params.right_id).ok()?;
+  let load = vector.index_2.first().copied()?;
+  let (times, currents) =
+    params.waveform(&base_curves.curve_x, left, right, load, vdd)?;
+  let peak = params.peak_current.abs();
+  Some(vector.index_3.iter().zip(&vector.values).fold(0.0, |max, (t, i)| {
+    let expanded = interpolate(&times, &currents, *t).unwrap_or(0.0);
+    max.max((expanded - i).abs() / peak)
+  }))
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::{DefaultCtx, Library};
+  const LIB: &str = r#"library (test) {
+    base_curves (ctbct1) {
+      base_curve_type : ccs_timing_half_curve;
+      curve_x ("0.2, 0.4, 0.6, 0.8");
+      curve_y (1, "0.9, 0.7, 0.45, 0.2");
+      curve_y (2, "0.95, 0.8, 0.5, 0.15");
+    }
+    compact_lut_template (c_ccs_drv) {
+      base_curves_group : "ctbct1";
+      variable_1 : input_net_transition;
+      variable_2 : total_output_net_capacitance;
+      variable_3 : curve_parameters;
+      index_1 ("0.1, 0.2");
+      index_2 ("1.0");
+      index_3 ("init_current, peak_current, peak_voltage, peak_time, left_id, right_id");
+    }
+    output_current_template (ccs_template) {
+      variable_1 : input_net_transition;
+      variable_2 : total_output_net_capacitance;
+      variable_3 : time;
+    }
+    cell (INV) {
+      pin (A) { direction : input; }
+      pin (Y) {
+        direction : output;
+        function : "!A";
+        timing () {
+          related_pin : "A";
+          compact_ccs_rise (c_ccs_drv) {
+            base_curves_group : "ctbct1";
+            values ("0.1, 2.0, 0.4, 0.5, 1, 2", "0.2, 1.5, 0.45, 0.7, 2, 1");
+          }
+          compact_ccs_fall (c_ccs_drv) {
+            base_curves_group : "ctbct1";
+            values ("-0.1, -2.0, 0.6, 0.5, 1, 2", "-0.2, -1.5, 0.55, 0.7, 1, 1");
+          }
+        }
+      }
+    }
+  }"#;
+  fn assert_close(a: f64, b: f64) {
+    assert!((a - b).abs() < 1e-9, "{a} != {b}");
+  }
+  /// The charge by the trapezoidal integration
+  fn charge(vector: &ReferenceTimeVector3D<DefaultCtx>) -> f64 {
+    vector
+      .index_3
+      .windows(2)
+      .zip(vector.values.windows(2))
+      .map(|(t, i)| match (t, i) {
+        ([t0, t1], [i0, i1]) => (t1 - t0) * (i0 + i1) / 2.0,
+        _ => 0.0,
+      })
+      .sum()
+  }
+  #[test]
+  fn expand_compress() {
+    let library = Library::<DefaultCtx>::parse_lib(LIB, None).unwrap();
+    let base_curves = library.base_curves.get("ctbct1").unwrap();
+    let current_template =
+      Arc::new(library.output_current_template.get("ccs_template").unwrap().clone());
+    let compact_template =
+      Arc::new(library.compact_lut_template.get("c_ccs_drv").unwrap().clone());
+    let cell = library.cell.get("INV").unwrap();
+    let timing = cell.pin.get("Y").unwrap().timing.iter().next().unwrap();
+    let rise = timing.compact_ccs_rise.as_ref().unwrap();
+    let fall = timing.compact_ccs_fall.as_ref().unwrap();
+    let output_current_rise = rise.expand(base_curves, 1.0, &current_template).unwrap();
+    let output_current_fall = fall.expand(base_curves, 1.0, &current_template).unwrap();
+    assert_eq!(output_current_rise.vector.len(), 2);
+    for (vector, params) in output_current_rise.vector.iter().zip(rise.params().unwrap())
+    {
+      assert_eq!(vector.index_2, [1.0]);
+      assert_close(vector.values[0], params.init_current);
+      let peak = vector
+        .index_3
+        .iter()
+        .position(|t| (t - params.peak_time).abs() < 1e-12)
+        .unwrap();
+      assert_close(vector.values[peak], params.peak_current);
+      assert_close(charge(vector), 1.0);
+    }
+    assert_close(output_current_rise.vector[1].index_1, 0.2);
+    for vector in &output_current_fall.vector {
+      assert!(vector.values.iter().all(|i| *i <= 0.0));
+      assert_close(charge(vector), -1.0);
+    }
+    // compress with the same base curves
+    let mut curves = base_curves.clone();
+    for (output_current, table) in
+      [(&output_current_rise, rise), (&output_current_fall, fall)]
+    {
+      let compressed = output_current
+        .compress(&compact_template, &mut curves, 1.0, 1e-6)
+        .unwrap();
+      assert!(compressed.new_curves.is_empty());
+      assert!(compressed.max_error < 1e-9, "{}", compressed.max_error);
+      for (a, b) in compressed.table.params().unwrap().iter().zip(table.params().unwrap())
+      {
+        assert_eq!((a.left_id, a.right_id), (b.left_id, b.right_id));
+        assert_close(a.init_current, b.init_current);
+        assert_close(a.peak_current, b.peak_current);
+        assert_close(a.peak_voltage, b.peak_voltage);
+        assert_close(a.peak_time, b.peak_time);
+      }
+    }
+    // compress without base curves, the curves are re-discovered
+    curves.curve_y.clear();
+    let compressed = output_current_rise
+      .compress(&compact_template, &mut curves, 1.0, 1e-6)
+      .unwrap();
+    assert_eq!(compressed.new_curves, [1, 2]);
+    assert!(compressed.max_error < 1e-9);
+    _ = rise.expand(&curves, 1.0, &current_template).unwrap();
+    curves.curve_y.clear();
+    assert_eq!(
+      rise.expand(&curves, 1.0, &current_template).err(),
+      Some(CcsError::BaseCurve(1))
+    );
+  }
+  #[test]
+  fn half_curve_ends() {
+    let params = CompactCcsParams {
+      init_current: 0.1,
+      peak_current: 2.0,
+      peak_voltage: 0.4,
+      peak_time: 0.5,
+      left_id: 1,
+      right_id: 2,
+    };
+    let inner = params
+      .waveform(&[0.2, 0.8], &[0.9, 0.2], &[0.95, 0.15], 1.0, 1.0)
+      .unwrap();
+    // the ends given in the curves are not added again
+    let ends = params
+      .waveform(
+        &[0.0, 0.2, 0.8, 1.0],
+        &[1.0, 0.9, 0.2, 0.0],
+        &[1.0, 0.95, 0.15, 0.0],
+        1.0,
+        1.0,
+      )
+      .unwrap();
+    assert_eq!(inner.0.len(), ends.0.len());
+    for (a, b) in inner.0.iter().zip(&ends.0).chain(inner.1.iter().zip(&ends.1)) {
+      assert_close(*a, *b);
+    }
+  }
+  #[test]
+  fn expand_saed32() {
+    const SAED32: &str =
+      include_str!("../../dev/tech/SAED32_EDK/ccs/saed32hvt_pg_ff0p95v125c.lib");
+    let library = Library::<DefaultCtx>::parse_lib(SAED32, None).unwrap();
+    let current_template = Arc::new(
+      Library::<DefaultCtx>::parse_lib(LIB, None)
+        .unwrap()
+        .output_current_template
+        .get("ccs_template")
+        .unwrap()
+        .clone(),
+    );
+    let base_curves = library.base_curves.get("nc_compact_ccs_curve_1").unwrap();
+    let vdd = library.nom_voltage.unwrap();
+    let mut n = 0;
+    for timing in library
+      .cell
+      .iter()
+      .flat_map(|cell| cell.pin.iter())
+      .flat_map(|pin| pin.timing.iter())
+    {
+      for table in [timing.compact_ccs_rise.as_ref(), timing.compact_ccs_fall.as_ref()]
+        .into_iter()
+        .flatten()
+      {
+        let output_current = table.expand(base_curves, vdd, &current_template).unwrap();
+        for (vector, params) in output_current.vector.iter().zip(table.params().unwrap())
+        {
+          assert_close(vector.values[0], params.init_current);
+          assert!(vector.index_3.is_sorted_by(|t0, t1| t0 < t1));
+          let peak = vector
+            .index_3
+            .iter()
+            .position(|t| (t - params.peak_time).abs() < 1e-12)
+            .unwrap();
+          assert_close(vector.values[peak], params.peak_current);
+        }
+        n += 1;
+      }
+    }
+    assert!(n > 0);
+  }
+}
@@ -0,0 +1,403 @@
+//! Sanity check of lookup tables, and the repair of their values.
+#![allow(clippy::multiple_inherent_impl)]
+use super::{TableCtx, TableLookUp};
+use crate::{Ctx, timing::TimingTableLookUp};
+use itertools::Itertools as _;
+
+/// A defect found by [`TimingTableLookUp::check`] / [`TableLookUp::check`]
+#[derive(Clone, Copy, Debug, thiserror::Error, PartialEq, Eq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub enum TableDefect {
+  /// `index_{0}` contains NaN or infinite values
+  #[error("index_{0} contains NaN or infinite values")]
+  NonFiniteIndex(usize),
+  /// `index_{0}` contains duplicated values
+  #[error("index_{0} contains duplicated values")]
+  DuplicateIndex(usize),
+  /// `index_{0}` is not increasing
+  #[error("index_{0} is not increasing")]
+  UnsortedIndex(usize),
+  /// The number of values mismatches with the product of indices length
+  #[error("expect {expected} values, found {found}")]
+  ValuesLength {
+    /// The product of indices length
+    expected: usize,
+    /// The number of values
+    found: usize,
+  },
+  /// The number of LVF values mismatches with the number of values
+  #[error("expect {expected} LVF values, found {found}")]
+  LvfLength {
+    /// The number of values
+    expected: usize,
+    /// The number of LVF values
+    found: usize,
+  },
+  /// There are `{0}` NaN or infinite values
+  #[error("{0} NaN or infinite values")]
+  NonFiniteValue(usize),
+  /// There are `{0}` negative values
+  #[error("{0} negative values")]
+  NegativeValue(usize),
+  /// The values decrease `count` times along `index_{axis}`
+  #[error("values decrease {count} times along index_{axis}")]
+  NonMonotonic {
+    /// The `index_{axis}`
+    axis: usize,
+    /// The number of decreasing steps
+    count: usize,
+  },
+}
+
+/// The expected properties of table values
+#[derive(Debug, Clone, Copy, Default)]
+#[derive(Hash, PartialEq, Eq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct TableCheck {
+  /// The values should not decrease along all the indices, e.g., delay versus load
+  pub monotonic: bool,
+  /// The values should not be negative, e.g., transition
+  pub non_negative: bool,
+}
+
+impl TableCheck {
+  /// The expected properties of the table with group name `key`
+  /// + `cell_rise`, `cell_fall`: monotonic
+  /// + `rise_transition`, `fall_transition`, `retain_rise_slew`, `retain_fall_slew`:
+  ///   monotonic and non-negative
+  /// + others (e.g., constraint and power): none
+  #[must_use]
+  #[inline]
+  pub fn for_table(key: &str) -> Self {
+    match key {
+      "cell_rise" | "cell_fall" => Self { monotonic: true, non_negative: false },
+      "rise_transition" | "fall_transition" | "retain_rise_slew" | "retain_fall_slew" => {
+        Self { monotonic: true, non_negative: true }
+      }
+      _ => Self::default(),
+    }
+  }
+}
+
+/// The repair strategies of [`TimingTableLookUp::repair`] / [`TableLookUp::repair`]
+#[derive(Debug, Clone, Copy, Default)]
+#[derive(Hash, PartialEq, Eq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct TableRepair {
+  /// Clamp the negative values to zero, for [`TableCheck::non_negative`] tables
+  pub clamp: bool,
+  /// Smooth the values into monotonic by least-squares isotonic regression
+  /// along each index, for [`TableCheck::monotonic`] tables
+  pub monotone: bool,
+}
+
+/// The table values in row-major order, on the non-empty indices
+#[derive(Debug)]
+struct Grid<'a> {
+  /// `(axis, index)`, the axis starts from 1
+  indices: Vec<(usize, &'a [f64])>,
+}
+
+impl<'a> Grid<'a> {
+  fn new<I: IntoIterator<Item = &'a [f64]>>(all_indices: I) -> Self {
+    Self {
+      indices: all_indices
+        .into_iter()
+        .enumerate()
+        .filter(|(_, index)| !index.is_empty())
+        .map(|(i, index)| (i.saturating_add(1), index))
+        .collect(),
+    }
+  }
+  fn len(&self) -> usize {
+    self.indices.iter().map(|(_, index)| index.len()).product()
+  }
+  fn check_indices(&self) -> Vec<TableDefect> {
+    let mut defects = Vec::new();
+    for (axis, index) in &self.indices {
+      if index.iter().any(|x| !x.is_finite()) {
+        defects.push(TableDefect::NonFiniteIndex(*axis));
+      } else {
+        if index.iter().tuple_windows().any(|(a, b)| a.total_cmp(b).is_eq()) {
+          defects.push(TableDefect::DuplicateIndex(*axis));
+        }
+        if index.iter().tuple_windows().any(|(a, b)| a > b) {
+          defects.push(TableDefect::UnsortedIndex(*axis));
+        }
+      }
+    }
+    defects
+  }
+  /// All the lines along the `k`-th non-empty index, as the positions of values
+  #[expect(clippy::arithmetic_side_effects)]
+  fn lines(&self, k: usize) -> Vec<Vec<usize>> {
+    let stride: usize = self
+      .indices
+      .iter()
+      .skip(k + 1)
+      .map(|(_, index)| index.len())
+      .product();
+    let len = self.indices.get(k).map_or(0, |(_, index)| index.len());
+    let outer: usize =
+      self.indices.iter().take(k).map(|(_, index)| index.len()).product();
+    (0..outer)
+      .flat_map(|o| (0..stride).map(move |j| o * len * stride + j))
+      .map(|start| (0..len).map(|i| start + i * stride).collect())
+      .collect()
+  }
+  fn check_values(&self, values: &[f64], check: TableCheck) -> Vec<TableDefect> {
+    let mut defects = Vec::new();
+    if values.len() != self.len() {
+      defects
+        .push(TableDefect::ValuesLength { expected: self.len(), found: values.len() });
+      return defects;
+    }
+    let non_finite = values.iter().filter(|v| !v.is_finite()).count();
+    if non_finite != 0 {
+      defects.push(TableDefect::NonFiniteValue(non_finite));
+    }
+    if check.non_negative {
+      let negative = values.iter().filter(|v| **v < 0.0).count();
+      if negative != 0 {
+        defects.push(TableDefect::NegativeValue(negative));
+      }
+    }
+    if check.monotonic {
+      for (k, (axis, _)) in self.indices.iter().enumerate() {
+        let count = self
+          .lines(k)
+          .iter()
+          .map(|line| {
+            line
+              .iter()
+              .filter_map(|position| values.get(*position))
+              .tuple_windows()
+              .filter(|(a, b)| b < a)
+              .count()
+          })
+          .sum();
+        if count != 0 {
+          defects.push(TableDefect::NonMonotonic { axis: *axis, count });
+        }
+      }
+    }
+    defects
+  }
+  /// Return whether any value is changed
+  fn repair(&self, values: &mut [f64], check: TableCheck, repair: TableRepair) -> bool {
+    if values.len() != self.len() || values.iter().any(|v| !v.is_finite()) {
+      return false;
+    }
+    let mut changed = false;
+    if check.monotonic && repair.monotone {
+      for k in 0..self.indices.len() {
+        for line in self.lines(k) {
+          let line_values: Vec<f64> = line
+            .iter()
+            .filter_map(|position| values.get(*position).copied())
+            .collect();
+          for (position, value) in line.iter().zip(isotonic(&line_values)) {
+            if let Some(v) = values.get_mut(*position) {
+              changed |= v.total_cmp(&value).is_ne();
+              *v = value;
+            }
+          }
+        }
+      }
+    }
+    if check.non_negative && repair.clamp {
+      for v in values.iter_mut().filter(|v| **v < 0.0) {
+        *v = 0.0;
+        changed = true;
+      }
+    }
+    changed
+  }
+}
+
+/// The defects of `all_indices`, and the mismatch of the number of values
+pub(super) fn grid_defects(
+  all_indices: &[&[f64]],
+  values_len: usize,
+) -> Vec<TableDefect> {
+  let grid = Grid::new(all_indices.iter().copied());
+  let mut defects = grid.check_indices();
+  if values_len != grid.len() {
+    defects.push(TableDefect::ValuesLength { expected: grid.len(), found: values_len });
+  }
+  defects
+}
+
+/// The indices of table, the omitted ones are inherited from its template
+#[cfg_attr(not(feature = "lut_template"), expect(unused_variables))]
+pub(super) fn table_indices<'a, C: 'static + Ctx>(
+  ctx: &'a impl TableCtx<C>,
+  own: [&'a [f64]; 4],
+) -> Vec<&'a [f64]> {
+  #[cfg(feature = "lut_template")]
+  if let Some(template) = ctx.lut_template().as_deref() {
+    return own
+      .into_iter()
+      .zip([&template.index_1, &template.index_2, &template.index_3, &template.index_4])
+      .map(
+        |(index, inherited)| {
+          if index.is_empty() { inherited.as_deref().unwrap_or(index) } else { index }
+        },
+      )
+      .collect();
+  }
+  own.to_vec()
+}
+
+/// The least-squares non-decreasing fit, by the pool adjacent violators algorithm
+#[expect(
+  clippy::float_arithmetic,
+  clippy::as_conversions,
+  clippy::cast_precision_loss,
+  clippy::arithmetic_side_effects
+)]
+fn isotonic(values: &[f64]) -> Vec<f64> {
+  // (mean, count) of the pooled blocks
+  let mut blocks: Vec<(f64, usize)> = Vec::with_capacity(values.len());
+  for value in values {
+    let mut block = (*value, 1_usize);
+    while let Some(last) = blocks.last().copied() {
+      if last.0 <= block.0 {
+        break;
+      }
+      _ = blocks.pop();
+      let count = last.1 + block.1;
+      block =
+        (last.0.mul_add(last.1 as f64, block.0 * block.1 as f64) / count as f64, count);
+    }
+    blocks.push(block);
+  }
+  blocks
+    .into_iter()
+    .flat_map(|(mean, count)| core::iter::repeat_n(mean, count))
+    .collect()
+}
+
+impl<C: 'static + Ctx> TimingTableLookUp<C> {
+  /// Check the indices (inherited from the template when omitted), the number of values (including the LVF values),
+  /// the NaN or infinite values, and the expected properties in `check`.
+  ///
+  /// The defects break [`TimingTableLookUp::lookup`].
+  #[must_use]
+  #[inline]
+  pub fn check(&self, check: TableCheck) -> Vec<TableDefect> {
+    let grid =
+      Grid::new(table_indices(&self.extra_ctx, [&self.index_1, &self.index_2, &[], &[]]));
+    let mut defects = grid.check_indices();
+    defects.extend(grid.check_values(&self.values, check));
+    for found in [self.lvf_moments_values.len(), self.lvf_early_late_values.len()] {
+      if found != 0 && found != self.values.len() {
+        defects.push(TableDefect::LvfLength { expected: self.values.len(), found });
+      }
+    }
+    defects
+  }
+  /// Repair the values with the strategies in `repair`, the LVF mean is shifted
+  /// along with the value. The tables with index defects, NaN or infinite values,
+  /// or mismatched length are not repaired.
+  ///
+  /// Return whether any value is changed.
+  #[inline]
+  #[expect(clippy::float_arithmetic)]
+  pub fn repair(&mut self, check: TableCheck, repair: TableRepair) -> bool {
+    let grid =
+      Grid::new(table_indices(&self.extra_ctx, [&self.index_1, &self.index_2, &[], &[]]));
+    if !grid.check_indices().is_empty() {
+      return false;
+    }
+    let old_values = self.values.clone();
+    let changed = grid.repair(&mut self.values, check, repair);
+    if changed && self.lvf_moments_values.len() == self.values.len() {
+      for ((moments, old), new) in
+        self.lvf_moments_values.iter_mut().zip(old_values).zip(&self.values)
+      {
+        moments.mean += new - old;
+      }
+    }
+    changed
+  }
+}
+
+impl<C: 'static + Ctx> TableLookUp<C> {
+  /// Check the indices (inherited from the template when omitted),
+  /// the number of values, the NaN or infinite values,
+  /// and the expected properties in `check`
+  #[must_use]
+  #[inline]
+  pub fn check(&self, check: TableCheck) -> Vec<TableDefect> {
+    let grid = Grid::new(table_indices(
+      &self.extra_ctx,
+      [&self.index_1, &self.index_2, &self.index_3, &self.index_4],
+    ));
+    let mut defects = grid.check_indices();
+    defects.extend(grid.check_values(&self.values.inner, check));
+    defects
+  }
+  /// Repair the values with the strategies in `repair`.
+  /// The tables with index defects, NaN or infinite values,
+  /// or mismatched length are not repaired.
+  ///
+  /// Return whether any value is changed.
+  #[inline]
+  pub fn repair(&mut self, check: TableCheck, repair: TableRepair) -> bool {
+    let grid = Grid::new(table_indices(
+      &self.extra_ctx,
+      [&self.index_1, &self.index_2, &self.index_3, &self.index_4],
+    ));
+    grid.check_indices().is_empty() && grid.repair(&mut self.values.inner, check, repair)
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::{DefaultCtx, ast::test_parse, timing::Timing};
+  #[test]
+  fn repair_monotone() {
+    let mut timing = test_parse::<Timing<DefaultCtx>>(
+      r#"() {
+        cell_rise (delay_template_3x3) {
+          index_1 ("10, 20, 30");
+          index_2 ("30, 50, 70");
+          values ("1, 4, 3", "2, 1, 5", "3, 6, 7");
+        }
+        ocv_mean_shift_cell_rise (delay_template_3x3) {
+          index_1 ("10, 20, 30");
+          index_2 ("30, 50, 70");
+          values ("0, 0, 0", "0, 0, 0", "0, 0, 0");
+        }
+        ocv_std_dev_cell_rise (delay_template_3x3) {
+          index_1 ("10, 20, 30");
+          index_2 ("30, 50, 70");
+          values ("1, 1, 1", "1, 1, 1", "1, 1, 1");
+        }
+        ocv_skewness_cell_rise (delay_template_3x3) {
+          index_1 ("10, 20, 30");
+          index_2 ("30, 50, 70");
+          values ("0, 0, 0", "0, 0, 0", "0, 0, 0");
+        }
+      }"#,
+    );
+    let check = TableCheck::for_table("cell_rise");
+    let table = timing.cell_rise.as_mut().unwrap();
+    assert_eq!(
+      table.check(check),
+      [
+        TableDefect::NonMonotonic { axis: 1, count: 1 },
+        TableDefect::NonMonotonic { axis: 2, count: 2 }
+      ]
+    );
+    assert!(table.repair(check, TableRepair { clamp: false, monotone: true }));
+    assert!(table.check(check).is_empty());
+    assert_eq!(table.values, [1.0, 2.5, 3.0, 2.0, 2.5, 5.0, 3.0, 6.0, 7.0]);
+    for (moments, value) in table.lvf_moments_values.iter().zip(&table.values) {
+      assert!(moments.mean.total_cmp(value).is_eq());
+    }
+    assert!(!table.repair(check, TableRepair { clamp: false, monotone: true }));
+  }
+}
@@ -0,0 +1,471 @@
+//! Conversion between the lookup tables and the polynomial tables,
+//! see [`TimingTableLookUp::fit_poly`] and [`TableLookUp::tabulate`].
+#![allow(clippy::multiple_inherent_impl)]
+use super::{
+  PolyError, PolyPoint, PolyTableCtx as _, PolyTemplate, TableCtx as _, TableDefect,
+  TableLookUp, TableLookUpDomain, TableTemple, Values, Variable,
+  check::{grid_defects, table_indices},
+  poly::evaluate,
+};
+use crate::{Ctx, ast::GroupComments, timing::TimingTableLookUp};
+use alloc::sync::Arc;
+
+/// The polynomial fitted from a lookup table, see [`TimingTableLookUp::fit_poly`]
+#[derive(Debug, Clone)]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(bound = "C::Table: serde::Serialize + serde::de::DeserializeOwned")]
+pub struct PolyFit<C: 'static + Ctx> {
+  /// The polynomial table with `orders` and `coefs`, linked to the `poly_template`
+  pub table: TableLookUpDomain<C>,
+  /// The maximum absolute error on the table points
+  pub max_error: f64,
+  /// The root-mean-square error on the table points
+  pub rms_error: f64,
+}
+
+/// The error of [`TimingTableLookUp::fit_poly`] / [`TableLookUp::fit_poly`]
+#[derive(Debug, Clone, Copy, thiserror::Error, PartialEq, Eq)]
+pub enum FitError {
+  /// The `variables` of `poly_template` mismatch with the
+  /// variables of the non-empty indices
+  #[error("the variables of poly_template mismatch with the table")]
+  Variables,
+  /// The number of orders mismatches with the variables
+  #[error("expect {expected} orders, found {found}")]
+  Orders {
+    /// The number of variables
+    expected: usize,
+    /// The number of orders
+    found: usize,
+  },
+  /// There are fewer table points than coefs
+  #[error("{coefs} coefs can not be fitted by {points} points")]
+  Underdetermined {
+    /// The number of coefs
+    coefs: usize,
+    /// The number of table points
+    points: usize,
+  },
+  /// The coefs can not be decided, e.g., the order is not less than
+  /// the number of points on that index
+  #[error("the fitting is singular")]
+  Singular,
+  /// The table is not able to fit
+  #[error(transparent)]
+  Table(#[from] TableDefect),
+}
+
+/// The variables of the non-empty indices, by the `lu_table_template`
+fn axis_variables<C: 'static + Ctx>(
+  ctx: &C::Table,
+  all_indices: &[&[f64]],
+) -> Vec<Option<Variable>> {
+  let variables = ctx.lut_template().as_deref().map_or([None; 4], |template| {
+    [template.variable_1, template.variable_2, template.variable_3, template.variable_4]
+  });
+  all_indices
+    .iter()
+    .zip(variables)
+    .filter(|(index, _)| !index.is_empty())
+    .map(|(_, variable)| variable)
+    .collect()
+}
+
+/// For each variable of `poly_template`, the position of its non-empty index.
+/// The variables are taken in order when any index variable is unknown.
+fn variable_axes(
+  poly_variables: &[Variable],
+  axis_variables: &[Option<Variable>],
+) -> Result<Vec<usize>, FitError> {
+  if poly_variables.len() != axis_variables.len() {
+    return Err(FitError::Variables);
+  }
+  let Some(known) = axis_variables.iter().copied().collect::<Option<Vec<_>>>() else {
+    return Ok((0..poly_variables.len()).collect());
+  };
+  let axes: Vec<usize> = poly_variables
+    .iter()
+    .map(|variable| known.iter().position(|v| v == variable))
+    .collect::<Option<_>>()
+    .ok_or(FitError::Variables)?;
+  if (0..axes.len()).all(|axis| axes.contains(&axis)) {
+    Ok(axes)
+  } else {
+    Err(FitError::Variables)
+  }
+}
+
+/// All the points of grid `indices` in row-major order,
+/// the last index changes fastest like `values`
+fn grid_points(indices: &[&[f64]]) -> Vec<Vec<f64>> {
+  indices.iter().fold(vec![Vec::new()], |points, index| {
+    points
+      .iter()
+      .flat_map(|point| {
+        index.iter().map(|x| {
+          let mut next = point.clone();
+          next.push(*x);
+          next
+        })
+      })
+      .collect()
+  })
+}
+
+/// The least-squares solution of `columns * x = b`,
+/// by Householder QR on the normalized columns
+#[expect(
+  clippy::float_arithmetic,
+  clippy::arithmetic_side_effects,
+  clippy::indexing_slicing
+)]
+fn least_squares(mut columns: Vec<Vec<f64>>, mut b: Vec<f64>) -> Option<Vec<f64>> {
+  let n = columns.len();
+  let mut scales = Vec::with_capacity(n);
+  for column in &mut columns {
+    let norm = column.iter().map(|a| a * a).sum::<f64>().sqrt();
+    if norm <= f64::MIN_POSITIVE {
+      return None;
+    }
+    for a in column.iter_mut() {
+      *a /= norm;
+    }
+    scales.push(norm);
+  }
+  for k in 0..n {
+    let norm = columns[k][k..].iter().map(|a| a * a).sum::<f64>().sqrt();
+    if norm <= 1e-12 {
+      return None;
+    }
+    let alpha = if columns[k][k] > 0.0 { -norm } else { norm };
+    let mut v = columns[k][k..].to_vec();
+    v[0] -= alpha;
+    let v_norm2 = v.iter().map(|a| a * a).sum::<f64>();
+    let reflect = |target: &mut [f64]| {
+      let s = v.iter().zip(target.iter()).map(|(vi, ti)| vi * ti).sum::<f64>();
+      let factor = 2.0 * s / v_norm2;
+      for (ti, vi) in target.iter_mut().zip(&v) {
+        *ti = factor.mul_add(-vi, *ti);
+      }
+    };
+    for column in &mut columns[k..] {
+      reflect(&mut column[k..]);
+    }
+    reflect(&mut b[k..]);
+  }
+  let mut x = vec![0.0; n];
+  for k in (0..n).rev() {
+    let sum = (k + 1..n).map(|j| columns[j][k] * x[j]).sum::<f64>();
+    x[k] = (b[k] - sum) / columns[k][k];
+  }
+  Some(x.iter().zip(scales).map(|(xi, scale)| xi / scale).collect())
+}
+
+/// Fit the `values` on the grid of `all_indices` by the polynomial of `orders`
+#[expect(clippy::float_arithmetic, clippy::as_conversions, clippy::cast_precision_loss)]
+fn fit<C: 'static + Ctx>(
+  ctx: &C::Table,
+  all_indices: &[&[f64]],
+  values: &[f64],
+  template: &Arc<PolyTemplate<C>>,
+  poly_orders: &[usize],
+) -> Result<PolyFit<C>, FitError> {
+  if let Some(defect) = grid_defects(all_indices, values.len()).into_iter().next() {
+    return Err(defect.into());
+  }
+  let axes = variable_axes(&template.variables, &axis_variables::<C>(ctx, all_indices))?;
+  if poly_orders.len() != axes.len() {
+    return Err(FitError::Orders { expected: axes.len(), found: poly_orders.len() });
+  }
+  let indices: Vec<&[f64]> = all_indices
+    .iter()
+    .copied()
+    .filter(|index| !index.is_empty())
+    .collect();
+  // the points in the order of poly variables
+  let points: Vec<Vec<f64>> = grid_points(&indices)
+    .into_iter()
+    .map(|point| axes.iter().filter_map(|axis| point.get(*axis).copied()).collect())
+    .collect();
+  let orders: Vec<isize> = poly_orders
+    .iter()
+    .map(|order| isize::try_from(*order).unwrap_or(isize::MAX))
+    .collect();
+  let n_coefs = orders
+    .iter()
+    .map(|order| order.unsigned_abs().saturating_add(1))
+    .product();
+  if n_coefs > points.len() {
+    return Err(FitError::Underdetermined { coefs: n_coefs, points: points.len() });
+  }
+  // the column of each coef is the polynomial with that coef only
+  let columns = (0..n_coefs)
+    .map(|i| {
+      let mut unit = vec![0.0; n_coefs];
+      if let Some(c) = unit.get_mut(i) {
+        *c = 1.0;
+      }
+      points
+        .iter()
+        .map(|x| evaluate(&orders, &unit, x).unwrap_or(f64::NAN))
+        .collect()
+    })
+    .collect();
+  let coefs = least_squares(columns, values.to_vec())
+    .filter(|coefs| coefs.iter().all(|c| c.is_finite()))
+    .ok_or(FitError::Singular)?;
+  let (mut max_error, mut sum_square) = (0.0_f64, 0.0);
+  for (x, value) in points.iter().zip(values) {
+    let error = evaluate(&orders, &coefs, x).unwrap_or(f64::NAN) - value;
+    max_error = max_error.max(error.abs());
+    sum_square = error.mul_add(error, sum_square);
+  }
+  let mut poly_ctx = C::PolyTable::default();
+  poly_ctx.set_poly_template(Some(template));
+  Ok(PolyFit {
+    table: TableLookUpDomain {
+      name: template.name.clone(),
+      comments: GroupComments::default(),
+      extra_ctx: C::Table::default(),
+      poly_ctx,
+      attributes: crate::ast::Attributes::default(),
+      index_1: Vec::new(),
+      index_2: Vec::new(),
+      index_3: Vec::new(),
+      index_4: Vec::new(),
+      values: Values::default(),
+      coefs: Values { chunk_size: orders.len(), inner: coefs },
+      orders,
+    },
+    max_error,
+    rms_error: (sum_square / points.len() as f64).sqrt(),
+  })
+}
+
+impl<C: 'static + Ctx> TimingTableLookUp<C> {
+  /// Fit the values by the polynomial of `orders` with least-squares `coefs`,
+  /// see [`TableLookUp::fit_poly`].
+  #[inline]
+  pub fn fit_poly(
+    &self,
+    template: &Arc<PolyTemplate<C>>,
+    orders: &[usize],
+  ) -> Result<PolyFit<C>, FitError> {
+    let all_indices =
+      table_indices(&self.extra_ctx, [&self.index_1, &self.index_2, &[], &[]]);
+    fit(&self.extra_ctx, &all_indices, &self.values, template, orders)
+  }
+}
+
+impl<C: 'static + Ctx> TableLookUp<C> {
+  /// Fit the values by the polynomial of `orders` with least-squares `coefs`,
+  /// the result is a [`TableLookUpDomain`] linked to `template`,
+  /// along with the fitting errors on the table points.
+  ///
+  /// The `variables` of `template` are matched with the variables of the
+  /// non-empty indices by the `lu_table_template`, or taken in order when the
+  /// latter are unknown. `orders` are in the order of `variables`.
+  /// The `variable_i_range` and domains of `template` are not used.
+  #[inline]
+  pub fn fit_poly(
+    &self,
+    template: &Arc<PolyTemplate<C>>,
+    orders: &[usize],
+  ) -> Result<PolyFit<C>, FitError> {
+    let all_indices = table_indices(
+      &self.extra_ctx,
+      [&self.index_1, &self.index_2, &self.index_3, &self.index_4],
+    );
+    fit(&self.extra_ctx, &all_indices, &self.values.inner, template, orders)
+  }
+}
+
+/// The indices of `template`, and the values of `eval` on its grid
+fn tabulate<C: 'static + Ctx>(
+  template: &TableTemple<C>,
+  point: &PolyPoint,
+  eval: impl Fn(&PolyPoint) -> Result<f64, PolyError>,
+) -> Result<(Vec<Vec<f64>>, Vec<f64>), PolyError> {
+  let mut variables = Vec::new();
+  let mut indices = Vec::new();
+  for (i, (template_variable, index)) in [
+    (template.variable_1, &template.index_1),
+    (template.variable_2, &template.index_2),
+    (template.variable_3, &template.index_3),
+    (template.variable_4, &template.index_4),
+  ]
+  .into_iter()
+  .enumerate()
+  {
+    if let Some(variable) = template_variable {
+      variables.push(variable);
+      indices.push(index.clone().ok_or(PolyError::TemplateIndex(i.saturating_add(1)))?);
+    }
+  }
+  let grid: Vec<&[f64]> = indices.iter().map(Vec::as_slice).collect();
+  let values = grid_points(&grid)
+    .into_iter()
+    .map(|x| {
+      eval(
+        &variables
+          .iter()
+          .zip(x)
+          .fold(point.clone(), |p, (variable, value)| p.variable(*variable, value)),
+      )
+    })
+    .collect::<Result<_, _>>()?;
+  Ok((indices, values))
+}
+
+duplicate::duplicate! {
+  [
+    table_type             lookup_type         eval_doc;
+    [TableLookUp<C>]       [Self]              ["[`TableLookUp::poly_eval`], the domain is selected on each point"];
+    [TableLookUpDomain<C>] [TableLookUp::<C>]  ["[`TableLookUpDomain::poly_eval`]"];
+  ]
+  impl<C: 'static + Ctx> table_type {
+    /// Tabulate the polynomial onto the grid of `template`, the inverse of `fit_poly`.
+    ///
+    /// The template variables take the index values, and the other variables are given
+    /// in `point`. The polynomial is evaluated by
+    #[doc = eval_doc]
+    #[doc = "."]
+    #[inline]
+    pub fn tabulate(
+      &self,
+      template: &Arc<TableTemple<C>>,
+      point: &PolyPoint,
+    ) -> Result<lookup_type, PolyError> {
+      let (grid_indices, values) = tabulate(template, point, |p| Ok(self.poly_eval(p)?.value))?;
+      let mut extra_ctx = C::Table::default();
+      extra_ctx.set_lut_template(Some(template));
+      let chunk_size = grid_indices.last().map_or(values.len(), Vec::len);
+      let mut indices = grid_indices.into_iter();
+      Ok(lookup_type {
+        name: template.name.clone(),
+        comments: GroupComments::default(),
+        extra_ctx,
+        poly_ctx: C::PolyTable::default(),
+        attributes: crate::ast::Attributes::default(),
+        index_1: indices.next().unwrap_or_default(),
+        index_2: indices.next().unwrap_or_default(),
+        index_3: indices.next().unwrap_or_default(),
+        index_4: indices.next().unwrap_or_default(),
+        values: Values { chunk_size, inner: values },
+        orders: Vec::new(),
+        coefs: Values::default(),
+        domain: crate::ast::LibertySet::default(),
+      })
+    }
+    /// Tabulate the polynomial onto the grid of `template` like `tabulate`,
+    /// as a timing table, the `template` has at most 2 variables.
+    #[inline]
+    pub fn tabulate_timing(
+      &self,
+      template: &Arc<TableTemple<C>>,
+      point: &PolyPoint,
+    ) -> Result<TimingTableLookUp<C>, PolyError> {
+      let (grid_indices, values) = tabulate(template, point, |p| Ok(self.poly_eval(p)?.value))?;
+      if grid_indices.len() > 2 {
+        return Err(PolyError::Dimension { expected: 2, found: grid_indices.len() });
+      }
+      let mut table = TimingTableLookUp::<C>::default();
+      table.extra_ctx.set_lut_template(Some(template));
+      table.name.clone_from(&template.name);
+      let mut indices = grid_indices.into_iter();
+      table.index_1 = indices.next().unwrap_or_default();
+      table.index_2 = indices.next().unwrap_or_default();
+      table.values = values;
+      Ok(table)
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::{DefaultCtx, Library};
+  const LIB: &str = r#"library (test) {
+    lu_table_template (delay_template_3x3) {
+      variable_1 : input_net_transition;
+      variable_2 : total_output_net_capacitance;
+      index_1 ("0.1, 0.5, 1.0");
+      index_2 ("0.01, 0.1, 0.5");
+    }
+    lu_table_template (delay_template_2x2) {
+      variable_1 : total_output_net_capacitance;
+      variable_2 : input_net_transition;
+      index_1 ("0.2, 0.3");
+      index_2 ("0.4, 0.8");
+    }
+    poly_template (delay_poly) {
+      variables (total_output_net_capacitance, input_net_transition);
+    }
+    cell (INV) {
+      pin (A) { direction : input; }
+      pin (Y) {
+        direction : output;
+        function : "!A";
+        timing () {
+          related_pin : "A";
+          cell_rise (delay_template_3x3) {
+            values ("1.2305, 1.505, 2.725", \
+                    "2.0325, 2.325, 3.625", \
+                    "3.035, 3.35, 4.75");
+          }
+        }
+      }
+    }
+  }"#;
+  fn assert_close(a: f64, b: f64) {
+    assert!((a - b).abs() < 1e-9, "{a} != {b}");
+  }
+  #[test]
+  fn fit_and_tabulate() {
+    let library = Library::<DefaultCtx>::parse_lib(LIB, None).unwrap();
+    let template = Arc::new(library.poly_template.get("delay_poly").unwrap().clone());
+    let cell = library.cell.get("INV").unwrap();
+    let timing = cell.pin.get("Y").unwrap().timing.iter().next().unwrap();
+    let cell_rise = timing.cell_rise.as_ref().unwrap();
+    // 1 + 3*c + 2*t + 0.5*c*t, the capacitance changes fastest
+    let fit = cell_rise.fit_poly(&template, &[1, 1]).unwrap();
+    assert_eq!(fit.table.name, "delay_poly");
+    assert_eq!(fit.table.orders, [1, 1]);
+    for (coef, expected) in fit.table.coefs.inner.iter().zip([1.0, 3.0, 2.0, 0.5]) {
+      assert_close(*coef, expected);
+    }
+    assert!(fit.max_error < 1e-9 && fit.rms_error <= fit.max_error);
+    // back to the grid of lookup table
+    let lu_template = cell_rise.extra_ctx.lut_template().clone().unwrap();
+    let table = fit
+      .table
+      .tabulate_timing(&lu_template, &PolyPoint::default())
+      .unwrap();
+    assert_eq!(table.index_1, [0.1, 0.5, 1.0]);
+    for (value, expected) in table.values.iter().zip(&cell_rise.values) {
+      assert_close(*value, *expected);
+    }
+    // the transposed template
+    let lu_template =
+      Arc::new(library.lu_table_template.get("delay_template_2x2").unwrap().clone());
+    let table = fit.table.tabulate(&lu_template, &PolyPoint::default()).unwrap();
+    assert_eq!(table.values.chunk_size, 2);
+    assert_close(
+      table.values.inner[1],
+      // 1 + 3 * 0.2 + 2 * 0.8 + 0.5 * 0.2 * 0.8
+      3.28,
+    );
+    // the lower orders leave the residual
+    let fit = cell_rise.fit_poly(&template, &[1, 0]).unwrap();
+    assert!(fit.max_error > 0.1 && fit.rms_error > 0.0);
+    assert_eq!(
+      cell_rise.fit_poly(&template, &[3, 3]).err(),
+      Some(FitError::Underdetermined { coefs: 16, points: 9 })
+    );
+    assert_eq!(cell_rise.fit_poly(&template, &[3, 0]).err(), Some(FitError::Singular));
+    assert_eq!(
+      cell_rise.fit_poly(&template, &[1]).err(),
+      Some(FitError::Orders { expected: 2, found: 1 })
+    );
+  }
+}
@@ -0,0 +1,675 @@
+//! Interpolation on the grid of lookup tables, and re-gridding
+//! the tables onto new index vectors.
+#![allow(clippy::multiple_inherent_impl, clippy::redundant_pub_crate)]
+#[cfg(feature = "lut_template")]
+use super::{TableCtx as _, TableTemple};
+use super::{TableLookUp, Values};
+use crate::{
+  Ctx,
+  timing::{LVFEarlyLate, LVFMoments, TimingTableLookUp},
+};
+#[cfg(feature = "lut_template")]
+use alloc::sync::Arc;
+use itertools::Itertools as _;
+
+/// The interpolation between the table points
+#[derive(Debug, Clone, Copy, Default)]
+#[derive(Hash, PartialEq, Eq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub enum Interpolation {
+  /// Linear (bilinear for 2-D table) interpolation and extrapolation,
+  /// the same as [`TimingTableLookUp::lookup`]
+  #[default]
+  Linear,
+  /// The value of the nearest table point,
+  /// the points out of range take the value at the boundary
+  Nearest,
+  /// Monotone cubic (Fritsch–Carlson, also known as PCHIP) interpolation along
+  /// each index in turn, which never overshoots the table values.
+  /// The points out of range are extrapolated linearly like [`Interpolation::Linear`],
+  /// and the indices with two points are interpolated linearly.
+  MonotoneCubic,
+}
+
+/// The re-gridded table, see [`TimingTableLookUp::resample`]
+#[derive(Debug, Clone)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct Resampled<T> {
+  /// The table on the new grid
+  pub table: T,
+  /// The new grid points outside the original grid, in the order of the non-empty
+  /// indices. Their values are extrapolated (or clamped for [`Interpolation::Nearest`]).
+  pub extrapolated: Vec<Vec<f64>>,
+}
+
+/// The value type which can be interpolated
+pub(crate) trait Lerp: Copy {
+  /// `self + (other - self) * t`
+  fn lerp(self, other: Self, t: f64) -> Self;
+  /// The monotone cubic interpolation of the `line` on `index`,
+  /// between `lo` and `lo + 1` with the weight `t`
+  fn cubic(index: &[f64], line: &[Self], lo: usize, t: f64) -> Option<Self>;
+}
+
+/// The slope of monotone cubic interpolation at the end `(x_0, y_0)`,
+/// with the width `h` and secant `delta` of the two nearest intervals
+#[expect(clippy::float_arithmetic)]
+fn end_slope(h: [f64; 2], delta: [f64; 2]) -> f64 {
+  let [h0, h1] = h;
+  let [delta0, delta1] = delta;
+  let slope = 2.0_f64.mul_add(h0, h1).mul_add(delta0, -h0 * delta1) / (h0 + h1);
+  if slope * delta0 <= 0.0 {
+    0.0
+  } else if delta0 * delta1 <= 0.0 && slope.abs() > 3.0 * delta0.abs() {
+    3.0 * delta0
+  } else {
+    slope
+  }
+}
+
+/// The slope of monotone cubic interpolation at `index[k]`
+#[expect(clippy::float_arithmetic, clippy::arithmetic_side_effects)]
+fn cubic_slope(index: &[f64], line: &[f64], k: usize) -> Option<f64> {
+  let h = |i: usize| Some(index.get(i + 1)? - index.get(i)?);
+  let delta = |i: usize| Some((line.get(i + 1)? - line.get(i)?) / h(i)?);
+  let n = index.len();
+  Some(if n == 2 {
+    delta(0)?
+  } else if k == 0 {
+    end_slope([h(0)?, h(1)?], [delta(0)?, delta(1)?])
+  } else if k == n - 1 {
+    end_slope([h(n - 2)?, h(n - 3)?], [delta(n - 2)?, delta(n - 3)?])
+  } else {
+    let (delta0, delta1) = (delta(k - 1)?, delta(k)?);
+    if delta0 * delta1 <= 0.0 {
+      0.0
+    } else {
+      let w0 = 2.0_f64.mul_add(h(k)?, h(k - 1)?);
+      let w1 = 2.0_f64.mul_add(h(k - 1)?, h(k)?);
+      (w0 + w1) / (w0 / delta0 + w1 / delta1)
+    }
+  })
+}
+
+#[expect(clippy::float_arithmetic)]
+impl Lerp for f64 {
+  #[inline]
+  fn lerp(self, other: Self, t: f64) -> Self {
+    (other - self).mul_add(t, self)
+  }
+  #[inline]
+  #[expect(clippy::arithmetic_side_effects)]
+  fn cubic(index: &[f64], line: &[Self], lo: usize, t: f64) -> Option<Self> {
+    let h = index.get(lo + 1)? - index.get(lo)?;
+    let (y0, y1) = (*line.get(lo)?, *line.get(lo + 1)?);
+    let (d0, d1) = (cubic_slope(index, line, lo)?, cubic_slope(index, line, lo + 1)?);
+    // the cubic Hermite basis
+    let t2 = t * t;
+    let t3 = t2 * t;
+    let h00 = 2.0_f64.mul_add(t3, (-3.0_f64).mul_add(t2, 1.0));
+    let h10 = (-2.0_f64).mul_add(t2, t3 + t);
+    let h01 = (-2.0_f64).mul_add(t3, 3.0 * t2);
+    let h11 = t3 - t2;
+    Some(y0.mul_add(h00, y1.mul_add(h01, h * d0.mul_add(h10, d1 * h11))))
+  }
+}
+
+impl Lerp for LVFMoments {
+  #[inline]
+  #[expect(clippy::arithmetic_side_effects)]
+  fn lerp(self, other: Self, t: f64) -> Self {
+    (other - self).mul_add(t, self)
+  }
+  #[inline]
+  fn cubic(index: &[f64], line: &[Self], lo: usize, t: f64) -> Option<Self> {
+    let component = |get: fn(&Self) -> f64| {
+      f64::cubic(index, &line.iter().map(get).collect::<Vec<_>>(), lo, t)
+    };
+    Some(Self {
+      mean: component(|moments| moments.mean)?,
+      std_dev: component(|moments| moments.std_dev)?,
+      skewness: component(|moments| moments.skewness)?,
+    })
+  }
+}
+
+impl Lerp for LVFEarlyLate {
+  #[inline]
+  fn lerp(self, other: Self, t: f64) -> Self {
+    Self {
+      early_sigma: self.early_sigma.lerp(other.early_sigma, t),
+      late_sigma: self.late_sigma.lerp(other.late_sigma, t),
+    }
+  }
+  #[inline]
+  fn cubic(index: &[f64], line: &[Self], lo: usize, t: f64) -> Option<Self> {
+    let component = |get: fn(&Self) -> f64| {
+      f64::cubic(index, &line.iter().map(get).collect::<Vec<_>>(), lo, t)
+    };
+    Some(Self {
+      early_sigma: component(|early_late| early_late.early_sigma)?,
+      late_sigma: component(|early_late| early_late.late_sigma)?,
+    })
+  }
+}
+
+/// The position of a value in one axis
+#[derive(Debug, Clone, Copy)]
+struct Segment {
+  lo: usize,
+  hi: usize,
+  /// The weight of `hi`
+  t: f64,
+  extrapolated: bool,
+}
+
+impl Segment {
+  #[expect(clippy::float_arithmetic, clippy::arithmetic_side_effects)]
+  fn new(index: &[f64], x: f64, interpolation: Interpolation) -> Option<Self> {
+    let first = *index.first()?;
+    let last = *index.last()?;
+    let len = index.len();
+    // the table is constant along an index with single point
+    let extrapolated = len > 1 && (x < first || x > last);
+    let pos = index.partition_point(|v| *v < x);
+    let at = |i: usize| Self { lo: i, hi: i, t: 0.0, extrapolated };
+    if index.get(pos).is_some_and(|v| v.total_cmp(&x).is_eq()) {
+      return Some(at(pos));
+    }
+    Some(match interpolation {
+      Interpolation::Nearest => {
+        if pos == 0 {
+          at(0)
+        } else if pos == len {
+          at(len - 1)
+        } else if x - index.get(pos - 1)? <= index.get(pos)? - x {
+          at(pos - 1)
+        } else {
+          at(pos)
+        }
+      }
+      Interpolation::Linear | Interpolation::MonotoneCubic => {
+        if len == 1 {
+          return Some(at(0));
+        }
+        let (lo, hi) = if pos == 0 {
+          (0, 1)
+        } else if pos == len {
+          (len - 2, len - 1)
+        } else {
+          (pos - 1, pos)
+        };
+        let x_lo = *index.get(lo)?;
+        let x_hi = *index.get(hi)?;
+        Self {
+          lo,
+          hi,
+          t: (x - x_lo) / (x_hi - x_lo),
+          extrapolated,
+        }
+      }
+    })
+  }
+}
+
+/// The values of table in row-major order, on the grid of the non-empty indices
+#[derive(Debug, Clone)]
+pub(crate) struct Grid<'a, T> {
+  indices: Vec<&'a [f64]>,
+  values: &'a [T],
+}
+
+impl<'a, T: Lerp> Grid<'a, T> {
+  /// Return `None` when the number of values mismatches with the indices
+  pub(crate) fn new<I: IntoIterator<Item = &'a [f64]>>(
+    all_indices: I,
+    values: &'a [T],
+  ) -> Option<Self> {
+    let indices: Vec<&[f64]> =
+      all_indices.into_iter().filter(|index| !index.is_empty()).collect();
+    (indices.iter().map(|index| index.len()).product::<usize>() == values.len())
+      .then_some(Self { indices, values })
+  }
+  /// The non-empty indices
+  pub(crate) fn indices(&self) -> &[&'a [f64]] {
+    &self.indices
+  }
+  #[expect(clippy::arithmetic_side_effects)]
+  /// The value with the `segments` of the remaining axes
+  fn value_at(
+    &self,
+    segments: &[Segment],
+    offset: usize,
+    interpolation: Interpolation,
+  ) -> Option<T> {
+    match segments.split_first() {
+      None => self.values.get(offset).copied(),
+      Some((segment, rest)) => {
+        let axis = self.indices.len() - segments.len();
+        let index = self.indices.get(axis)?;
+        let stride: usize =
+          self.indices.iter().skip(axis + 1).map(|other| other.len()).product();
+        if segment.lo == segment.hi {
+          self.value_at(rest, offset + segment.lo * stride, interpolation)
+        } else if interpolation == Interpolation::MonotoneCubic
+          && !segment.extrapolated
+          && index.len() > 2
+        {
+          let line = (0..index.len())
+            .map(|i| self.value_at(rest, offset + i * stride, interpolation))
+            .collect::<Option<Vec<T>>>()?;
+          T::cubic(index, &line, segment.lo, segment.t)
+        } else {
+          let lo = self.value_at(rest, offset + segment.lo * stride, interpolation)?;
+          let hi = self.value_at(rest, offset + segment.hi * stride, interpolation)?;
+          Some(lo.lerp(hi, segment.t))
+        }
+      }
+    }
+  }
+  /// The value at `point` (in the order of non-empty indices),
+  /// and whether the point is out of the grid
+  pub(crate) fn interpolate(
+    &self,
+    point: &[f64],
+    interpolation: Interpolation,
+  ) -> Option<(T, bool)> {
+    if point.len() != self.indices.len() {
+      return None;
+    }
+    let segments: Vec<Segment> = self
+      .indices
+      .iter()
+      .zip(point)
+      .map(|(index, x)| Segment::new(index, *x, interpolation))
+      .collect::<Option<_>>()?;
+    let extrapolated = segments.iter().any(|segment| segment.extrapolated);
+    self
+      .value_at(&segments, 0, interpolation)
+      .map(|value| (value, extrapolated))
+  }
+  /// Resample onto the `new_indices` (non-empty), the `k`-th new axis is
+  /// the `axes[k]`-th axis of this grid.
+  /// Return the values and the extrapolated points.
+  pub(crate) fn resample(
+    &self,
+    new_indices: &[&[f64]],
+    axes: &[usize],
+    interpolation: Interpolation,
+  ) -> Option<(Vec<T>, Vec<Vec<f64>>)> {
+    if new_indices.len() != self.indices.len() || axes.len() != self.indices.len() {
+      return None;
+    }
+    let mut values = Vec::new();
+    let mut extrapolated = Vec::new();
+    for new_point in new_indices
+      .iter()
+      .map(|index| index.iter().copied())
+      .multi_cartesian_product()
+    {
+      let mut point = vec![0.0; new_point.len()];
+      for (axis, x) in axes.iter().zip(&new_point) {
+        *point.get_mut(*axis)? = *x;
+      }
+      let (value, is_extrapolated) = self.interpolate(&point, interpolation)?;
+      values.push(value);
+      if is_extrapolated {
+        extrapolated.push(new_point);
+      }
+    }
+    Some((values, extrapolated))
+  }
+}
+
+/// The identity axes mapping of `n` non-empty indices
+fn identity_axes(n: usize) -> Vec<usize> {
+  (0..n).collect()
+}
+
+/// The axes mapping from the `old` template to the `new` template, by matching
+/// their variables. Return identity when any variable is unknown,
+/// and `None` when the variables do not match.
+#[cfg(feature = "lut_template")]
+fn template_axes<C: 'static + Ctx>(
+  old: Option<&TableTemple<C>>,
+  new: &TableTemple<C>,
+  n: usize,
+) -> Option<Vec<usize>> {
+  let variables = |template: &TableTemple<C>| {
+    [template.variable_1, template.variable_2, template.variable_3, template.variable_4]
+      .into_iter()
+      .take(n)
+      .collect::<Option<Vec<_>>>()
+  };
+  match (old.and_then(variables), variables(new)) {
+    (Some(old_variables), Some(new_variables)) => {
+      let axes: Vec<usize> = new_variables
+        .iter()
+        .map(|variable| old_variables.iter().position(|v| v == variable))
+        .collect::<Option<_>>()?;
+      axes.iter().all_unique().then_some(axes)
+    }
+    _ => Some(identity_axes(n)),
+  }
+}
+
+/// The new indices of template in the new order,
+/// use the old ones when the template does not define them
+#[cfg(feature = "lut_template")]
+fn template_indices<'a, C: 'static + Ctx>(
+  template: &'a TableTemple<C>,
+  old_indices: &[&'a [f64]],
+  axes: &[usize],
+) -> Option<Vec<&'a [f64]>> {
+  [&template.index_1, &template.index_2, &template.index_3, &template.index_4]
+    .into_iter()
+    .zip(axes)
+    .map(|(index, axis)| index.as_deref().or_else(|| old_indices.get(*axis).copied()))
+    .collect()
+}
+
+impl<C: 'static + Ctx> TimingTableLookUp<C> {
+  /// Resample onto the new indices, the `k`-th new non-empty index is
+  /// the `axes[k]`-th non-empty index of this table
+  fn resample_axes(
+    &self,
+    new_indices: &[&[f64]],
+    axes: &[usize],
+    interpolation: Interpolation,
+  ) -> Option<Resampled<Self>> {
+    let grid = Grid::new([self.index_1.as_slice(), &self.index_2], &self.values)?;
+    let (values, extrapolated) = grid.resample(new_indices, axes, interpolation)?;
+    let lvf_moments_values = if self.lvf_moments_values.is_empty() {
+      Vec::new()
+    } else {
+      Grid::new(grid.indices().iter().copied(), &self.lvf_moments_values)?
+        .resample(new_indices, axes, interpolation)?
+        .0
+    };
+    let lvf_early_late_values = if self.lvf_early_late_values.is_empty() {
+      Vec::new()
+    } else {
+      Grid::new(grid.indices().iter().copied(), &self.lvf_early_late_values)?
+        .resample(new_indices, axes, interpolation)?
+        .0
+    };
+    let mut new_indices_iter = new_indices.iter();
+    let mut next_index = |old: &[f64]| {
+      if old.is_empty() {
+        Some(Vec::new())
+      } else {
+        new_indices_iter.next().map(|index| index.to_vec())
+      }
+    };
+    let mut table = self.clone();
+    table.index_1 = next_index(&self.index_1)?;
+    table.index_2 = next_index(&self.index_2)?;
+    table.values = values;
+    table.lvf_moments_values = lvf_moments_values;
+    table.lvf_early_late_values = lvf_early_late_values;
+    Some(Resampled { table, extrapolated })
+  }
+  /// Resample the `values`, `lvf_moments_values` and `lvf_early_late_values`
+  /// onto the new `index_1` / `index_2`.
+  ///
+  /// The empty indices should stay empty, e.g., `index_2` of 1-D table.
+  /// Return `None` when the number of values mismatches with the indices.
+  #[must_use]
+  #[inline]
+  pub fn resample(
+    &self,
+    index_1: &[f64],
+    index_2: &[f64],
+    interpolation: Interpolation,
+  ) -> Option<Resampled<Self>> {
+    let new_indices: Vec<&[f64]> = [index_1, index_2]
+      .into_iter()
+      .zip([&self.index_1, &self.index_2])
+      .map(|(new, old)| (new.is_empty() == old.is_empty()).then_some(new))
+      .collect::<Option<Vec<_>>>()?
+      .into_iter()
+      .filter(|index| !index.is_empty())
+      .collect();
+    self.resample_axes(&new_indices, &identity_axes(new_indices.len()), interpolation)
+  }
+  /// Resample onto the indices of `template`, and re-point the table at it.
+  ///
+  /// When the variables of the current template and the new one are
+  /// in different order, the axes are transposed accordingly.
+  /// The indices not defined in `template` are kept.
+  /// Return `None` when the variables do not match.
+  #[cfg(feature = "lut_template")]
+  #[must_use]
+  #[inline]
+  pub fn resample_to_template(
+    &self,
+    template: &Arc<TableTemple<C>>,
+    interpolation: Interpolation,
+  ) -> Option<Resampled<Self>> {
+    let old_indices: Vec<&[f64]> = [self.index_1.as_slice(), &self.index_2]
+      .into_iter()
+      .filter(|index| !index.is_empty())
+      .collect();
+    let axes = template_axes(
+      self.extra_ctx.lut_template().as_deref(),
+      template,
+      old_indices.len(),
+    )?;
+    let new_indices = template_indices(template, &old_indices, &axes)?;
+    let mut resampled = self.resample_axes(&new_indices, &axes, interpolation)?;
+    resampled.table.name.clone_from(&template.name);
+    resampled.table.extra_ctx.set_lut_template(Some(template));
+    Some(resampled)
+  }
+}
+
+impl<C: 'static + Ctx> TableLookUp<C> {
+  /// Resample onto the new indices, the `k`-th new non-empty index is
+  /// the `axes[k]`-th non-empty index of this table
+  fn resample_axes(
+    &self,
+    new_indices: &[&[f64]],
+    axes: &[usize],
+    interpolation: Interpolation,
+  ) -> Option<Resampled<Self>> {
+    let old_indices = [&self.index_1, &self.index_2, &self.index_3, &self.index_4];
+    let grid =
+      Grid::new(old_indices.iter().map(|index| index.as_slice()), &self.values.inner)?;
+    let (values, extrapolated) = grid.resample(new_indices, axes, interpolation)?;
+    let mut new_indices_iter = new_indices.iter();
+    let [index_1, index_2, index_3, index_4] = old_indices.map(|old| {
+      if old.is_empty() {
+        Some(Vec::new())
+      } else {
+        new_indices_iter.next().map(|index| index.to_vec())
+      }
+    });
+    let mut table = self.clone();
+    let chunk_size = new_indices.last().map_or(values.len(), |index| index.len());
+    table.values = Values { chunk_size, inner: values };
+    table.index_1 = index_1?;
+    table.index_2 = index_2?;
+    table.index_3 = index_3?;
+    table.index_4 = index_4?;
+    Some(Resampled { table, extrapolated })
+  }
+  /// Resample the `values` onto the new `index_1` / `index_2` / `index_3`,
+  /// the `index_4` is kept.
+  ///
+  /// The empty indices should stay empty, e.g., `index_2` of 1-D table.
+  /// Return `None` when the number of values mismatches with the indices.
+  #[must_use]
+  #[inline]
+  pub fn resample(
+    &self,
+    index_1: &[f64],
+    index_2: &[f64],
+    index_3: &[f64],
+    interpolation: Interpolation,
+  ) -> Option<Resampled<Self>> {
+    let new_indices: Vec<&[f64]> = [index_1, index_2, index_3, &self.index_4]
+      .into_iter()
+      .zip([&self.index_1, &self.index_2, &self.index_3, &self.index_4])
+      .map(|(new, old)| (new.is_empty() == old.is_empty()).then_some(new))
+      .collect::<Option<Vec<_>>>()?
+      .into_iter()
+      .filter(|index| !index.is_empty())
+      .collect();
+    self.resample_axes(&new_indices, &identity_axes(new_indices.len()), interpolation)
+  }
+  /// Resample onto the indices of `template`, and re-point the table at it.
+  ///
+  /// When the variables of the current template and the new one are
+  /// in different order, the axes are transposed accordingly.
+  /// The indices not defined in `template` are kept.
+  /// Return `None` when the variables do not match.
+  #[cfg(feature = "lut_template")]
+  #[must_use]
+  #[inline]
+  pub fn resample_to_template(
+    &self,
+    template: &Arc<TableTemple<C>>,
+    interpolation: Interpolation,
+  ) -> Option<Resampled<Self>> {
+    let old_indices: Vec<&[f64]> =
+      [&self.index_1, &self.index_2, &self.index_3, &self.index_4]
+        .into_iter()
+        .map(Vec::as_slice)
+        .filter(|index| !index.is_empty())
+        .collect();
+    let axes = template_axes(
+      self.extra_ctx.lut_template().as_deref(),
+      template,
+      old_indices.len(),
+    )?;
+    let new_indices = template_indices(template, &old_indices, &axes)?;
+    let mut resampled = self.resample_axes(&new_indices, &axes, interpolation)?;
+    resampled.table.name.clone_from(&template.name);
+    resampled.table.extra_ctx.set_lut_template(Some(template));
+    Some(resampled)
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::{DefaultCtx, Library, timing::LVFMoments};
+  const LIB: &str = r#"library (test) {
+    lu_table_template (delay_template_2x3) {
+      variable_1 : input_net_transition;
+      variable_2 : total_output_net_capacitance;
+      index_1 ("10, 30");
+      index_2 ("30, 50, 60");
+    }
+    lu_table_template (delay_template_load_first) {
+      variable_1 : total_output_net_capacitance;
+      variable_2 : input_net_transition;
+      index_1 ("40, 60");
+      index_2 ("10, 20, 30");
+    }
+    power_lut_template (power_template_3) {
+      variable_1 : input_transition_time;
+      index_1 ("1, 2, 3");
+    }
+    cell (INV) {
+      pin (A) {
+        direction : input;
+        internal_power () {
+          rise_power (power_template_3) {
+            index_1 ("1, 2, 3");
+            values ("1, 2, 4");
+          }
+        }
+      }
+      pin (Y) {
+        direction : output;
+        function : "!A";
+        timing () {
+          related_pin : "A";
+          cell_rise (delay_template_2x3) {
+            index_1 ("10, 30");
+            index_2 ("30, 50, 60");
+            values ("100, 200, 300", "700, 800, 900");
+          }
+          ocv_mean_shift_cell_rise (delay_template_2x3) {
+            index_1 ("10, 30");
+            index_2 ("30, 50, 60");
+            values ("0, 0, 0", "0, 0, 0");
+          }
+          ocv_std_dev_cell_rise (delay_template_2x3) {
+            index_1 ("10, 30");
+            index_2 ("30, 50, 60");
+            values ("10, 20, 30", "70, 80, 90");
+          }
+          ocv_skewness_cell_rise (delay_template_2x3) {
+            index_1 ("10, 30");
+            index_2 ("30, 50, 60");
+            values ("1, 2, 3", "7, 8, 9");
+          }
+        }
+      }
+    }
+  }"#;
+  #[test]
+  fn resample() {
+    let library = Library::<DefaultCtx>::parse_lib(LIB, None).unwrap();
+    let cell = library.cell.get("INV").unwrap();
+    let timing = cell.pin.get("Y").unwrap().timing.iter().next().unwrap();
+    let table = timing.cell_rise.as_ref().unwrap();
+    let resampled = table
+      .resample(&[10.0, 20.0, 40.0], &[30.0, 40.0], Interpolation::Linear)
+      .unwrap();
+    assert_eq!(resampled.table.index_1, [10.0, 20.0, 40.0]);
+    assert_eq!(resampled.table.index_2, [30.0, 40.0]);
+    assert_eq!(resampled.table.values, [100.0, 150.0, 400.0, 450.0, 1000.0, 1050.0]);
+    for (i1, i2) in [(10.0, 40.0), (20.0, 30.0), (40.0, 40.0)] {
+      let lookup = table.lookup(&i1, &i2).unwrap();
+      assert_eq!(resampled.table.lookup(&i1, &i2), Some(lookup));
+    }
+    assert_eq!(
+      resampled.table.lookup_lvf_moments(&20.0, &40.0),
+      Some(LVFMoments { mean: 450.0, std_dev: 45.0, skewness: 4.5 })
+    );
+    assert_eq!(resampled.extrapolated, [[40.0, 30.0], [40.0, 40.0]]);
+    let nearest = table
+      .resample(&[19.0, 21.0], &[45.0], Interpolation::Nearest)
+      .unwrap();
+    assert_eq!(nearest.table.values, [200.0, 800.0]);
+    assert!(nearest.extrapolated.is_empty());
+    // index_2 of 2-D table can not be empty
+    assert!(table.resample(&[10.0], &[], Interpolation::Linear).is_none());
+
+    // transpose the axes with template
+    let template = Arc::new(
+      library
+        .lu_table_template
+        .get("delay_template_load_first")
+        .unwrap()
+        .clone(),
+    );
+    let resampled = table.resample_to_template(&template, Interpolation::Linear).unwrap();
+    assert_eq!(resampled.table.name, "delay_template_load_first");
+    assert_eq!(resampled.table.index_1, [40.0, 60.0]);
+    assert_eq!(resampled.table.index_2, [10.0, 20.0, 30.0]);
+    assert_eq!(resampled.table.values, [150.0, 450.0, 750.0, 300.0, 600.0, 900.0]);
+    assert!(resampled.extrapolated.is_empty());
+    assert_eq!(
+      resampled
+        .table
+        .extra_ctx
+        .lut_template()
+        .as_ref()
+        .map(|t| t.name.as_str()),
+      Some("delay_template_load_first")
+    );
+
+    let power = cell.pin.get("A").unwrap().internal_power.iter().next().unwrap();
+    let rise_power = power.rise_power.as_ref().unwrap();
+    let resampled = rise_power
+      .resample(&[0.0, 1.5, 2.5], &[], &[], Interpolation::Linear)
+      .unwrap();
+    assert_eq!(resampled.table.values.inner, [0.0, 1.5, 3.0]);
+    assert_eq!(resampled.table.values.chunk_size, 3);
+    assert_eq!(resampled.extrapolated, [[0.0]]);
+  }
+}
@@ -0,0 +1,438 @@
+//! Table lookup with selectable interpolation and out-of-range policy,
+//! see [`LookupConfig`].
+#![allow(clippy::multiple_inherent_impl)]
+#[cfg(feature = "lut_template")]
+use super::TableCtx as _;
+use super::{
+  DcCurrent, TableDefect, TableLookUp, TableLookUpMultiSegment, TableTemple, Variable,
+  grid::{Grid, Lerp},
+};
+use crate::{
+  Ctx,
+  timing::{LVFEarlyLate, LVFMoments, TimingTableLookUp},
+};
+
+/// The policy for the queries out of the table range
+#[derive(Debug, Clone, Copy, Default)]
+#[derive(Hash, PartialEq, Eq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub enum OutOfRange {
+  /// Extrapolate with the [`Interpolation`](super::Interpolation),
+  /// the same as [`TimingTableLookUp::lookup`]
+  #[default]
+  Extrapolate,
+  /// Clamp the query into the table range
+  Clamp,
+  /// Return [`LookupError::OutOfRange`]
+  Error,
+}
+
+/// The configuration of [`TimingTableLookUp::lookup_with`] / [`TableLookUp::lookup_with`],
+/// the default one is the same as [`TimingTableLookUp::lookup`]
+#[derive(Debug, Clone, Copy, Default)]
+#[derive(Hash, PartialEq, Eq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct LookupConfig {
+  /// The interpolation between the table points
+  pub interpolation: super::Interpolation,
+  /// The policy for the queries out of the table range
+  pub out_of_range: OutOfRange,
+}
+
+/// The result of [`TimingTableLookUp::lookup_with`] / [`TableLookUp::lookup_with`]
+#[derive(Debug, Clone, Copy)]
+#[derive(PartialEq, Eq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct Lookup<T> {
+  /// The value at the query
+  pub value: T,
+  /// Whether the query is out of the table range, so that the value is
+  /// extrapolated, or clamped by [`OutOfRange::Clamp`]
+  pub extrapolated: bool,
+}
+
+/// The error of [`TimingTableLookUp::lookup_with`] / [`TableLookUp::lookup_with`]
+#[derive(Debug, Clone, Copy, thiserror::Error, PartialEq)]
+pub enum LookupError {
+  /// The number of query variables mismatches with the non-empty indices
+  #[error("expect {expected} variables, found {found}")]
+  Dimension {
+    /// The number of non-empty indices
+    expected: usize,
+    /// The number of query variables
+    found: usize,
+  },
+  /// The query is out of `index_{axis}`, with [`OutOfRange::Error`]
+  #[error("{value} is out of index_{axis} range [{min}, {max}]")]
+  OutOfRange {
+    /// The `index_{axis}`
+    axis: usize,
+    /// The query variable
+    value: f64,
+    /// The first value of index
+    min: f64,
+    /// The last value of index
+    max: f64,
+  },
+  /// The table is not able to look up
+  #[error(transparent)]
+  Table(#[from] TableDefect),
+}
+
+/// Look up the `values` on the grid of `all_indices`,
+/// the `point` has one variable per non-empty index
+fn lookup<T: Lerp>(
+  all_indices: &[&[f64]],
+  values: &[T],
+  point: &[f64],
+  config: LookupConfig,
+) -> Result<Lookup<T>, LookupError> {
+  let indices: Vec<(usize, &[f64])> = all_indices
+    .iter()
+    .enumerate()
+    .filter(|(_, index)| !index.is_empty())
+    .map(|(i, index)| (i.saturating_add(1), *index))
+    .collect();
+  if point.len() != indices.len() {
+    return Err(LookupError::Dimension { expected: indices.len(), found: point.len() });
+  }
+  let grid = Grid::new(indices.iter().map(|(_, index)| *index), values).ok_or(
+    TableDefect::ValuesLength {
+      expected: indices.iter().map(|(_, index)| index.len()).product(),
+      found: values.len(),
+    },
+  )?;
+  let mut extrapolated = false;
+  let mut query = Vec::with_capacity(point.len());
+  for ((axis, index), value) in indices.iter().zip(point) {
+    let (Some(min), Some(max)) = (index.first().copied(), index.last().copied()) else {
+      continue;
+    };
+    let out = index.len() > 1 && !(min..=max).contains(value);
+    extrapolated |= out;
+    query.push(match (out, config.out_of_range) {
+      (false, _) | (true, OutOfRange::Extrapolate) => *value,
+      (true, OutOfRange::Clamp) => value.clamp(min, max),
+      (true, OutOfRange::Error) => {
+        return Err(LookupError::OutOfRange { axis: *axis, value: *value, min, max });
+      }
+    });
+  }
+  grid
+    .interpolate(&query, config.interpolation)
+    .map(|(value, _)| Lookup { value, extrapolated })
+    .ok_or(LookupError::Dimension { expected: indices.len(), found: query.len() })
+}
+
+impl<C: 'static + Ctx> TimingTableLookUp<C> {
+  /// Look up the value with the interpolation and out-of-range policy in `config`.
+  ///
+  /// Unlike [`TimingTableLookUp::lookup`], the tables with single-point
+  /// indices or no index (scalar) are supported, they are constant along
+  /// those indices, and the query variables of empty indices are ignored,
+  /// e.g., `idx2` of 1-D table.
+  #[inline]
+  pub fn lookup_with(
+    &self,
+    idx1: f64,
+    idx2: f64,
+    config: LookupConfig,
+  ) -> Result<Lookup<f64>, LookupError> {
+    let all_indices = [self.index_1.as_slice(), &self.index_2];
+    lookup(&all_indices, &self.values, &Self::query(&all_indices, idx1, idx2), config)
+  }
+  /// Look up the LVF moments like [`TimingTableLookUp::lookup_with`],
+  /// the moments are interpolated component-wise.
+  #[inline]
+  pub fn lookup_lvf_moments_with(
+    &self,
+    idx1: f64,
+    idx2: f64,
+    config: LookupConfig,
+  ) -> Result<Lookup<LVFMoments>, LookupError> {
+    if self.lvf_moments_values.len() != self.values.len() {
+      return Err(
+        TableDefect::LvfLength {
+          expected: self.values.len(),
+          found: self.lvf_moments_values.len(),
+        }
+        .into(),
+      );
+    }
+    let all_indices = [self.index_1.as_slice(), &self.index_2];
+    lookup(
+      &all_indices,
+      &self.lvf_moments_values,
+      &Self::query(&all_indices, idx1, idx2),
+      config,
+    )
+  }
+  /// Look up the LVF early/late sigmas like [`TimingTableLookUp::lookup_with`],
+  /// the sigmas are interpolated component-wise.
+  #[inline]
+  pub fn lookup_lvf_early_late_with(
+    &self,
+    idx1: f64,
+    idx2: f64,
+    config: LookupConfig,
+  ) -> Result<Lookup<LVFEarlyLate>, LookupError> {
+    if self.lvf_early_late_values.len() != self.values.len() {
+      return Err(
+        TableDefect::LvfLength {
+          expected: self.values.len(),
+          found: self.lvf_early_late_values.len(),
+        }
+        .into(),
+      );
+    }
+    let all_indices = [self.index_1.as_slice(), &self.index_2];
+    lookup(
+      &all_indices,
+      &self.lvf_early_late_values,
+      &Self::query(&all_indices, idx1, idx2),
+      config,
+    )
+  }
+  /// The query variables of the non-empty indices
+  fn query(all_indices: &[&[f64]; 2], idx1: f64, idx2: f64) -> Vec<f64> {
+    all_indices
+      .iter()
+      .zip([idx1, idx2])
+      .filter(|(index, _)| !index.is_empty())
+      .map(|(_, x)| x)
+      .collect()
+  }
+}
+
+/// Look up `values` at the point given by `value` of the variable of each non-empty
+/// index. The variables come from `template`, or `defaults` without template,
+/// and the indices omitted by the table are taken from `template`.
+fn lookup_variables<C: 'static + Ctx>(
+  template: Option<&TableTemple<C>>,
+  indices: [&Vec<f64>; 4],
+  values: &[f64],
+  defaults: &[Variable],
+  value: impl Fn(Option<Variable>) -> f64,
+  config: LookupConfig,
+) -> Result<Lookup<f64>, LookupError> {
+  let (variables, template_indices) = template.map_or(
+    ([0, 1, 2, 3].map(|i| defaults.get(i).copied()), [None, None, None, None]),
+    |found| {
+      (
+        [found.variable_1, found.variable_2, found.variable_3, found.variable_4],
+        [&found.index_1, &found.index_2, &found.index_3, &found.index_4]
+          .map(Option::as_ref),
+      )
+    },
+  );
+  let all_indices: Vec<&[f64]> = indices
+    .into_iter()
+    .zip(template_indices)
+    .map(|(index, inherited)| match inherited {
+      Some(template_index) if index.is_empty() => template_index.as_slice(),
+      _ => index.as_slice(),
+    })
+    .collect();
+  let point: Vec<f64> = variables
+    .into_iter()
+    .zip(&all_indices)
+    .filter(|(_, index)| !index.is_empty())
+    .map(|(variable, _)| value(variable))
+    .collect();
+  lookup(&all_indices, values, &point, config)
+}
+
+impl<C: 'static + Ctx> TableLookUp<C> {
+  /// Look up the value with the interpolation and out-of-range policy in `config`,
+  /// the `point` has one variable per non-empty index, in the order of
+  /// `index_1` to `index_4`.
+  ///
+  /// The tables with single-point indices or no index (scalar) are supported,
+  /// they are constant along those indices.
+  #[inline]
+  pub fn lookup_with(
+    &self,
+    point: &[f64],
+    config: LookupConfig,
+  ) -> Result<Lookup<f64>, LookupError> {
+    lookup(
+      &[&self.index_1, &self.index_2, &self.index_3, &self.index_4],
+      &self.values.inner,
+      point,
+      config,
+    )
+  }
+  /// Look up the value like [`TableLookUp::lookup_with`], at the point given by
+  /// `value` of the variable of each non-empty index.
+  ///
+  /// The variables and the indices omitted by the table come from the linked
+  /// template, e.g., `lu_table_template`. Without template, the variables are
+  /// `defaults` in the order of indices.
+  #[inline]
+  pub fn lookup_variables<F: Fn(Option<Variable>) -> f64>(
+    &self,
+    defaults: &[Variable],
+    value: F,
+    config: LookupConfig,
+  ) -> Result<Lookup<f64>, LookupError> {
+    #[cfg(feature = "lut_template")]
+    let template = self.extra_ctx.lut_template().as_deref();
+    #[cfg(not(feature = "lut_template"))]
+    let template = None::<&TableTemple<C>>;
+    lookup_variables(
+      template,
+      [&self.index_1, &self.index_2, &self.index_3, &self.index_4],
+      &self.values.inner,
+      defaults,
+      value,
+      config,
+    )
+  }
+}
+
+impl<C: 'static + Ctx> TableLookUpMultiSegment<C> {
+  /// Look up the value of the segment like [`TableLookUp::lookup_with`]
+  #[inline]
+  pub fn lookup_with(
+    &self,
+    point: &[f64],
+    config: LookupConfig,
+  ) -> Result<Lookup<f64>, LookupError> {
+    lookup(
+      &[&self.index_1, &self.index_2, &self.index_3, &self.index_4],
+      &self.values.inner,
+      point,
+      config,
+    )
+  }
+  /// Look up the value of the segment like [`TableLookUp::lookup_variables`]
+  #[inline]
+  pub fn lookup_variables<F: Fn(Option<Variable>) -> f64>(
+    &self,
+    defaults: &[Variable],
+    value: F,
+    config: LookupConfig,
+  ) -> Result<Lookup<f64>, LookupError> {
+    #[cfg(feature = "lut_template")]
+    let template = self.extra_ctx.lut_template().as_deref();
+    #[cfg(not(feature = "lut_template"))]
+    let template = None::<&TableTemple<C>>;
+    lookup_variables(
+      template,
+      [&self.index_1, &self.index_2, &self.index_3, &self.index_4],
+      &self.values.inner,
+      defaults,
+      value,
+      config,
+    )
+  }
+}
+
+impl<C: 'static + Ctx> DcCurrent<C> {
+  /// Look up the DC current at the input voltage `vin` (`index_1`) and the
+  /// output voltage `vout` (`index_2`), with the interpolation and
+  /// out-of-range policy in `config`
+  #[inline]
+  pub fn lookup_with(
+    &self,
+    vin: f64,
+    vout: f64,
+    config: LookupConfig,
+  ) -> Result<Lookup<f64>, LookupError> {
+    lookup(&[&self.index_1, &self.index_2], &self.values.inner, &[vin, vout], config)
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::{DefaultCtx, ast::test_parse, table::Interpolation, timing::Timing};
+  fn assert_close(a: f64, b: f64) {
+    assert!((a - b).abs() < 1e-9, "{a} != {b}");
+  }
+  fn timing() -> Timing<DefaultCtx> {
+    test_parse::<Timing<DefaultCtx>>(
+      r#"() {
+        cell_rise (delay_template_2x3) {
+          index_1 ("10, 30");
+          index_2 ("30, 50, 60");
+          values ("100, 300, 400", "700, 900, 1000");
+        }
+        cell_fall (delay_template_1x3) {
+          index_1 ("10");
+          index_2 ("0, 1, 2, 3");
+          values ("0, 1, 1, 1");
+        }
+        rise_transition (scalar) {
+          values ("5");
+        }
+      }"#,
+    )
+  }
+  #[test]
+  fn lookup_policy() {
+    let timing = timing();
+    let table = timing.cell_rise.as_ref().unwrap();
+    let default = LookupConfig::default();
+    for (i1, i2) in [(20.0, 40.0), (40.0, 55.0), (0.0, 70.0)] {
+      let lookup = table.lookup_with(i1, i2, default).unwrap();
+      assert_close(lookup.value, table.lookup(&i1, &i2).unwrap());
+    }
+    assert!(!table.lookup_with(20.0, 40.0, default).unwrap().extrapolated);
+    assert!(table.lookup_with(40.0, 40.0, default).unwrap().extrapolated);
+    let clamp = LookupConfig { out_of_range: OutOfRange::Clamp, ..default };
+    let lookup = table.lookup_with(40.0, 70.0, clamp).unwrap();
+    assert_eq!(lookup, Lookup { value: 1000.0, extrapolated: true });
+    let error = LookupConfig { out_of_range: OutOfRange::Error, ..default };
+    assert_eq!(
+      table.lookup_with(20.0, 70.0, error),
+      Err(LookupError::OutOfRange { axis: 2, value: 70.0, min: 30.0, max: 60.0 })
+    );
+    let nearest = LookupConfig { interpolation: Interpolation::Nearest, ..default };
+    assert_close(table.lookup_with(25.0, 42.0, nearest).unwrap().value, 900.0);
+    assert_eq!(
+      table.lookup_lvf_moments_with(20.0, 40.0, default),
+      Err(LookupError::Table(TableDefect::LvfLength { expected: 6, found: 0 }))
+    );
+  }
+  #[test]
+  fn lookup_degenerate() {
+    let timing = timing();
+    // single-point index_1
+    let table = timing.cell_fall.as_ref().unwrap();
+    assert_eq!(table.lookup(&20.0, &1.5), None);
+    let error = LookupConfig {
+      out_of_range: OutOfRange::Error,
+      ..LookupConfig::default()
+    };
+    let lookup = table.lookup_with(20.0, 1.5, error).unwrap();
+    assert_eq!(lookup, Lookup { value: 1.0, extrapolated: false });
+    // scalar
+    let table = timing.rise_transition.as_ref().unwrap();
+    let lookup = table.lookup_with(20.0, 1.5, error).unwrap();
+    assert_eq!(lookup, Lookup { value: 5.0, extrapolated: false });
+  }
+  #[test]
+  fn lookup_monotone_cubic() {
+    let timing = timing();
+    let cubic = LookupConfig {
+      interpolation: Interpolation::MonotoneCubic,
+      ..LookupConfig::default()
+    };
+    // no overshoot on the step-like values
+    let table = timing.cell_fall.as_ref().unwrap();
+    assert_close(table.lookup_with(10.0, 0.5, cubic).unwrap().value, 0.6875);
+    let mut last = 0.0;
+    for i in 0..=30 {
+      let value = table.lookup_with(10.0, f64::from(i) / 10.0, cubic).unwrap().value;
+      assert!(value >= last - 1e-12 && value <= 1.0 + 1e-12, "{value}");
+      last = value;
+    }
+    // reproduce the linear values
+    let table = timing.cell_rise.as_ref().unwrap();
+    for (i1, i2) in [(20.0, 40.0), (15.0, 55.0), (40.0, 70.0)] {
+      let lookup = table.lookup_with(i1, i2, cubic).unwrap();
+      assert_close(lookup.value, table.lookup(&i1, &i2).unwrap());
+    }
+  }
+}
@@ -17,6 +17,36 @@ use core::{
 };
 use strum::{Display, EnumString};
 
+mod arithmetic;
+#[cfg(feature = "lut_template")]
+mod ccs;
+mod check;
+#[cfg(feature = "lut_template")]
+mod fit;
+mod grid;
+mod lookup;
+#[cfg(feature = "lut_template")]
+mod nldm;
+#[cfg(feature = "lut_template")]
+mod poly;
+mod prepared;
+mod statistics;
+mod waveform;
+#[cfg(feature = "lut_template")]
+pub use ccs::{CcsCompressed, CcsError, CompactCcsParams};
+pub use check::{TableCheck, TableDefect, TableRepair};
+#[cfg(feature = "lut_template")]
+pub use fit::{FitError, PolyFit};
+pub use grid::{Interpolation, Resampled};
+pub use lookup::{Lookup, LookupConfig, LookupError, OutOfRange};
+#[cfg(feature = "lut_template")]
+pub use nldm::{CcsNldm, NldmError, NldmTable};
+#[cfg(feature = "lut_template")]
+pub use poly::{PolyError, PolyPoint, PolyValue};
+pub use prepared::{LookupGradient, PreparedLookup};
+pub use statistics::{LvfDistribution, LvfRng};
+pub use waveform::{CurrentWaveform, TimingThresholds, WaveformError, WaveformTiming};
+
 pub trait TableCtx<C: 'static + Ctx> {
   /// Comes from one of
   /// + `lu_table_template`
@@ -72,10 +102,16 @@ macro_rules! add_use_power_template {
       #[inline]
       pub(crate) fn use_power_template(&mut self, scope: &mut ast::BuilderScope<C>) {
         #[cfg(feature = "lut_template")]
-        TableCtx::set_lut_template(
-          &mut self.extra_ctx,
-          scope.power_lut_template.get(&self.name),
-        )
+        {
+          TableCtx::set_lut_template(
+            &mut self.extra_ctx,
+            scope.power_lut_template.get(&self.name),
+          );
+          PolyTableCtx::set_poly_template(
+            &mut self.poly_ctx,
+            scope.power_poly_template.get(&self.name),
+          );
+        }
       }
     }
   };
@@ -97,20 +133,51 @@ macro_rules! add_use_power_template {
 //     }
 //   };
 // }
-// macro_rules! add_use_poly_template {
-//   ($table_ty:tt) => {
-//     impl<C: 'static + Ctx> $table_ty<C> {
-//       #[inline]
-//       pub(crate) fn use_poly_template(&mut self, scope: &mut ast::BuilderScope<C>) {
-//         #[cfg(feature = "lut_template")]
-//         PolyTableCtx::set_poly_template(
-//           &mut self.extra_ctx,
-//           scope.poly_template.get(&self.name),
-//         )
-//       }
-//     }
-//   };
-// }
+macro_rules! add_use_poly_template {
+  ($table_ty:tt) => {
+    impl<C: 'static + Ctx> $table_ty<C> {
+      #[inline]
+      pub(crate) fn use_poly_template(&mut self, scope: &mut ast::BuilderScope<C>) {
+        #[cfg(feature = "lut_template")]
+        PolyTableCtx::set_poly_template(
+          &mut self.poly_ctx,
+          scope.poly_template.get(&self.name),
+        )
+      }
+    }
+  };
+}
+macro_rules! add_use_noise_template {
+  ($table_ty:tt) => {
+    impl<C: 'static + Ctx> $table_ty<C> {
+      #[inline]
+      pub(crate) fn use_noise_template(&mut self, scope: &mut ast::BuilderScope<C>) {
+        #[cfg(feature = "lut_template")]
+        TableCtx::set_lut_template(
+          &mut self.extra_ctx,
+          scope
+            .noise_lut_template
+            .get(&self.name)
+            .or_else(|| scope.lu_table_template.get(&self.name)),
+        )
+      }
+      #[inline]
+      pub(crate) fn use_propagation_template(
+        &mut self,
+        scope: &mut ast::BuilderScope<C>,
+      ) {
+        #[cfg(feature = "lut_template")]
+        TableCtx::set_lut_template(
+          &mut self.extra_ctx,
+          scope
+            .noise_propagation_template
+            .get(&self.name)
+            .or_else(|| scope.lu_table_template.get(&self.name)),
+        )
+      }
+    }
+  };
+}
 macro_rules! add_use_current_template {
   ($table_ty:tt) => {
     impl<C: 'static + Ctx> $table_ty<C> {
@@ -140,12 +207,6 @@ macro_rules! add_use_compact_template {
   };
 }
 
-pub(crate) use add_use_common_template;
-pub(crate) use add_use_compact_template;
-pub(crate) use add_use_current_template;
-// pub(crate) use add_use_poly_template;
-pub(crate) use add_use_power_template;
-
 #[derive(Clone, Default, Debug)]
 #[derive(serde::Serialize, serde::Deserialize)]
 #[serde(bound = "C::Other: serde::Serialize + serde::de::DeserializeOwned")]
@@ -268,6 +329,14 @@ pub struct TableLookUpMultiSegment<C: 'static + Ctx> {
   pub values: Values,
 }
 add_use_common_template!(TableLookUpMultiSegment);
+impl<C: 'static + Ctx> TableLookUpMultiSegment<C> {
+  /// The `segment` of the multi-segment model
+  #[inline]
+  #[must_use]
+  pub const fn segment(&self) -> usize {
+    self.segment
+  }
+}
 
 #[derive(Debug, Clone)]
 #[derive(liberty_macros::Group)]
@@ -1320,7 +1389,8 @@ add_use_compact_template!(CompactCcsTable);
 #[derive(liberty_macros::Group)]
 #[mut_set::derive::item]
 #[derive(serde::Serialize, serde::Deserialize)]
-#[serde(bound = "C::Table: serde::Serialize + serde::de::DeserializeOwned")]
+#[serde(bound = "C::Table: serde::Serialize + serde::de::DeserializeOwned, \
+  C::PolyTable: serde::Serialize + serde::de::DeserializeOwned")]
 pub struct TableLookUpDomain<C: 'static + Ctx> {
   #[liberty(name)]
   #[id(borrow = str)]
@@ -1330,6 +1400,9 @@ pub struct TableLookUpDomain<C: 'static + Ctx> {
   comments: GroupComments,
   #[liberty(extra_ctx)]
   pub extra_ctx: C::Table,
+  /// The linked `poly_template` / `power_poly_template` of the polynomial table
+  #[liberty(extra_ctx)]
+  pub poly_ctx: C::PolyTable,
   /// group undefined attributes
   #[liberty(attributes)]
   pub attributes: Attributes,
@@ -1350,6 +1423,8 @@ pub struct TableLookUpDomain<C: 'static + Ctx> {
 }
 add_use_common_template!(TableLookUp);
 add_use_power_template!(TableLookUp);
+add_use_poly_template!(TableLookUp);
+add_use_noise_template!(TableLookUp);
 
 impl<C: 'static + Ctx> GroupFn<C> for TableLookUpDomain<C> {
   #[expect(clippy::arithmetic_side_effects)]
@@ -1372,6 +1447,9 @@ impl<C: 'static + Ctx> GroupFn<C> for TableLookUp<C> {
     }
     builder.coefs.chunk_size = builder.orders.len();
   }
+  fn after_build(&mut self, scope: &mut BuilderScope<C>) {
+    self.use_poly_template(scope);
+  }
 }
 impl<C: 'static + Ctx> GroupFn<C> for TableLookUpMultiSegment<C> {
   #[expect(clippy::arithmetic_side_effects)]
@@ -1814,6 +1892,8 @@ impl Variable {
     Self::Capacitance(CapacitanceVariable::RelatedOutOutputNetPinCap);
   pub const FANOUT_PIN_CAPACITANCE: Self =
     Self::Capacitance(CapacitanceVariable::FanoutPinCapacitance);
+  pub const EQUAL_OR_OPPOSITE_OUTPUT_NET_CAPACITANCE: Self =
+    Self::Capacitance(CapacitanceVariable::EqualOrOppositeOutputNetCapacitance);
   pub const OUTPUT_NET_LENGTH: Self = Self::Length(LengthVariable::OutputNetLength);
   pub const RELATED_OUT_OUTPUT_NET_LENGTH: Self =
     Self::Length(LengthVariable::RelatedOutOutputNetLength);
@@ -1851,6 +1931,9 @@ impl FromStr for Variable {
       "related_out_output_net_wire_cap" => Self::RELATED_OUT_OUTPUT_NET_WIRE_CAP,
       "related_out_output_net_pin_cap" => Self::RELATED_OUT_OUTPUT_NET_PIN_CAP,
       "fanout_pin_capacitance" => Self::FANOUT_PIN_CAPACITANCE,
+      "equal_or_opposite_output_net_capacitance" => {
+        Self::EQUAL_OR_OPPOSITE_OUTPUT_NET_CAPACITANCE
+      }
       "output_net_length" => Self::OUTPUT_NET_LENGTH,
       "related_out_output_net_length" => Self::RELATED_OUT_OUTPUT_NET_LENGTH,
       "fanout_number" => Self::FANOUT_NUMBER,
@@ -1894,6 +1977,9 @@ impl fmt::Display for Variable {
         f.write_str("related_out_output_net_pin_cap")
       }
       Self::FANOUT_PIN_CAPACITANCE => f.write_str("fanout_pin_capacitance"),
+      Self::EQUAL_OR_OPPOSITE_OUTPUT_NET_CAPACITANCE => {
+        f.write_str("equal_or_opposite_output_net_capacitance")
+      }
       Self::OUTPUT_NET_LENGTH => f.write_str("output_net_length"),
       Self::RELATED_OUT_OUTPUT_NET_LENGTH => f.write_str("related_out_output_net_length"),
       Self::FANOUT_NUMBER => f.write_str("fanout_number"),
@@ -1989,6 +2075,9 @@ pub enum CapacitanceVariable {
   /// `fanout_pin_capacitance`
   #[strum(serialize = "fanout_pin_capacitance")]
   FanoutPinCapacitance,
+  /// `equal_or_opposite_output_net_capacitance`
+  #[strum(serialize = "equal_or_opposite_output_net_capacitance")]
+  EqualOrOppositeOutputNetCapacitance,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -0,0 +1,203 @@
+//! Derivation of the NLDM timing tables from the CCS output-current waveforms,
+//! see [`Timing::nldm_from_ccs`].
+#![allow(clippy::multiple_inherent_impl)]
+use super::{
+  ReferenceTimeVector3DGrpup, TableCtx as _, TableTemple, TimingThresholds, Variable,
+  WaveformError, waveform::axis,
+};
+use crate::{
+  Ctx,
+  ast::{Attributes, GroupComments},
+  timing::{Timing, TimingTableLookUp},
+};
+use alloc::sync::Arc;
+
+/// The error of [`Timing::nldm_from_ccs`]
+#[derive(Debug, Clone, thiserror::Error, PartialEq)]
+pub enum NldmError {
+  /// The template is not found, by name
+  #[error("no lu_table_template {0}")]
+  NoTemplate(String),
+  /// The supply voltage is not found, neither in `voltage_map` nor `nom_voltage`
+  #[error("no supply voltage")]
+  NoVoltage,
+  /// The template variables are not `input_net_transition` and
+  /// `total_output_net_capacitance`
+  #[error("template variables {0:?} are not supported")]
+  Variables([Option<Variable>; 2]),
+  /// The `index_{0}` of template is not defined
+  #[error("no index_{0} in template")]
+  TemplateIndex(usize),
+  /// The waveform at the grid point is not able to measure
+  #[error("at index_1 {index_1}, index_2 {index_2}: {error}")]
+  Waveform {
+    /// The input transition
+    index_1: f64,
+    /// The output load
+    index_2: f64,
+    /// The error of waveform
+    #[source]
+    error: WaveformError,
+  },
+}
+
+/// One NLDM table derived by [`Timing::nldm_from_ccs`]
+#[derive(Debug, Clone)]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(bound = "C::Table: serde::Serialize + serde::de::DeserializeOwned")]
+pub struct NldmTable<C: 'static + Ctx> {
+  /// The derived table
+  pub table: TimingTableLookUp<C>,
+  /// The maximum absolute difference to the existing table of the arc,
+  /// `None` if there is no existing one
+  pub deviation: Option<f64>,
+}
+
+/// The NLDM tables derived by [`Timing::nldm_from_ccs`],
+/// `None` if there is no CCS waveform of the direction
+#[derive(Debug, Clone)]
+pub struct CcsNldm<C: 'static + Ctx> {
+  /// From `output_current_rise`
+  pub cell_rise: Option<Result<NldmTable<C>, NldmError>>,
+  /// From `output_current_fall`
+  pub cell_fall: Option<Result<NldmTable<C>, NldmError>>,
+  /// From `output_current_rise`
+  pub rise_transition: Option<Result<NldmTable<C>, NldmError>>,
+  /// From `output_current_fall`
+  pub fall_transition: Option<Result<NldmTable<C>, NldmError>>,
+}
+
+/// The `lu_table_template` on the grid of the CCS vectors,
+/// named by its size, e.g., `ccs_delay_7x7`
+fn ccs_template<C: 'static + Ctx>(ccs: &ReferenceTimeVector3DGrpup<C>) -> TableTemple<C> {
+  let index_1 = axis(ccs.vector.iter().map(|vector| vector.index_1));
+  let index_2 =
+    axis(ccs.vector.iter().filter_map(|vector| vector.index_2.first().copied()));
+  TableTemple {
+    name: format!("ccs_delay_{}x{}", index_1.len(), index_2.len()),
+    comments: GroupComments::default(),
+    extra_ctx: C::Other::default(),
+    attributes: Attributes::default(),
+    variable_1: Some(Variable::INPUT_NET_TRANSITION),
+    variable_2: Some(Variable::TOTAL_OUTPUT_NET_CAPACITANCE),
+    variable_3: None,
+    variable_4: None,
+    index_1: Some(index_1),
+    index_2: Some(index_2),
+    index_3: None,
+    index_4: None,
+  }
+}
+
+/// Simulate the CCS waveforms on the grid of `existing` table,
+/// or of `template` if there is no existing one,
+/// or of the CCS vectors without both, see [`ccs_template`]
+#[expect(clippy::float_arithmetic)]
+fn derive<C: 'static + Ctx>(
+  ccs: &ReferenceTimeVector3DGrpup<C>,
+  existing: Option<&TimingTableLookUp<C>>,
+  template: Option<&Arc<TableTemple<C>>>,
+  vdd: f64,
+  thresholds: &TimingThresholds,
+  delay: bool,
+) -> Result<NldmTable<C>, NldmError> {
+  let grid_template = existing
+    .and_then(|table| table.extra_ctx.lut_template().as_ref())
+    .or(template)
+    .map_or_else(|| Arc::new(ccs_template(ccs)), Arc::clone);
+  let load_first = match (grid_template.variable_1, grid_template.variable_2) {
+    (
+      Some(Variable::INPUT_NET_TRANSITION),
+      Some(Variable::TOTAL_OUTPUT_NET_CAPACITANCE),
+    ) => false,
+    (
+      Some(Variable::TOTAL_OUTPUT_NET_CAPACITANCE),
+      Some(Variable::INPUT_NET_TRANSITION),
+    ) => true,
+    variables => return Err(NldmError::Variables(variables.into())),
+  };
+  let index = |table_index: Option<&Vec<f64>>, template_index: &Option<Vec<f64>>, i| {
+    table_index
+      .filter(|index| !index.is_empty())
+      .or(template_index.as_ref())
+      .cloned()
+      .ok_or(NldmError::TemplateIndex(i))
+  };
+  let index_1 = index(existing.map(|table| &table.index_1), &grid_template.index_1, 1)?;
+  let index_2 = index(existing.map(|table| &table.index_2), &grid_template.index_2, 2)?;
+  let mut values = Vec::with_capacity(index_1.len().saturating_mul(index_2.len()));
+  for x1 in &index_1 {
+    for x2 in &index_2 {
+      let (slew, load) = if load_first { (*x2, *x1) } else { (*x1, *x2) };
+      let timing = ccs
+        .timing(slew, load, vdd, thresholds)
+        .map_err(|error| NldmError::Waveform { index_1: *x1, index_2: *x2, error })?;
+      values.push(if delay { timing.delay } else { timing.slew });
+    }
+  }
+  let deviation =
+    existing
+      .filter(|table| table.values.len() == values.len())
+      .map(|table| {
+        table
+          .values
+          .iter()
+          .zip(&values)
+          .fold(0.0, |max, (a, b)| f64::max(max, (a - b).abs()))
+      });
+  let mut table = TimingTableLookUp::<C>::default();
+  table.extra_ctx.set_lut_template(Some(&grid_template));
+  table.name.clone_from(&grid_template.name);
+  table.index_1 = index_1;
+  table.index_2 = index_2;
+  table.values = values;
+  Ok(NldmTable { table, deviation })
+}
+
+impl<C: 'static + Ctx> Timing<C> {
+  /// Derive the NLDM tables by simulating the CCS `output_current_rise/fall`
+  /// into the indexed load, with the supply voltage `vdd`, and measuring
+  /// the delay and slew at the `rise` / `fall` thresholds,
+  /// see [`ReferenceTimeVector3DGrpup::timing`].
+  ///
+  /// Each table is derived on the grid of the existing table of the arc,
+  /// or of `template` if there is no existing one, and the deviation to the
+  /// existing table is measured. The template variables shall be
+  /// `input_net_transition` and `total_output_net_capacitance`.
+  /// Without both, the table is derived on the `index_1` and `index_2` of the
+  /// CCS vectors, with a new template named by its size, e.g., `ccs_delay_7x7`.
+  #[inline]
+  #[must_use]
+  pub fn nldm_from_ccs(
+    &self,
+    template: Option<&Arc<TableTemple<C>>>,
+    vdd: f64,
+    rise: &TimingThresholds,
+    fall: &TimingThresholds,
+  ) -> CcsNldm<C> {
+    let derive_from = |ccs: &Option<ReferenceTimeVector3DGrpup<C>>,
+                       existing: &Option<TimingTableLookUp<C>>,
+                       thresholds: &TimingThresholds,
+                       delay: bool| {
+      ccs
+        .as_ref()
+        .map(|group| derive(group, existing.as_ref(), template, vdd, thresholds, delay))
+    };
+    CcsNldm {
+      cell_rise: derive_from(&self.output_current_rise, &self.cell_rise, rise, true),
+      cell_fall: derive_from(&self.output_current_fall, &self.cell_fall, fall, true),
+      rise_transition: derive_from(
+        &self.output_current_rise,
+        &self.rise_transition,
+        rise,
+        false,
+      ),
+      fall_transition: derive_from(
+        &self.output_current_fall,
+        &self.fall_transition,
+        fall,
+        false,
+      ),
+    }
+  }
+}
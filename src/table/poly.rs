@@ -0,0 +1,408 @@
+//! Evaluation of the scalable polynomial delay model (SPDM),
+//! see [`TableLookUp::poly_eval`].
+#![allow(clippy::multiple_inherent_impl)]
+use super::{PolyTableCtx as _, PolyTemplate, TableLookUp, TableLookUpDomain, Variable};
+use crate::{
+  Ctx,
+  ast::LibertySet,
+  library::{PowerSupply, VoltageMapping},
+};
+
+/// The variable values to evaluate a polynomial table, see [`TableLookUp::poly_eval`]
+#[derive(Debug, Clone, Default)]
+#[derive(PartialEq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct PolyPoint {
+  /// The values of variables, e.g., `input_noise_width`, `temperature`
+  pub variables: Vec<(Variable, f64)>,
+  /// The voltages of power rails, for the `voltage`/`voltagei` variables
+  /// that are mapped to the rails by the `mapping` of template
+  pub rails: Vec<(String, f64)>,
+}
+
+impl PolyPoint {
+  /// Set the value of `variable`
+  #[must_use]
+  #[inline]
+  pub fn variable(mut self, variable: Variable, value: f64) -> Self {
+    if let Some(v) = self.variables.iter_mut().find(|(var, _)| *var == variable) {
+      v.1 = value;
+    } else {
+      self.variables.push((variable, value));
+    }
+    self
+  }
+  /// Set the voltage of power rail `name`
+  #[must_use]
+  #[inline]
+  pub fn rail(mut self, name: &str, voltage: f64) -> Self {
+    if let Some(v) = self.rails.iter_mut().find(|(rail, _)| rail == name) {
+      v.1 = voltage;
+    } else {
+      self.rails.push((name.to_owned(), voltage));
+    }
+    self
+  }
+  /// Set the voltages of all the `power_rail` in `power_supply`
+  #[must_use]
+  #[inline]
+  pub fn power_supply<C: 'static + Ctx>(self, power_supply: &PowerSupply<C>) -> Self {
+    power_supply
+      .power_rail
+      .iter()
+      .fold(self, |point, rail| point.rail(&rail.name, rail.voltage))
+  }
+  /// The value of `variable`, the voltage variables fall back to
+  /// the power rails by `mapping`
+  fn get(&self, variable: Variable, mapping: &LibertySet<VoltageMapping>) -> Option<f64> {
+    self
+      .variables
+      .iter()
+      .find(|(var, _)| *var == variable)
+      .map(|(_, value)| *value)
+      .or_else(|| {
+        let Variable::VoltageName(name) = variable else {
+          return None;
+        };
+        let rail = &mapping.get(&name)?.value;
+        self
+          .rails
+          .iter()
+          .find(|(r, _)| r == rail)
+          .map(|(_, voltage)| *voltage)
+      })
+  }
+}
+
+/// The result of [`TableLookUp::poly_eval`]
+#[derive(Debug, Clone)]
+#[derive(PartialEq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct PolyValue {
+  /// The value of polynomial
+  pub value: f64,
+  /// The name of selected domain, `None` for the table without domain
+  pub domain: Option<String>,
+  /// Whether any variable is out of its `variable_i_range`,
+  /// it is only possible for the table without domain
+  pub extrapolated: bool,
+}
+
+/// The error of [`TableLookUp::poly_eval`]
+#[derive(Debug, Clone, thiserror::Error, PartialEq, Eq)]
+pub enum PolyError {
+  /// The table does not refer to a `poly_template`
+  #[error("no poly_template")]
+  NoTemplate,
+  /// The value of variable is not given in [`PolyPoint`]
+  #[error("variable {0} is not given")]
+  MissingVariable(Variable),
+  /// None of the template domains contains the variables
+  #[error("no domain contains the variables")]
+  NoDomain,
+  /// The selected domain of template is not defined in the table
+  #[error("domain {0} is not defined")]
+  MissingDomain(String),
+  /// The number of orders mismatches with the variables
+  #[error("expect {expected} orders, found {found}")]
+  Orders {
+    /// The number of variables
+    expected: usize,
+    /// The number of orders
+    found: usize,
+  },
+  /// The order is negative
+  #[error("negative order {0}")]
+  NegativeOrder(isize),
+  /// The number of coefs mismatches with the orders
+  #[error("expect {expected} coefs, found {found}")]
+  Coefs {
+    /// The product of `order + 1`
+    expected: usize,
+    /// The number of coefs
+    found: usize,
+  },
+  /// The `index_{0}` of `lu_table_template` is not defined
+  #[error("index_{0} of template is not defined")]
+  TemplateIndex(usize),
+  /// The `lu_table_template` has more variables than the table
+  #[error("expect at most {expected} variables, found {found}")]
+  Dimension {
+    /// The number of indices of table
+    expected: usize,
+    /// The number of variables of template
+    found: usize,
+  },
+}
+
+/// The values of `variables`, and whether all of them are in `variable_range`
+fn values(
+  variable_range: &[Option<[f64; 2]>],
+  variables: &[Variable],
+  mapping: &LibertySet<VoltageMapping>,
+  point: &PolyPoint,
+) -> Result<(Vec<f64>, bool), PolyError> {
+  let mut inside = true;
+  let mut values = Vec::with_capacity(variables.len());
+  for (i, variable) in variables.iter().enumerate() {
+    let value = point
+      .get(*variable, mapping)
+      .ok_or(PolyError::MissingVariable(*variable))?;
+    if let Some(Some([lo, hi])) = variable_range.get(i) {
+      inside &= (*lo..=*hi).contains(&value);
+    }
+    values.push(value);
+  }
+  Ok((values, inside))
+}
+
+/// Evaluate the polynomial of `orders` and `coefs` at `x`,
+/// the exponent of the first variable changes fastest in `coefs`
+#[expect(clippy::float_arithmetic)]
+pub(super) fn evaluate(
+  orders: &[isize],
+  coefs: &[f64],
+  x: &[f64],
+) -> Result<f64, PolyError> {
+  if orders.len() != x.len() {
+    return Err(PolyError::Orders { expected: x.len(), found: orders.len() });
+  }
+  // the powers `[1, x, x^2, ..]` of each variable
+  let mut powers = Vec::with_capacity(x.len());
+  for (order, xi) in orders.iter().zip(x) {
+    let n = usize::try_from(*order).map_err(|_e| PolyError::NegativeOrder(*order))?;
+    powers.push(
+      core::iter::successors(Some(1.0), |p| Some(p * xi))
+        .take(n.saturating_add(1))
+        .collect::<Vec<f64>>(),
+    );
+  }
+  let expected = powers.iter().map(Vec::len).product();
+  if coefs.len() != expected {
+    return Err(PolyError::Coefs { expected, found: coefs.len() });
+  }
+  let mut exponents = vec![0_usize; x.len()];
+  let mut value = 0.0;
+  for coef in coefs {
+    let term = exponents
+      .iter()
+      .zip(&powers)
+      .filter_map(|(e, power)| power.get(*e))
+      .product::<f64>();
+    value = coef.mul_add(term, value);
+    // the next exponents, the first variable changes fastest
+    for (e, power) in exponents.iter_mut().zip(&powers) {
+      *e = e.saturating_add(1);
+      if *e < power.len() {
+        break;
+      }
+      *e = 0;
+    }
+  }
+  Ok(value)
+}
+
+/// Evaluate `orders` and `coefs` with the variables of `template`
+fn eval_template<C: 'static + Ctx>(
+  template: &PolyTemplate<C>,
+  orders: &[isize],
+  coefs: &[f64],
+  point: &PolyPoint,
+) -> Result<PolyValue, PolyError> {
+  let (x, inside) =
+    values(&template.variable_range, &template.variables, &template.mapping, point)?;
+  Ok(PolyValue {
+    value: evaluate(orders, coefs, &x)?,
+    domain: None,
+    extrapolated: !inside,
+  })
+}
+
+impl<C: 'static + Ctx> TableLookUpDomain<C> {
+  /// Evaluate the `orders` and `coefs` at `point` with the `variables` of the linked
+  /// `poly_template`, regardless of its domains, e.g., the result of
+  /// [`TimingTableLookUp::fit_poly`](crate::timing::TimingTableLookUp::fit_poly).
+  ///
+  /// See [`TableLookUp::poly_eval`] for the variables and the order of `coefs`.
+  #[inline]
+  pub fn poly_eval(&self, point: &PolyPoint) -> Result<PolyValue, PolyError> {
+    let template = self.poly_ctx.poly_template().as_ref().ok_or(PolyError::NoTemplate)?;
+    eval_template(template, &self.orders, &self.coefs.inner, point)
+  }
+}
+
+impl<C: 'static + Ctx> TableLookUp<C> {
+  /// Evaluate the scalable polynomial delay model at `point`,
+  /// with the `variables` of the linked `poly_template`.
+  ///
+  /// When the template has domains, the first domain containing `point` in all its
+  /// `variable_i_range` is selected, and the table `domain` with the same name provides
+  /// `orders` and `coefs`. Otherwise, the `orders` and `coefs` of table are used.
+  /// The `voltage`/`voltagei` variables take the voltage of the power rail
+  /// by `mapping`, unless they are given in `point`.
+  ///
+  /// The polynomial is `sum(coef * x_1^e_1 * .. * x_n^e_n)` with `e_i` in `0..=order_i`,
+  /// where the exponent of the first variable changes fastest in `coefs`.
+  #[inline]
+  pub fn poly_eval(&self, point: &PolyPoint) -> Result<PolyValue, PolyError> {
+    let template = self.poly_ctx.poly_template().as_ref().ok_or(PolyError::NoTemplate)?;
+    if template.domain.is_empty() {
+      return eval_template(template, &self.orders, &self.coefs.inner, point);
+    }
+    for domain in &template.domain {
+      let variables =
+        if domain.variables.is_empty() { &template.variables } else { &domain.variables };
+      let mapping =
+        if domain.mapping.is_empty() { &template.mapping } else { &domain.mapping };
+      let (x, inside) = values(&domain.variable_range, variables, mapping, point)?;
+      if inside {
+        let table = self
+          .domain
+          .get(domain.name.as_str())
+          .ok_or_else(|| PolyError::MissingDomain(domain.name.clone()))?;
+        return Ok(PolyValue {
+          value: evaluate(&table.orders, &table.coefs.inner, &x)?,
+          domain: Some(domain.name.clone()),
+          extrapolated: false,
+        });
+      }
+    }
+    Err(PolyError::NoDomain)
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::{DefaultCtx, Library};
+  const LIB: &str = include_str!("../../dev/tech/test_suite/inverter_spdm.lib");
+  fn assert_close(a: f64, b: f64) {
+    assert!((a - b).abs() < 1e-9, "{a} != {b}");
+  }
+  #[test]
+  fn spdm_eval() {
+    let library = Library::<DefaultCtx>::parse_lib(LIB, None).unwrap();
+    let cell = library.cell.get("INV").unwrap();
+    let timing = cell.pin.get("Y").unwrap().timing.iter().next().unwrap();
+    let table = timing.noise_immunity_low.as_ref().unwrap();
+    assert_eq!(
+      table.poly_ctx.poly_template().as_ref().map(|t| t.name.as_str()),
+      Some("my_noise_reject")
+    );
+    let (width, load) = (0.5, 0.2);
+    let point = PolyPoint::default()
+      .power_supply(library.power_supply.as_ref().unwrap())
+      .variable(Variable::INPUT_NOISE_WIDTH, width)
+      .variable(Variable::TOTAL_OUTPUT_NET_CAPACITANCE, load);
+    // typ: all the coefs are 1 except `w*v*v1` and `w*v*v1*t`
+    let typ = table
+      .poly_eval(&point.clone().variable(Variable::TEMPERATURE, 25.0))
+      .unwrap();
+    let x = [width, 1.6, 1.3, 25.0, load];
+    assert_eq!(typ.domain.as_deref(), Some("typ"));
+    assert_close(
+      typ.value,
+      (x[0] * x[1] * x[2])
+        .mul_add(-(1.0 + x[3]), x.iter().map(|xi| 1.0 + xi).product::<f64>()),
+    );
+    // max needs voltage1 in (1.1, 1.2)
+    assert_eq!(
+      table.poly_eval(&point.clone().variable(Variable::TEMPERATURE, 125.0)),
+      Err(PolyError::NoDomain)
+    );
+    // min: orders (1, 3, 1, 1), without the load
+    let min = table
+      .poly_eval(
+        &point
+          .clone()
+          .rail("VDD1", 1.75)
+          .rail("VDD2", 1.45)
+          .variable(Variable::TEMPERATURE, -40.0),
+      )
+      .unwrap();
+    assert_eq!(min.domain.as_deref(), Some("min"));
+    let coefs = &table.domain.get("min").unwrap().coefs.inner;
+    let mut expected = 0.0;
+    let mut k = 0;
+    for t in 0..2 {
+      for v1 in 0..2 {
+        for v in 0..4 {
+          for w in 0..2 {
+            expected += coefs[k]
+              * width.powi(w)
+              * 1.75_f64.powi(v)
+              * 1.45_f64.powi(v1)
+              * (-40.0_f64).powi(t);
+            k += 1;
+          }
+        }
+      }
+    }
+    assert_close(min.value, expected);
+    assert_eq!(
+      table.poly_eval(&PolyPoint::default().variable(Variable::TEMPERATURE, 25.0)),
+      Err(PolyError::MissingVariable(Variable::INPUT_NOISE_WIDTH))
+    );
+    // no domain: `variable_i_range` of template
+    let below_low = timing.noise_immunity_below_low.as_ref().unwrap();
+    let value = below_low
+      .poly_eval(&point.variable(Variable::TEMPERATURE, 200.0))
+      .unwrap();
+    assert!(value.extrapolated && value.domain.is_none());
+  }
+  #[test]
+  fn power_poly_template() {
+    let library = Library::<DefaultCtx>::parse_lib(
+      r#"library (test) {
+        poly_template (poly) {
+          variables (input_noise_width);
+          variable_1_range (0, 1);
+        }
+        power_poly_template (poly) {
+          variables (input_transition_time, total_output_net_capacitance);
+          variable_1_range (0, 1);
+          variable_2_range (0, 1);
+        }
+        cell (INV) {
+          pin (A) { direction : input; }
+          pin (Y) {
+            direction : output;
+            function : "!A";
+            timing () {
+              related_pin : "A";
+              noise_immunity_low (poly) {
+                orders ("1");
+                coefs ("1, 2");
+              }
+            }
+            internal_power () {
+              related_pin : "A";
+              rise_power (poly) {
+                orders ("1, 1");
+                coefs ("1, 2, 3, 4");
+              }
+            }
+          }
+        }
+      }"#,
+      None,
+    )
+    .unwrap();
+    let pin = library.cell.get("INV").unwrap().pin.get("Y").unwrap();
+    let timing = pin.timing.iter().next().unwrap();
+    let noise = timing.noise_immunity_low.as_ref().unwrap();
+    let power = pin.internal_power.iter().next().unwrap().rise_power.as_ref().unwrap();
+    let variables = |table: &TableLookUp<DefaultCtx>| {
+      table.poly_ctx.poly_template().as_ref().map(|t| t.variables.len())
+    };
+    // the templates with the same name are kept apart
+    assert_eq!(variables(noise), Some(1));
+    assert_eq!(variables(power), Some(2));
+    let point = PolyPoint::default()
+      .variable(Variable::INPUT_NOISE_WIDTH, 0.5)
+      .variable(Variable::INPUT_TRANSITION_TIME, 0.5)
+      .variable(Variable::TOTAL_OUTPUT_NET_CAPACITANCE, 0.5);
+    assert_close(noise.poly_eval(&point).unwrap().value, 2.0);
+    assert_close(power.poly_eval(&point).unwrap().value, 4.5);
+  }
+}
@@ -0,0 +1,269 @@
+//! The 2-D timing table prepared for the repeated lookups in static timing analysis,
+//! see [`PreparedLookup`].
+#![allow(clippy::multiple_inherent_impl)]
+#[cfg(feature = "lut_template")]
+use super::{TableCtx as _, Variable};
+use super::{TableDefect, check::grid_defects};
+use crate::{Ctx, timing::TimingTableLookUp};
+
+/// The value and its partial derivatives, see [`PreparedLookup::lookup`]
+#[derive(Debug, Clone, Copy, Default)]
+#[derive(PartialEq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct LookupGradient {
+  /// The value at `(transition, load)`
+  pub value: f64,
+  /// The derivative with respect to the input transition, `d value / d transition`
+  pub d_slew: f64,
+  /// The derivative with respect to the output load, `d value / d load`
+  pub d_load: f64,
+}
+
+/// One axis of [`PreparedLookup`], with at least one point
+#[derive(Debug, Clone)]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct Axis {
+  index: Vec<f64>,
+}
+
+impl Axis {
+  /// The empty index is treated as a single point, i.e., constant along it
+  fn new(index: &[f64]) -> Self {
+    Self {
+      index: if index.is_empty() { vec![0.0] } else { index.to_vec() },
+    }
+  }
+  /// The number of segments
+  fn segments(&self) -> usize {
+    self.index.len().saturating_sub(1).max(1)
+  }
+  /// The `(lo, hi)` point of segment `i`
+  fn bounds(&self, i: usize) -> (usize, usize) {
+    (i, i.saturating_add(1).min(self.index.len().saturating_sub(1)))
+  }
+  /// The segment of `x`, and the offset from its lower point.
+  /// The end segments are used for the out-of-range `x`.
+  #[expect(clippy::float_arithmetic)]
+  fn locate(&self, x: f64) -> (usize, f64) {
+    let segment = self
+      .index
+      .partition_point(|v| *v < x)
+      .saturating_sub(1)
+      .min(self.segments().saturating_sub(1));
+    (segment, self.index.get(segment).map_or(0.0, |lo| x - lo))
+  }
+}
+
+/// A 2-D [`TimingTableLookUp`] prepared for the repeated lookups.
+///
+/// The bilinear coefficients of every grid cell are computed once, so a lookup
+/// is two binary searches on the plain indices and a few multiply-adds.
+/// It gives the same values as [`TimingTableLookUp::lookup`] (including the linear
+/// extrapolation), along with the derivatives, which are one-sided at the grid points.
+/// The tables with single-point or empty indices are constant along those indices.
+#[derive(Debug, Clone)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct PreparedLookup {
+  slew: Axis,
+  load: Axis,
+  /// The bilinear coefficients `[v, d_slew, d_load, d_cross]` of grid cells,
+  /// in row-major order of (slew segment, load segment)
+  patches: Vec<[f64; 4]>,
+}
+
+impl PreparedLookup {
+  /// `values` in row-major order of (`slew`, `load`)
+  #[expect(clippy::float_arithmetic, clippy::arithmetic_side_effects)]
+  fn new(
+    slew: Axis,
+    load: Axis,
+    value: impl Fn(usize, usize) -> Option<f64>,
+  ) -> Option<Self> {
+    let mut patches = Vec::with_capacity(slew.segments() * load.segments());
+    for i in 0..slew.segments() {
+      let (i0, i1) = slew.bounds(i);
+      let h_slew = slew.index.get(i1)? - slew.index.get(i0)?;
+      for j in 0..load.segments() {
+        let (j0, j1) = load.bounds(j);
+        let h_load = load.index.get(j1)? - load.index.get(j0)?;
+        let (v00, v01, v10, v11) =
+          (value(i0, j0)?, value(i0, j1)?, value(i1, j0)?, value(i1, j1)?);
+        let d_slew = if i0 == i1 { 0.0 } else { (v10 - v00) / h_slew };
+        let d_load = if j0 == j1 { 0.0 } else { (v01 - v00) / h_load };
+        let d_cross = if i0 == i1 || j0 == j1 {
+          0.0
+        } else {
+          (v11 - v10 - v01 + v00) / (h_slew * h_load)
+        };
+        patches.push([v00, d_slew, d_load, d_cross]);
+      }
+    }
+    Some(Self { slew, load, patches })
+  }
+  /// The value and derivatives at `(transition, load)`
+  #[must_use]
+  #[inline]
+  #[expect(clippy::float_arithmetic, clippy::arithmetic_side_effects)]
+  pub fn lookup(&self, transition: f64, load: f64) -> LookupGradient {
+    let (i, dx) = self.slew.locate(transition);
+    let (j, dy) = self.load.locate(load);
+    let [v, d_slew, d_load, d_cross] = self
+      .patches
+      .get(i * self.load.segments() + j)
+      .copied()
+      .unwrap_or_default();
+    LookupGradient {
+      value: d_cross.mul_add(dx * dy, d_load.mul_add(dy, d_slew.mul_add(dx, v))),
+      d_slew: d_cross.mul_add(dy, d_slew),
+      d_load: d_cross.mul_add(dx, d_load),
+    }
+  }
+  /// Look up the `(transition, load)` pairs at once
+  #[must_use]
+  #[inline]
+  pub fn lookup_batch(&self, queries: &[(f64, f64)]) -> Vec<LookupGradient> {
+    queries
+      .iter()
+      .map(|(transition, load)| self.lookup(*transition, *load))
+      .collect()
+  }
+  /// Look up the `(transition, load)` pairs into `out`,
+  /// to reuse the buffer across iterations
+  #[inline]
+  pub fn lookup_batch_into(&self, queries: &[(f64, f64)], out: &mut Vec<LookupGradient>) {
+    out.clear();
+    out.extend(
+      queries
+        .iter()
+        .map(|(transition, load)| self.lookup(*transition, *load)),
+    );
+  }
+}
+
+impl<C: 'static + Ctx> TimingTableLookUp<C> {
+  /// Whether `index_1` is the output load and `index_2` is the input transition,
+  /// by the variables of template
+  fn load_first(&self) -> bool {
+    #[cfg(feature = "lut_template")]
+    if let Some(template) = self.extra_ctx.lut_template() {
+      return matches!(
+        (template.variable_1, template.variable_2),
+        (Some(Variable::Capacitance(_)), None | Some(Variable::Time(_)))
+      );
+    }
+    false
+  }
+  /// Prepare the table for the repeated lookups of `(transition, load)`.
+  ///
+  /// The axes are arranged by the variables of template, `index_1` is taken as the
+  /// transition unless the template says it is a capacitance.
+  /// Return the first defect when the indices are not strictly increasing,
+  /// or the number of values mismatches.
+  #[inline]
+  #[expect(clippy::arithmetic_side_effects)]
+  pub fn prepare(&self) -> Result<PreparedLookup, TableDefect> {
+    let all_indices = [self.index_1.as_slice(), &self.index_2];
+    if let Some(defect) = grid_defects(&all_indices, self.values.len()).into_iter().next()
+    {
+      return Err(defect);
+    }
+    let (index_1, index_2) = (Axis::new(&self.index_1), Axis::new(&self.index_2));
+    let len_2 = index_2.index.len();
+    let value = |i1: usize, i2: usize| self.values.get(i1 * len_2 + i2).copied();
+    if self.load_first() {
+      PreparedLookup::new(index_2, index_1, |i, j| value(j, i))
+    } else {
+      PreparedLookup::new(index_1, index_2, value)
+    }
+    .ok_or(TableDefect::ValuesLength {
+      expected: self.index_1.len().max(1) * self.index_2.len().max(1),
+      found: self.values.len(),
+    })
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::{DefaultCtx, Library};
+  const LIB: &str = r#"library (test) {
+    lu_table_template (delay_slew_first) {
+      variable_1 : input_net_transition;
+      variable_2 : total_output_net_capacitance;
+      index_1 ("10, 30");
+      index_2 ("30, 50, 60");
+    }
+    lu_table_template (delay_load_first) {
+      variable_1 : total_output_net_capacitance;
+      variable_2 : input_net_transition;
+      index_1 ("30, 50, 60");
+      index_2 ("10, 30");
+    }
+    lu_table_template (delay_slew) {
+      variable_1 : input_net_transition;
+      index_1 ("10, 30");
+    }
+    cell (INV) {
+      pin (A) { direction : input; }
+      pin (Y) {
+        direction : output;
+        function : "!A";
+        timing () {
+          related_pin : "A";
+          cell_rise (delay_slew_first) {
+            index_1 ("10, 30");
+            index_2 ("30, 50, 60");
+            values ("100, 200, 300", "700, 900, 1200");
+          }
+          cell_fall (delay_load_first) {
+            index_1 ("30, 50, 60");
+            index_2 ("10, 30");
+            values ("100, 700", "200, 900", "300, 1200");
+          }
+          rise_transition (delay_slew) {
+            index_1 ("10, 30");
+            values ("5, 9");
+          }
+        }
+      }
+    }
+  }"#;
+  fn assert_close(a: f64, b: f64) {
+    assert!((a - b).abs() < 1e-9, "{a} != {b}");
+  }
+  #[test]
+  fn prepared_lookup() {
+    let library = Library::<DefaultCtx>::parse_lib(LIB, None).unwrap();
+    let cell = library.cell.get("INV").unwrap();
+    let timing = cell.pin.get("Y").unwrap().timing.iter().next().unwrap();
+    let cell_rise = timing.cell_rise.as_ref().unwrap();
+    let cell_fall = timing.cell_fall.as_ref().unwrap();
+    let rise = cell_rise.prepare().unwrap();
+    let fall = cell_fall.prepare().unwrap();
+    let queries = [(20.0, 40.0), (10.0, 50.0), (5.0, 70.0), (40.0, 20.0), (30.0, 55.0)];
+    let batch = rise.lookup_batch(&queries);
+    for ((transition, load), gradient) in queries.iter().zip(&batch) {
+      assert_close(gradient.value, cell_rise.lookup(transition, load).unwrap());
+      // the transposed table gives the same result
+      assert_eq!(fall.lookup(*transition, *load), *gradient);
+    }
+    // the derivatives inside the grid cell
+    let h = 1e-3;
+    let at = rise.lookup(20.0, 40.0);
+    assert_close(at.d_slew, (rise.lookup(20.0 + h, 40.0).value - at.value) / h);
+    assert_close(at.d_load, (rise.lookup(20.0, 40.0 + h).value - at.value) / h);
+    assert_eq!(at, LookupGradient { value: 475.0, d_slew: 32.5, d_load: 7.5 });
+    let mut out = Vec::new();
+    rise.lookup_batch_into(&queries[..2], &mut out);
+    assert_eq!(out, batch[..2]);
+    // 1-D table is constant along the load
+    let slew = timing.rise_transition.as_ref().unwrap().prepare().unwrap();
+    assert_eq!(
+      slew.lookup(20.0, 1e3),
+      LookupGradient { value: 7.0, d_slew: 0.2, d_load: 0.0 }
+    );
+    let mut broken = cell_rise.clone();
+    broken.index_2 = vec![30.0, 60.0, 50.0];
+    assert_eq!(broken.prepare().err(), Some(TableDefect::UnsortedIndex(2)));
+  }
+}
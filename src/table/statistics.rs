@@ -0,0 +1,482 @@
+//! Statistical queries on the LVF data of timing tables: quantiles, CDF and
+//! seeded sampling, see [`LvfDistribution`].
+#![allow(clippy::multiple_inherent_impl)]
+use super::{LookupConfig, LookupError, TableDefect};
+use crate::{
+  Ctx,
+  timing::{LVFEarlyLate, LVFMoments, TimingTableLookUp},
+};
+
+/// The standard normal variable beyond which the probability is negligible,
+/// `Φ(-Z_LIMIT) < 1e-300`
+const Z_LIMIT: f64 = 37.0;
+
+/// The standard normal CDF `Φ(z)`, by the complementary error function
+/// with fractional error less than `1.2e-7` (Numerical Recipes `erfcc`)
+#[expect(clippy::float_arithmetic)]
+fn normal_cdf(z: f64) -> f64 {
+  let x = z.abs() / core::f64::consts::SQRT_2;
+  let t = 1.0 / 0.5_f64.mul_add(x, 1.0);
+  let poly = [
+    -0.822_152_23,
+    1.488_515_87,
+    -1.135_203_98,
+    0.278_868_07,
+    -0.186_288_06,
+    0.096_784_18,
+    0.374_091_96,
+    1.000_023_68,
+    -1.265_512_23,
+  ]
+  .iter()
+  .fold(0.170_872_77_f64, |acc, c| acc.mul_add(t, *c));
+  let erfc = t * x.mul_add(-x, poly).exp();
+  if z >= 0.0 { 0.5_f64.mul_add(-erfc, 1.0) } else { 0.5 * erfc }
+}
+
+/// The standard normal quantile `Φ^-1(p)` for `p` in `(0, 1)`,
+/// with relative error less than `1.15e-9` (Acklam's algorithm)
+#[expect(clippy::float_arithmetic)]
+fn normal_quantile(p: f64) -> f64 {
+  const A: [f64; 6] = [
+    -3.969_683_028_665_376e1,
+    2.209_460_984_245_205e2,
+    -2.759_285_104_469_687e2,
+    1.383_577_518_672_69e2,
+    -3.066_479_806_614_716e1,
+    2.506_628_277_459_239,
+  ];
+  const B: [f64; 5] = [
+    -5.447_609_879_822_406e1,
+    1.615_858_368_580_409e2,
+    -1.556_989_798_598_866e2,
+    6.680_131_188_771_972e1,
+    -1.328_068_155_288_572e1,
+  ];
+  const C: [f64; 6] = [
+    -7.784_894_002_430_293e-3,
+    -3.223_964_580_411_365e-1,
+    -2.400_758_277_161_838,
+    -2.549_732_539_343_734,
+    4.374_664_141_464_968,
+    2.938_163_982_698_783,
+  ];
+  const D: [f64; 4] = [
+    7.784_695_709_041_462e-3,
+    3.224_671_290_700_398e-1,
+    2.445_134_137_142_996,
+    3.754_408_661_907_416,
+  ];
+  const P_LOW: f64 = 0.024_25;
+  let horner = |coefficients: &[f64], x: f64| {
+    coefficients.iter().fold(0.0_f64, |acc, c| acc.mul_add(x, *c))
+  };
+  let tail = |q: f64| horner(&C, q) / horner(&D, q).mul_add(q, 1.0);
+  if p <= 0.0 {
+    -Z_LIMIT
+  } else if p >= 1.0 {
+    Z_LIMIT
+  } else if p < P_LOW {
+    tail((-2.0 * p.ln()).sqrt())
+  } else if p <= 1.0 - P_LOW {
+    let q = p - 0.5;
+    let r = q * q;
+    horner(&A, r) * q / horner(&B, r).mul_add(r, 1.0)
+  } else {
+    -tail((-2.0 * (1.0 - p).ln()).sqrt())
+  }
+}
+
+#[expect(clippy::float_arithmetic)]
+impl LVFMoments {
+  /// The Cornish–Fisher quantile at the standard normal variable `z`,
+  /// `μ + σ[ z + γ*​​(z^2−1)/6 − γ^2*​​(2*z^3−5*z)/36 ]`.
+  ///
+  /// The expansion is monotone only within [`LVFMoments::z_range`],
+  /// so `z` is clamped into it, i.e., the tails beyond are lumped at its ends.
+  #[inline]
+  #[must_use]
+  pub fn quantile(&self, z: f64) -> f64 {
+    let (lo, hi) = self.z_range();
+    self.cornish_fisher(z.clamp(lo, hi))
+  }
+  fn cornish_fisher(&self, z: f64) -> f64 {
+    let g = self.skewness;
+    let z2 = z * z;
+    let tmp1 = (z2 - 1.0) / 6.0;
+    let tmp2 = z * 2.0_f64.mul_add(z2, -5.0) / 36.0;
+    self
+      .std_dev
+      .mul_add((g * g).mul_add(-tmp2, g.mul_add(tmp1, z)), self.mean)
+  }
+  /// The range of standard normal variable `z` where [`LVFMoments::quantile`]
+  /// is monotone, i.e., `1 + γ*z/3 − γ^2*(6*z^2−5)/36 >= 0`,
+  /// which is unbounded (`±∞`) without skewness.
+  #[inline]
+  #[must_use]
+  pub fn z_range(&self) -> (f64, f64) {
+    let g = self.skewness;
+    if g == 0.0 || !g.is_finite() {
+      return (f64::NEG_INFINITY, f64::INFINITY);
+    }
+    // a*z^2 + b*z + c >= 0, with a < 0
+    let a = -g * g / 6.0;
+    let b = g / 3.0;
+    let c = (5.0 * g * g).mul_add(1.0 / 36.0, 1.0);
+    let disc = b.mul_add(b, -4.0 * a * c).sqrt();
+    let (r1, r2) = ((-b + disc) / (2.0 * a), (-b - disc) / (2.0 * a));
+    (r1.min(r2), r1.max(r2))
+  }
+  /// The CDF `P(X <= x)` of the Cornish–Fisher distribution, i.e., `Φ(z)` where
+  /// `quantile(z) = x`, which is a step at `mean` when `std_dev` is zero
+  #[inline]
+  #[must_use]
+  pub fn cdf(&self, x: f64) -> f64 {
+    if self.std_dev <= 0.0 {
+      return if x < self.mean { 0.0 } else { 1.0 };
+    }
+    let (z_min, z_max) = self.z_range();
+    let (mut lo, mut hi) = (z_min.max(-Z_LIMIT), z_max.min(Z_LIMIT));
+    if x < self.cornish_fisher(lo) {
+      return 0.0;
+    }
+    if x >= self.cornish_fisher(hi) {
+      return 1.0;
+    }
+    // bisection on the monotone range
+    for _ in 0..100 {
+      let mid = 0.5 * (lo + hi);
+      if self.cornish_fisher(mid) <= x {
+        lo = mid;
+      } else {
+        hi = mid;
+      }
+      if hi - lo < 1e-12 {
+        break;
+      }
+    }
+    normal_cdf(0.5 * (lo + hi))
+  }
+}
+
+/// The seeded pseudo-random generator of [`LvfDistribution::sample`], `SplitMix64`,
+/// so that the samples are reproducible across runs and platforms
+#[derive(Debug, Clone, Copy)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct LvfRng {
+  state: u64,
+}
+
+impl LvfRng {
+  /// The generator with `seed`
+  #[inline]
+  #[must_use]
+  pub const fn new(seed: u64) -> Self {
+    Self { state: seed }
+  }
+  /// The next uniform `u64`
+  #[inline]
+  pub const fn next_u64(&mut self) -> u64 {
+    self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = self.state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+  }
+  /// The next uniform `f64` in the open interval `(0, 1)`
+  #[inline]
+  #[expect(clippy::as_conversions, clippy::cast_precision_loss, clippy::float_arithmetic)]
+  pub const fn next_f64(&mut self) -> f64 {
+    ((self.next_u64() >> 11) as f64 + 0.5) / (1_u64 << 53) as f64
+  }
+}
+
+/// The distribution of one timing value, see [`TimingTableLookUp::lvf_distribution`]
+#[derive(Debug, Clone, Copy)]
+#[derive(PartialEq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub enum LvfDistribution {
+  /// The Cornish–Fisher expansion of the moments, see [`LVFMoments::quantile`]
+  Moments(LVFMoments),
+  /// The two-piece normal distribution around `nominal`,
+  /// with `early_sigma` below it and `late_sigma` above it
+  EarlyLate {
+    /// The nominal value, i.e., the median
+    nominal: f64,
+    /// The early and late sigmas
+    sigma: LVFEarlyLate,
+  },
+}
+
+#[expect(clippy::float_arithmetic)]
+impl LvfDistribution {
+  /// The quantile at the standard normal variable `z`,
+  /// e.g., `quantile(3.0)` is the 3-sigma late value
+  #[inline]
+  #[must_use]
+  pub fn quantile(&self, z: f64) -> f64 {
+    match self {
+      Self::Moments(moments) => moments.quantile(z),
+      Self::EarlyLate { nominal, sigma } => {
+        z.mul_add(if z < 0.0 { sigma.early_sigma } else { sigma.late_sigma }, *nominal)
+      }
+    }
+  }
+  /// The quantile at the probability `p` in `(0, 1)`
+  #[inline]
+  #[must_use]
+  pub fn quantile_at(&self, p: f64) -> f64 {
+    self.quantile(normal_quantile(p))
+  }
+  /// The CDF `P(X <= x)`
+  #[inline]
+  #[must_use]
+  pub fn cdf(&self, x: f64) -> f64 {
+    match self {
+      Self::Moments(moments) => moments.cdf(x),
+      Self::EarlyLate { nominal, sigma } => {
+        let side = if x < *nominal { sigma.early_sigma } else { sigma.late_sigma };
+        if side > 0.0 {
+          normal_cdf((x - nominal) / side)
+        } else if x < *nominal {
+          0.0
+        } else {
+          1.0
+        }
+      }
+    }
+  }
+  /// Draw one sample by the inverse transform of the next uniform of `rng`
+  #[inline]
+  pub fn sample(&self, rng: &mut LvfRng) -> f64 {
+    self.quantile_at(rng.next_f64())
+  }
+}
+
+impl<C: 'static + Ctx> TimingTableLookUp<C> {
+  /// The distribution at `(idx1, idx2)`, from the LVF moments when exist,
+  /// otherwise from the early/late sigmas around the nominal value,
+  /// looked up with `config`
+  #[inline]
+  pub fn lvf_distribution(
+    &self,
+    idx1: f64,
+    idx2: f64,
+    config: LookupConfig,
+  ) -> Result<LvfDistribution, LookupError> {
+    if !self.lvf_moments_values.is_empty() {
+      let moments = self.lookup_lvf_moments_with(idx1, idx2, config)?;
+      Ok(LvfDistribution::Moments(moments.value))
+    } else if !self.lvf_early_late_values.is_empty() {
+      let sigma = self.lookup_lvf_early_late_with(idx1, idx2, config)?;
+      let nominal = self.lookup_with(idx1, idx2, config)?;
+      Ok(LvfDistribution::EarlyLate { nominal: nominal.value, sigma: sigma.value })
+    } else {
+      Err(TableDefect::LvfLength { expected: self.values.len(), found: 0 }.into())
+    }
+  }
+  /// The quantile at the standard normal variable `z`, see [`LvfDistribution::quantile`]
+  #[inline]
+  pub fn lvf_quantile(
+    &self,
+    idx1: f64,
+    idx2: f64,
+    z: f64,
+    config: LookupConfig,
+  ) -> Result<f64, LookupError> {
+    self.lvf_distribution(idx1, idx2, config).map(|d| d.quantile(z))
+  }
+  /// The CDF `P(X <= x)`, see [`LvfDistribution::cdf`]
+  #[inline]
+  pub fn lvf_cdf(
+    &self,
+    idx1: f64,
+    idx2: f64,
+    x: f64,
+    config: LookupConfig,
+  ) -> Result<f64, LookupError> {
+    self.lvf_distribution(idx1, idx2, config).map(|d| d.cdf(x))
+  }
+  /// Draw `n` samples with `seed`, see [`LvfDistribution::sample`]
+  #[inline]
+  pub fn lvf_samples(
+    &self,
+    idx1: f64,
+    idx2: f64,
+    seed: u64,
+    n: usize,
+    config: LookupConfig,
+  ) -> Result<Vec<f64>, LookupError> {
+    let distribution = self.lvf_distribution(idx1, idx2, config)?;
+    let mut rng = LvfRng::new(seed);
+    Ok(
+      core::iter::repeat_with(|| distribution.sample(&mut rng))
+        .take(n)
+        .collect(),
+    )
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::{DefaultCtx, ast::test_parse, timing::Timing};
+  fn assert_close(a: f64, b: f64, tol: f64) {
+    assert!((a - b).abs() < tol, "{a} != {b}");
+  }
+  #[test]
+  fn standard_normal() {
+    assert_close(normal_cdf(0.0), 0.5, 1e-7);
+    assert_close(normal_cdf(1.0), 0.841_344_746, 1e-7);
+    assert_close(normal_cdf(-3.0), 0.001_349_898, 1e-7);
+    assert_close(normal_quantile(0.975), 1.959_963_985, 1e-8);
+    assert_close(normal_quantile(0.001), -3.090_232_306, 1e-8);
+    for z in [-5.0, -2.5, -0.3, 0.0, 1.2, 4.0] {
+      assert_close(normal_quantile(normal_cdf(z)), z, 1e-5);
+    }
+  }
+  #[test]
+  fn moments_distribution() {
+    // normal
+    let normal = LVFMoments { mean: 1.0, std_dev: 0.1, skewness: 0.0 };
+    assert_close(normal.quantile(1.0), 1.1, 1e-12);
+    assert_close(normal.quantile(-3.0), 0.7, 1e-12);
+    assert_close(normal.cdf(1.1), 0.841_344_746, 1e-7);
+    assert_close(normal.cdf(1.0), 0.5, 1e-7);
+    // skewed, the ±3σ quantiles agree with the early/late sigmas
+    let skewed = LVFMoments { mean: 1.0, std_dev: 0.1, skewness: 0.5 };
+    let early_late = skewed.to_early_late(1.0).unwrap();
+    assert_close(skewed.quantile(3.0), early_late.late_sigma.mul_add(3.0, 1.0), 1e-12);
+    assert_close(skewed.quantile(-3.0), early_late.early_sigma.mul_add(-3.0, 1.0), 1e-12);
+    assert!(skewed.quantile(0.0) < 1.0);
+    for z in [-2.5, -1.0, 0.0, 0.7, 2.0] {
+      assert_close(skewed.cdf(skewed.quantile(z)), normal_cdf(z), 1e-7);
+    }
+    // the tails beyond the monotone range are lumped
+    let (lo, hi) = skewed.z_range();
+    assert!(lo < -3.0 && hi > 3.0);
+    assert_close(skewed.quantile(lo - 1.0), skewed.quantile(lo), 1e-12);
+    assert_close(skewed.cdf(skewed.quantile(lo) - 1e-9), 0.0, 1e-12);
+    // zero std_dev
+    let constant = LVFMoments { mean: 1.0, std_dev: 0.0, skewness: 0.5 };
+    assert_close(constant.quantile(3.0), 1.0, 1e-12);
+    assert_close(constant.cdf(0.999), 0.0, 1e-12);
+    assert_close(constant.cdf(1.0), 1.0, 1e-12);
+  }
+  #[test]
+  fn early_late_distribution() {
+    let distribution = LvfDistribution::EarlyLate {
+      nominal: 1.0,
+      sigma: LVFEarlyLate { early_sigma: 0.1, late_sigma: 0.2 },
+    };
+    assert_close(distribution.quantile(3.0), 1.6, 1e-12);
+    assert_close(distribution.quantile(-3.0), 0.7, 1e-12);
+    assert_close(distribution.quantile_at(0.5), 1.0, 1e-9);
+    assert_close(distribution.cdf(1.0), 0.5, 1e-7);
+    assert_close(distribution.cdf(1.2), 0.841_344_746, 1e-7);
+    assert_close(distribution.cdf(0.9), 1.0 - 0.841_344_746, 1e-7);
+  }
+  #[test]
+  fn sampling() {
+    let moments = LVFMoments { mean: 1.0, std_dev: 0.1, skewness: 0.0 };
+    let distribution = LvfDistribution::Moments(moments);
+    let draw = |distribution: LvfDistribution, seed| {
+      let mut rng = LvfRng::new(seed);
+      core::iter::repeat_with(|| distribution.sample(&mut rng))
+        .take(20000)
+        .collect::<Vec<_>>()
+    };
+    let samples = draw(distribution, 7);
+    assert_eq!(samples, draw(distribution, 7));
+    assert_ne!(samples, draw(distribution, 8));
+    let estimated = LVFMoments::estimate(samples.into_iter().map(Ok::<_, ()>))
+      .unwrap()
+      .unwrap();
+    assert_close(estimated.mean, 1.0, 0.003);
+    assert_close(estimated.std_dev, 0.1, 0.003);
+    assert_close(estimated.skewness, 0.0, 0.05);
+    let skewed = LvfDistribution::Moments(LVFMoments { skewness: 0.3, ..moments });
+    let estimated = LVFMoments::estimate(draw(skewed, 7).into_iter().map(Ok::<_, ()>))
+      .unwrap()
+      .unwrap();
+    assert_close(estimated.mean, 1.0, 0.003);
+    assert_close(estimated.std_dev, 0.1, 0.003);
+    assert_close(estimated.skewness, 0.3, 0.06);
+  }
+  #[test]
+  fn table_queries() {
+    let timing = test_parse::<Timing<DefaultCtx>>(
+      r#"() {
+        cell_rise (delay_template_2x2) {
+          index_1 ("10, 30");
+          index_2 ("30, 50");
+          values ("1.0, 2.0", "3.0, 4.0");
+        }
+        ocv_mean_shift_cell_rise (delay_template_2x2) {
+          index_1 ("10, 30");
+          index_2 ("30, 50");
+          values ("0.0, 0.0", "0.0, 0.0");
+        }
+        ocv_std_dev_cell_rise (delay_template_2x2) {
+          index_1 ("10, 30");
+          index_2 ("30, 50");
+          values ("0.1, 0.2", "0.3, 0.4");
+        }
+        ocv_skewness_cell_rise (delay_template_2x2) {
+          index_1 ("10, 30");
+          index_2 ("30, 50");
+          values ("0.0, 0.0", "0.0, 0.0");
+        }
+        cell_fall (delay_template_2x2) {
+          index_1 ("10, 30");
+          index_2 ("30, 50");
+          values ("1.0, 2.0", "3.0, 4.0");
+        }
+        ocv_sigma_cell_fall (delay_template_2x2) {
+          sigma_type : early;
+          index_1 ("10, 30");
+          index_2 ("30, 50");
+          values ("0.1, 0.2", "0.3, 0.4");
+        }
+        ocv_sigma_cell_fall (delay_template_2x2) {
+          sigma_type : late;
+          index_1 ("10, 30");
+          index_2 ("30, 50");
+          values ("0.2, 0.4", "0.6, 0.8");
+        }
+        rise_transition (delay_template_2x2) {
+          index_1 ("10, 30");
+          index_2 ("30, 50");
+          values ("1.0, 2.0", "3.0, 4.0");
+        }
+      }"#,
+    );
+    let config = LookupConfig::default();
+    let rise = timing.cell_rise.as_ref().unwrap();
+    assert_close(rise.lvf_quantile(30.0, 50.0, 3.0, config).unwrap(), 5.2, 1e-12);
+    assert_close(rise.lvf_quantile(20.0, 40.0, -1.0, config).unwrap(), 2.25, 1e-12);
+    assert_close(rise.lvf_cdf(20.0, 40.0, 2.5, config).unwrap(), 0.5, 1e-7);
+    let fall = timing.cell_fall.as_ref().unwrap();
+    assert_eq!(
+      fall.lvf_distribution(10.0, 30.0, config),
+      Ok(LvfDistribution::EarlyLate {
+        nominal: 1.0,
+        sigma: LVFEarlyLate { early_sigma: 0.1, late_sigma: 0.2 }
+      })
+    );
+    assert_close(fall.lvf_quantile(30.0, 50.0, 3.0, config).unwrap(), 6.4, 1e-12);
+    assert_close(fall.lvf_quantile(30.0, 50.0, -3.0, config).unwrap(), 2.8, 1e-12);
+    let samples = fall.lvf_samples(20.0, 40.0, 1, 1000, config).unwrap();
+    assert_eq!(samples, fall.lvf_samples(20.0, 40.0, 1, 1000, config).unwrap());
+    let below = samples.iter().filter(|x| **x < 2.5).count();
+    assert!((400..600).contains(&below), "{below}");
+    assert_eq!(
+      timing
+        .rise_transition
+        .as_ref()
+        .unwrap()
+        .lvf_quantile(20.0, 40.0, 3.0, config),
+      Err(LookupError::Table(TableDefect::LvfLength { expected: 4, found: 0 }))
+    );
+  }
+}
@@ -0,0 +1,515 @@
+//! Evaluation of the CCS output-current waveforms, and the delay / slew
+//! derived from them, see [`ReferenceTimeVector3DGrpup::waveform`], and of the
+//! CCSN propagated-noise waveforms, see [`Vector4DGrpup::waveform`].
+#![allow(clippy::multiple_inherent_impl)]
+use super::{Lookup, ReferenceTimeVector3DGrpup, Vector4D, Vector4DGrpup};
+use crate::{Ctx, Library, ccsn::VoltageWaveform};
+
+/// The measurement thresholds of delay and slew, in percentage of the supply voltage
+#[derive(Debug, Clone, Copy)]
+#[derive(PartialEq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct TimingThresholds {
+  /// The output threshold of delay, `output_threshold_pct_*`
+  pub delay: f64,
+  /// The lower threshold of slew, `slew_lower_threshold_pct_*`
+  pub slew_lower: f64,
+  /// The upper threshold of slew, `slew_upper_threshold_pct_*`
+  pub slew_upper: f64,
+  /// The slew measured between the thresholds is divided by it,
+  /// `slew_derate_from_library`
+  pub slew_derate: f64,
+}
+
+impl Default for TimingThresholds {
+  #[inline]
+  fn default() -> Self {
+    Self {
+      delay: 50.0,
+      slew_lower: 20.0,
+      slew_upper: 80.0,
+      slew_derate: 1.0,
+    }
+  }
+}
+
+impl TimingThresholds {
+  /// The thresholds of the rising output in `library`
+  #[inline]
+  #[must_use]
+  pub const fn rise<C: 'static + Ctx>(library: &Library<C>) -> Self {
+    Self {
+      delay: library.output_threshold_pct_rise,
+      slew_lower: library.slew_lower_threshold_pct_rise,
+      slew_upper: library.slew_upper_threshold_pct_rise,
+      slew_derate: library.slew_derate_from_library,
+    }
+  }
+  /// The thresholds of the falling output in `library`
+  #[inline]
+  #[must_use]
+  pub const fn fall<C: 'static + Ctx>(library: &Library<C>) -> Self {
+    Self {
+      delay: library.output_threshold_pct_fall,
+      slew_lower: library.slew_lower_threshold_pct_fall,
+      slew_upper: library.slew_upper_threshold_pct_fall,
+      slew_derate: library.slew_derate_from_library,
+    }
+  }
+}
+
+/// The output-current waveform at one `(slew, load)`,
+/// the result of [`ReferenceTimeVector3DGrpup::waveform`]
+#[derive(Debug, Clone, Default)]
+#[derive(PartialEq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct CurrentWaveform {
+  /// The time when the input crosses its delay threshold
+  pub reference_time: f64,
+  /// The time points, ascending
+  pub time: Vec<f64>,
+  /// The output current at the time points, negative for the falling output
+  pub current: Vec<f64>,
+  /// Whether the `(slew, load)` is out of the vectors range,
+  /// so that the waveform is extrapolated
+  pub extrapolated: bool,
+}
+
+/// The delay and slew derived from a [`CurrentWaveform`]
+#[derive(Debug, Clone, Copy, Default)]
+#[derive(PartialEq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct WaveformTiming {
+  /// From `reference_time` to the output crossing the delay threshold
+  pub delay: f64,
+  /// Between the output crossing the slew thresholds, divided by the slew derate
+  pub slew: f64,
+}
+
+/// The error of [`ReferenceTimeVector3DGrpup::waveform`] / [`CurrentWaveform::timing`]
+/// / [`Vector4DGrpup::waveform`]
+#[derive(Debug, Clone, Copy, thiserror::Error, PartialEq)]
+pub enum WaveformError {
+  /// There is no vector, or the vector has no / mismatched time points
+  #[error("no valid vector")]
+  Empty,
+  /// The vectors are not on a full grid of `(slew, load)`
+  #[error("no vector at index_1 {index_1}, index_2 {index_2}")]
+  MissingVector {
+    /// The input transition
+    index_1: f64,
+    /// The output load
+    index_2: f64,
+  },
+  /// The output voltage never crosses the threshold (in percentage)
+  #[error("the output never crosses {0}%")]
+  Crossing(f64),
+}
+
+/// The value at `x` by the linear interpolation of `(xs, ys)`,
+/// `None` beyond the last point
+#[expect(clippy::float_arithmetic, clippy::arithmetic_side_effects)]
+pub(super) fn interpolate(xs: &[f64], ys: &[f64], x: f64) -> Option<f64> {
+  let i = xs.partition_point(|v| *v < x);
+  if i == 0 {
+    return ys.first().copied();
+  }
+  let (x0, x1, y0, y1) = (xs.get(i - 1)?, xs.get(i)?, ys.get(i - 1)?, ys.get(i)?);
+  Some(if x1 > x0 { y0 + (y1 - y0) * (x - x0) / (x1 - x0) } else { *y1 })
+}
+
+/// The two bracketing positions of `x` in the ascending `axis`,
+/// and the weight of the upper one
+#[expect(clippy::float_arithmetic, clippy::arithmetic_side_effects)]
+fn bracket(axis: &[f64], x: f64) -> Option<(usize, usize, f64)> {
+  match axis {
+    [] => None,
+    [_] => Some((0, 0, 0.0)),
+    _ => {
+      let hi = axis.partition_point(|v| *v < x).clamp(1, axis.len() - 1);
+      let (x0, x1) = (axis.get(hi - 1)?, axis.get(hi)?);
+      Some((hi - 1, hi, (x - x0) / (x1 - x0)))
+    }
+  }
+}
+
+/// The sorted and deduplicated values
+pub(super) fn axis(values: impl Iterator<Item = f64>) -> Vec<f64> {
+  let mut axis: Vec<f64> = values.collect();
+  axis.sort_by(f64::total_cmp);
+  axis.dedup_by(|a, b| a.total_cmp(b).is_eq());
+  axis
+}
+
+/// The weighted sum of the `(weight, time, values)` waveforms, aligned by their
+/// normalized time, from the first to the last time point
+#[expect(clippy::float_arithmetic)]
+fn blend<'a>(
+  corners: impl Iterator<Item = (f64, &'a [f64], &'a [f64])>,
+) -> Result<(Vec<f64>, Vec<f64>), WaveformError> {
+  let mut aligned = Vec::new();
+  for (w, time, values) in corners {
+    let (Some(first), Some(last)) = (time.first().copied(), time.last().copied()) else {
+      return Err(WaveformError::Empty);
+    };
+    if values.len() != time.len() {
+      return Err(WaveformError::Empty);
+    }
+    let span = last - first;
+    let normalized: Vec<f64> = time
+      .iter()
+      .map(|t| if span > 0.0 { (t - first) / span } else { 0.0 })
+      .collect();
+    aligned.push((w, values, first, span, normalized));
+  }
+  let positions = axis(aligned.iter().flat_map(|(_, _, _, _, s)| s.iter().copied()));
+  let mut time = Vec::with_capacity(positions.len());
+  let mut values = Vec::with_capacity(positions.len());
+  for position in positions {
+    let (mut t, mut value) = (0.0, 0.0);
+    for (w, ys, first, span, normalized) in &aligned {
+      t += w * span.mul_add(position, *first);
+      value += w * interpolate(normalized, ys, position).unwrap_or_default();
+    }
+    time.push(t);
+    values.push(value);
+  }
+  Ok((time, values))
+}
+
+impl<C: 'static + Ctx> ReferenceTimeVector3DGrpup<C> {
+  /// The output-current waveform at the input transition `slew` and the output `load`.
+  ///
+  /// The vectors (`index_1` the slew, `index_2` the load and `index_3` the time)
+  /// shall be on a full grid of `(slew, load)`. The waveforms of the four
+  /// surrounding vectors are aligned by their normalized time, from the first
+  /// to the last time point, then the time, current and `reference_time` are
+  /// interpolated bilinearly, or extrapolated out of the grid.
+  #[inline]
+  #[expect(clippy::float_arithmetic)]
+  pub fn waveform(&self, slew: f64, load: f64) -> Result<CurrentWaveform, WaveformError> {
+    let slews = axis(self.vector.iter().map(|vector| vector.index_1));
+    let loads = axis(
+      self
+        .vector
+        .iter()
+        .filter_map(|vector| vector.index_2.first().copied()),
+    );
+    let ((s0, s1, ws), (l0, l1, wl)) = bracket(&slews, slew)
+      .zip(bracket(&loads, load))
+      .ok_or(WaveformError::Empty)?;
+    let mut corners = Vec::with_capacity(4);
+    for (s, w_s) in [(s0, 1.0 - ws), (s1, ws)] {
+      for (l, w_l) in [(l0, 1.0 - wl), (l1, wl)] {
+        let (index_1, index_2) = (
+          slews.get(s).copied().unwrap_or_default(),
+          loads.get(l).copied().unwrap_or_default(),
+        );
+        let vector = self
+          .vector
+          .iter()
+          .find(|vector| {
+            vector.index_1.total_cmp(&index_1).is_eq()
+              && vector.index_2.first().is_some_and(|v| v.total_cmp(&index_2).is_eq())
+          })
+          .ok_or(WaveformError::MissingVector { index_1, index_2 })?;
+        corners.push((w_s * w_l, vector));
+      }
+    }
+    let (time, current) = blend(
+      corners
+        .iter()
+        .map(|(w, vector)| (*w, vector.index_3.as_slice(), vector.values.as_slice())),
+    )?;
+    Ok(CurrentWaveform {
+      reference_time: corners.iter().map(|(w, vector)| w * vector.reference_time).sum(),
+      time,
+      current,
+      extrapolated: !(0.0..=1.0).contains(&ws) || !(0.0..=1.0).contains(&wl),
+    })
+  }
+  /// The delay and slew at the input transition `slew` and the output `load`,
+  /// see [`ReferenceTimeVector3DGrpup::waveform`] and [`CurrentWaveform::timing`]
+  #[inline]
+  pub fn timing(
+    &self,
+    slew: f64,
+    load: f64,
+    vdd: f64,
+    thresholds: &TimingThresholds,
+  ) -> Result<WaveformTiming, WaveformError> {
+    self.waveform(slew, load)?.timing(load, vdd, thresholds)
+  }
+}
+
+/// The `(weight, vectors)` brackets of [`nested`]
+type Brackets<'a, T> = Vec<(f64, Vec<&'a T>)>;
+
+/// The `(weight, vectors)` of the vectors bracketing `x` by `key`,
+/// and whether `x` is out of their range
+#[expect(clippy::float_arithmetic)]
+fn nested<'a, T>(
+  vectors: &[&'a T],
+  key: impl Fn(&T) -> f64,
+  x: f64,
+) -> Option<(Brackets<'a, T>, bool)> {
+  let keys = axis(vectors.iter().map(|vector| key(vector)));
+  let (i0, i1, w) = bracket(&keys, x)?;
+  let at = |i: usize| {
+    let k = keys.get(i).copied().unwrap_or_default();
+    vectors
+      .iter()
+      .copied()
+      .filter(|vector| key(vector).total_cmp(&k).is_eq())
+      .collect()
+  };
+  let brackets =
+    if i0 == i1 { vec![(1.0, at(i0))] } else { vec![(1.0 - w, at(i0)), (w, at(i1))] };
+  Some((brackets, !(0.0..=1.0).contains(&w)))
+}
+
+impl<C: 'static + Ctx> Vector4DGrpup<C> {
+  /// The output-voltage waveform at the input noise `height` and `width`,
+  /// and the output `load`, e.g., of `propagated_noise_high/low`.
+  ///
+  /// The vectors (`index_1` the height, `index_2` the width, `index_3` the load
+  /// and `index_4` the time) need not be on a full grid: the loads are
+  /// bracketed first, then the heights at each load, then the widths at each
+  /// (load, height), so that the scattered noise points along the immunity
+  /// curve are supported. The waveforms of the surrounding vectors are aligned
+  /// by their normalized time and interpolated, or extrapolated out of the range.
+  #[inline]
+  #[expect(clippy::float_arithmetic)]
+  pub fn waveform(
+    &self,
+    height: f64,
+    width: f64,
+    load: f64,
+  ) -> Result<Lookup<VoltageWaveform>, WaveformError> {
+    let vectors: Vec<&Vector4D<C>> = self.vector.iter().collect();
+    let (loads, mut extrapolated) =
+      nested(&vectors, |vector| vector.index_3, load).ok_or(WaveformError::Empty)?;
+    let mut corners = Vec::with_capacity(8);
+    for (w_load, at_load) in loads {
+      let (heights, height_out) =
+        nested(&at_load, |vector| vector.index_1, height).ok_or(WaveformError::Empty)?;
+      extrapolated |= height_out;
+      for (w_height, at_height) in heights {
+        let (widths, width_out) = nested(&at_height, |vector| vector.index_2, width)
+          .ok_or(WaveformError::Empty)?;
+        extrapolated |= width_out;
+        for (w_width, at_width) in widths {
+          if let Some(vector) = at_width.first() {
+            corners.push((w_load * w_height * w_width, *vector));
+          }
+        }
+      }
+    }
+    let (time, voltage) = blend(
+      corners
+        .iter()
+        .map(|(w, vector)| (*w, vector.index_4.as_slice(), vector.values.as_slice())),
+    )?;
+    Ok(Lookup {
+      value: VoltageWaveform { time, voltage },
+      extrapolated,
+    })
+  }
+}
+
+impl CurrentWaveform {
+  /// The charge delivered to the output from the first time point,
+  /// at each time point, by the trapezoidal integration
+  #[inline]
+  #[must_use]
+  #[expect(clippy::float_arithmetic)]
+  pub fn charge(&self) -> Vec<f64> {
+    let mut charge = 0.0;
+    core::iter::once(0.0)
+      .chain(self.time.windows(2).zip(self.current.windows(2)).map(|(t, i)| {
+        if let ([t0, t1], [i0, i1]) = (t, i) {
+          charge += (t1 - t0) * (i0 + i1) / 2.0;
+        }
+        charge
+      }))
+      .collect()
+  }
+  /// The output voltage at each time point, by charging the `load` from
+  /// the rail of `vdd` (falling output) or ground (rising output).
+  ///
+  /// The units shall be consistent, e.g., the library default `mA`, `ns`,
+  /// `pF` and `V`.
+  #[inline]
+  #[must_use]
+  #[expect(clippy::float_arithmetic)]
+  pub fn voltage(&self, load: f64, vdd: f64) -> Vec<f64> {
+    let charge = self.charge();
+    let start = if charge.last().is_some_and(|q| *q < 0.0) { vdd } else { 0.0 };
+    charge.into_iter().map(|q| start + q / load).collect()
+  }
+  /// The first time when `voltage` crosses `level`, rising or falling
+  #[expect(clippy::float_arithmetic)]
+  fn crossing(&self, voltage: &[f64], level: f64, rise: bool) -> Option<f64> {
+    let reached = |v: f64| if rise { v >= level } else { v <= level };
+    let i = voltage.iter().position(|v| reached(*v))?;
+    let t1 = *self.time.get(i)?;
+    let v1 = *voltage.get(i)?;
+    let Some(prev) = i.checked_sub(1) else {
+      return Some(t1);
+    };
+    let (t0, v0) = (*self.time.get(prev)?, *voltage.get(prev)?);
+    // `v0` has not reached the level, so that `v1 != v0`
+    Some((t1 - t0).mul_add((level - v0) / (v1 - v0), t0))
+  }
+  /// The delay and slew of the output voltage on `load` with the supply `vdd`,
+  /// measured at the `thresholds`, see [`CurrentWaveform::voltage`].
+  /// The delay is measured from `reference_time`.
+  #[inline]
+  #[expect(clippy::float_arithmetic)]
+  pub fn timing(
+    &self,
+    load: f64,
+    vdd: f64,
+    thresholds: &TimingThresholds,
+  ) -> Result<WaveformTiming, WaveformError> {
+    let voltage = self.voltage(load, vdd);
+    let rise = self.charge().last().is_none_or(|q| *q >= 0.0);
+    let cross = |pct: f64| {
+      self
+        .crossing(&voltage, vdd * pct / 100.0, rise)
+        .ok_or(WaveformError::Crossing(pct))
+    };
+    let delay = cross(thresholds.delay)? - self.reference_time;
+    let slew = (cross(thresholds.slew_upper)? - cross(thresholds.slew_lower)?).abs()
+      / thresholds.slew_derate;
+    Ok(WaveformTiming { delay, slew })
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::DefaultCtx;
+  const LIB: &str = r#"library (test) {
+    slew_lower_threshold_pct_fall : 30.0;
+    slew_upper_threshold_pct_fall : 70.0;
+    slew_derate_from_library : 0.5;
+    output_current_template (ccs_template) {
+      variable_1 : input_net_transition;
+      variable_2 : total_output_net_capacitance;
+      variable_3 : time;
+    }
+    cell (INV) {
+      pin (A) { direction : input; }
+      pin (Y) {
+        direction : output;
+        function : "!A";
+        timing () {
+          related_pin : "A";
+          cell_rise (scalar) { values ("0.55"); }
+          output_current_rise () {
+            vector (ccs_template) {
+              reference_time : 0.05;
+              index_1 ("0.1");
+              index_2 ("1.0");
+              index_3 ("0.1, 0.6, 1.1");
+              values ("1.0, 1.0, 1.0");
+            }
+            vector (ccs_template) {
+              reference_time : 0.05;
+              index_1 ("0.1");
+              index_2 ("2.0");
+              index_3 ("0.1, 2.1");
+              values ("1.0, 1.0");
+            }
+            vector (ccs_template) {
+              reference_time : 0.15;
+              index_1 ("0.3");
+              index_2 ("1.0");
+              index_3 ("0.2, 1.2");
+              values ("1.0, 1.0");
+            }
+            vector (ccs_template) {
+              reference_time : 0.15;
+              index_1 ("0.3");
+              index_2 ("2.0");
+              index_3 ("0.2, 2.2");
+              values ("1.0, 1.0");
+            }
+          }
+          output_current_fall () {
+            vector (ccs_template) {
+              reference_time : 0.05;
+              index_1 ("0.1");
+              index_2 ("1.0");
+              index_3 ("0.1, 0.6, 1.1");
+              values ("-0.5, -1.5, -1.0");
+            }
+          }
+        }
+      }
+    }
+  }"#;
+  fn assert_close(a: f64, b: f64) {
+    assert!((a - b).abs() < 1e-9, "{a} != {b}");
+  }
+  #[test]
+  fn waveform_timing() {
+    let library = Library::<DefaultCtx>::parse_lib(LIB, None).unwrap();
+    let timing = library
+      .cell
+      .get("INV")
+      .unwrap()
+      .pin
+      .get("Y")
+      .unwrap()
+      .timing
+      .iter()
+      .next()
+      .unwrap();
+    let rise = timing.output_current_rise.as_ref().unwrap();
+    let fall = timing.output_current_fall.as_ref().unwrap();
+    let thresholds = TimingThresholds::rise(&library);
+    assert_eq!(thresholds, TimingThresholds { slew_derate: 0.5, ..Default::default() });
+    // at the grid point, the constant current charges the load to vdd
+    let waveform = rise.waveform(0.1, 1.0).unwrap();
+    assert!(!waveform.extrapolated);
+    assert_eq!(waveform.voltage(1.0, 1.0), [0.0, 0.5, 1.0]);
+    let result = rise.timing(0.1, 1.0, 1.0, &thresholds).unwrap();
+    assert_close(result.delay, 0.55);
+    assert_close(result.slew, 1.2);
+    let cell_rise = timing.cell_rise.as_ref().unwrap();
+    assert_close(result.delay, cell_rise.values[0]);
+    // the load 1.5 is charged to vdd in 1.5
+    let waveform = rise.waveform(0.1, 1.5).unwrap();
+    assert_close(waveform.charge().last().copied().unwrap(), 1.5);
+    let result = waveform.timing(1.5, 1.0, &thresholds).unwrap();
+    assert_close(result.delay, 0.8);
+    assert_close(result.slew, 1.8);
+    // the reference time and start time are interpolated by slew
+    let waveform = rise.waveform(0.2, 1.0).unwrap();
+    assert_close(waveform.reference_time, 0.1);
+    assert_close(waveform.time[0], 0.15);
+    assert!(rise.waveform(0.4, 1.0).unwrap().extrapolated);
+    // the falling output with the library thresholds
+    let thresholds = TimingThresholds::fall(&library);
+    let waveform = fall.waveform(0.3, 3.0).unwrap();
+    assert_eq!(waveform.current, [-0.5, -1.5, -1.0]);
+    let voltage = waveform.voltage(1.0, 1.0);
+    assert_close(voltage[1], 0.5);
+    assert_close(voltage[2], -0.125);
+    let result = waveform.timing(1.0, 1.0, &thresholds).unwrap();
+    assert_close(result.delay, 0.55);
+    assert_eq!(
+      waveform.timing(3.0, 1.0, &thresholds),
+      Err(WaveformError::Crossing(50.0))
+    );
+    assert_close(result.slew, 0.72);
+    let mut partial = rise.clone();
+    partial.vector.pop();
+    assert_eq!(
+      partial.waveform(0.2, 1.5),
+      Err(WaveformError::MissingVector { index_1: 0.3, index_2: 2.0 })
+    );
+  }
+}
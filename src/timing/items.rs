@@ -326,49 +326,54 @@ impl LVFMoments {
   const Z: f64 = 3.0;
   const TMP1: f64 = (Self::Z * Self::Z - 1.0) / 6.0;
   const TMP2: f64 = (2.0 * Self::Z * Self::Z * Self::Z - 5.0 * Self::Z) / 36.0;
-  #[inline]
-  #[must_use]
-  #[deprecated = "TODO"]
-  /// `q+ ​​= μ + σ[ z + γ*TMP1 − γ^2*TMP2 ] = z*late_sigma - nominal`
+  /// The largest `|skewness|` that the `±3σ` quantiles keep their order,
+  /// i.e., `z - γ^2*TMP2 >= 0`
+  pub const MAX_SKEWNESS: f64 = 1.664_100_588_675_687_4;
+  /// `q+ ​​= μ + σ[ z + γ*TMP1 − γ^2*TMP2 ] = nominal + z*late_sigma`
   ///
   /// `​q− = μ + σ[−z + γ*TMP1 + γ^2*TMP2 ] = nominal - z*early_sigma`
+  ///
+  /// `None` when the moments are not finite, `std_dev` is negative,
+  /// `|skewness|` exceeds [`LVFMoments::MAX_SKEWNESS`], or the quantiles
+  /// do not enclose `nominal`, e.g., a mean shift with zero `std_dev`.
+  #[inline]
+  #[must_use]
   pub const fn to_early_late(&self, nominal: f64) -> Option<LVFEarlyLate> {
     if !(self.std_dev >= 0.0
       && nominal.is_finite()
       && self.mean.is_finite()
       && self.std_dev.is_finite()
-      && self.skewness.is_finite())
+      && self.skewness.is_finite()
+      && self.skewness.abs() <= Self::MAX_SKEWNESS)
     {
       return None;
     }
-    let quantile_plus_3sigma = self.mean
-      + self.std_dev
-        * (self.skewness * Self::TMP1 + Self::Z
-          - self.skewness * self.skewness * Self::TMP2);
-    let quantile_minus_3sigma = self.mean
-      + self.std_dev
-        * (self.skewness * Self::TMP1 - Self::Z
-          + self.skewness * self.skewness * Self::TMP2);
-    Some(LVFEarlyLate {
-      early_sigma: (nominal - quantile_minus_3sigma) / Self::Z,
-      late_sigma: (quantile_plus_3sigma - nominal) / Self::Z,
-    })
+    let symmetric = self.std_dev * (Self::Z - self.skewness * self.skewness * Self::TMP2);
+    let center = (self.std_dev * self.skewness).mul_add(Self::TMP1, self.mean);
+    let early_sigma = (nominal - (center - symmetric)) / Self::Z;
+    let late_sigma = (center + symmetric - nominal) / Self::Z;
+    if early_sigma >= 0.0 && late_sigma >= 0.0 {
+      Some(LVFEarlyLate { early_sigma, late_sigma })
+    } else {
+      None
+    }
   }
 }
 
 #[expect(clippy::float_arithmetic)]
 impl LVFEarlyLate {
-  /// `q+ ​​= μ + σ[ z + γ*TMP1 − γ^2*TMP2 ] = z*late_sigma - nominal`
+  /// The inverse of [`LVFMoments::to_early_late`] with the given `mean`,
+  /// since two sigmas are not able to decide three moments.
   ///
-  /// `​q− = μ + σ[−z + γ*TMP1 + γ^2*TMP2 ] = nominal - z*early_sigma`
+  /// `D = (q+ - q-)/2 = z*(early_sigma+late_sigma)/2 = σ(z - γ^2*TMP2)`
   ///
-  /// `Δ = q+ - q- = z*(early_sigma+late_sigma) - 2*nominal`
+  /// `s = σγ = ((q+ + q-)/2 - μ)/TMP1 = (nominal + z*(late_sigma-early_sigma)/2 - μ)/TMP1`
   ///
-  /// `a = (q+ + q-)/2 = z*(late_sigma-early_sigma)/2`
+  /// so that `z*σ^2 - D*σ - s^2*TMP2 = 0`, and `σ` is its non-negative root.
+  ///
+  /// `None` when the sigmas are negative or not finite.
   #[inline]
   #[must_use]
-  #[expect(clippy::suboptimal_flops)]
-  #[deprecated = "TODO"]
   pub fn to_moments(&self, nominal: f64, mean: f64) -> Option<LVFMoments> {
     if !(self.early_sigma >= 0.0
       && self.late_sigma >= 0.0
@@ -379,27 +384,20 @@ impl LVFEarlyLate {
     {
       return None;
     }
-
-    let delta = LVFMoments::Z * (self.early_sigma + self.late_sigma) - 2.0 * nominal;
-    let a = LVFMoments::Z * (self.late_sigma - self.early_sigma) / 2.0;
-    let a_minus_mean = a - mean;
-
-    // K = 2*TMP2*(a-mean)^2 / TMP1^2
-    let k = 2.0 * LVFMoments::TMP2 * a_minus_mean * a_minus_mean
-      / (LVFMoments::TMP1 * LVFMoments::TMP1);
-
-    // disc = delta^2 + 8*Z*k
-    let disc = delta * delta + 8.0 * LVFMoments::Z * k;
-    if !disc.is_finite() {
+    let half_span = LVFMoments::Z * (self.early_sigma + self.late_sigma) / 2.0;
+    let center =
+      (LVFMoments::Z * (self.late_sigma - self.early_sigma)).mul_add(0.5, nominal);
+    let scaled_skewness = (center - mean) / LVFMoments::TMP1;
+    let disc = half_span.mul_add(
+      half_span,
+      4.0 * LVFMoments::Z * LVFMoments::TMP2 * scaled_skewness * scaled_skewness,
+    );
+    let std_dev = (half_span + disc.sqrt()) / (2.0 * LVFMoments::Z);
+    if !std_dev.is_finite() {
       return None;
     }
-    let std_dev = (delta + disc.sqrt()) / (4.0 * LVFMoments::Z);
-    if !(std_dev.is_finite() && std_dev >= 0.0) {
-      return None;
-    } // skewness=0 when std_dev==0
-    let skewness =
-      if std_dev > 0.0 { a_minus_mean / (std_dev * LVFMoments::TMP1) } else { 0.0 };
-
+    // skewness=0 when std_dev==0
+    let skewness = if std_dev > 0.0 { scaled_skewness / std_dev } else { 0.0 };
     Some(LVFMoments { mean, std_dev, skewness })
   }
 }
@@ -454,7 +452,7 @@ impl LVFMoments {
   #[inline]
   #[must_use]
   /// self * a + b
-  pub fn mul_add(self, a: f64, b: Self) -> Self {
+  pub const fn mul_add(self, a: f64, b: Self) -> Self {
     Self {
       mean: self.mean.mul_add(a, b.mean),
       std_dev: self.std_dev.mul_add(a, b.std_dev),
@@ -470,6 +468,13 @@ impl PartialEq for LVFMoments {
       && f64_into_hash_ord_fn(&self.skewness) == f64_into_hash_ord_fn(&other.skewness)
   }
 }
+impl PartialEq for LVFEarlyLate {
+  #[inline]
+  fn eq(&self, other: &Self) -> bool {
+    f64_into_hash_ord_fn(&self.early_sigma) == f64_into_hash_ord_fn(&other.early_sigma)
+      && f64_into_hash_ord_fn(&self.late_sigma) == f64_into_hash_ord_fn(&other.late_sigma)
+  }
+}
 #[expect(clippy::float_arithmetic)]
 impl Add for LVFMoments {
   type Output = Self;
@@ -805,21 +810,67 @@ impl<C: 'static + Ctx> Group<C> for TimingTableLookUp<C> {}
 #[cfg(test)]
 mod test {
   use super::{LVFEarlyLate, LVFMoments};
+  use crate::common::f64_eq;
 
   #[test]
-  #[expect(deprecated)]
   fn lvf_convert() {
     let nominal = 0.5065;
     let mean_shift = 0.005352;
     let std_dev = 0.04952;
     let skewness = 0.03483;
-    let early_sigma = 0.0422;
-    let late_sigma = 0.0624;
     let mean = nominal + mean_shift;
     let moments = LVFMoments { mean, std_dev, skewness };
-    let early_late = LVFEarlyLate { early_sigma, late_sigma };
-    moments.to_early_late(nominal);
-    moments.to_early_late(nominal).unwrap().to_moments(nominal, mean);
-    early_late.to_moments(nominal, mean);
+    let early_late = moments.to_early_late(nominal).unwrap();
+    assert!(early_late.late_sigma > early_late.early_sigma);
+    let back = early_late.to_moments(nominal, mean).unwrap();
+    assert!(f64_eq(back.std_dev, std_dev) && f64_eq(back.skewness, skewness));
+    // the sigmas are exact quantiles
+    let early_late = LVFEarlyLate { early_sigma: 0.0422, late_sigma: 0.0624 };
+    let back = early_late
+      .to_moments(nominal, mean)
+      .unwrap()
+      .to_early_late(nominal)
+      .unwrap();
+    assert!(f64_eq(back.early_sigma, 0.0422) && f64_eq(back.late_sigma, 0.0624));
+    // symmetric without mean shift
+    let moments = LVFEarlyLate { early_sigma: 0.05, late_sigma: 0.05 }
+      .to_moments(nominal, nominal)
+      .unwrap();
+    assert!(f64_eq(moments.std_dev, 0.05) && f64_eq(moments.skewness, 0.0));
+  }
+  #[test]
+  fn lvf_convert_edge() {
+    // zero std_dev
+    let moments = LVFMoments { mean: 1.0, std_dev: 0.0, skewness: 0.5 };
+    let early_late = moments.to_early_late(1.0).unwrap();
+    assert!(f64_eq(early_late.early_sigma, 0.0) && f64_eq(early_late.late_sigma, 0.0));
+    let back = early_late.to_moments(1.0, 1.0).unwrap();
+    assert!(f64_eq(back.std_dev, 0.0) && f64_eq(back.skewness, 0.0));
+    // the mean shift is not able to represent without std_dev
+    assert!(LVFMoments { mean: 1.1, ..moments }.to_early_late(1.0).is_none());
+    // large skew, round trip until the quantiles cross
+    for skewness in [-1.6, -1.0, 1.0, 1.6] {
+      let moments = LVFMoments { mean: 1.0, std_dev: 0.1, skewness };
+      // the nominal in the middle of quantiles
+      let nominal = (0.1 * skewness).mul_add(LVFMoments::TMP1, 1.0);
+      let back = moments
+        .to_early_late(nominal)
+        .unwrap()
+        .to_moments(nominal, 1.0)
+        .unwrap();
+      assert!(
+        (back.std_dev - 0.1).abs() < 1e-9 && (back.skewness - skewness).abs() < 1e-6
+      );
+    }
+    let moments = LVFMoments { mean: 1.0, std_dev: 0.1, skewness: 1.7 };
+    assert!(moments.to_early_late(1.0).is_none());
+    // invalid inputs
+    assert!(LVFMoments { std_dev: -0.1, ..moments }.to_early_late(1.0).is_none());
+    assert!(LVFMoments { mean: f64::NAN, ..moments }.to_early_late(1.0).is_none());
+    assert!(
+      LVFEarlyLate { early_sigma: -0.1, late_sigma: 0.1 }
+        .to_moments(1.0, 1.0)
+        .is_none()
+    );
   }
 }
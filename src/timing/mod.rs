@@ -9,6 +9,7 @@ mod timing_type;
 pub use timing_type::*;
 pub mod impls;
 pub mod items;
+mod noise;
 use crate::{
   Ctx,
   ast::{Attributes, GroupComments, GroupFn, LibertySet, LibertyVec},
@@ -24,6 +25,7 @@ use crate::{
   },
 };
 pub use items::*;
+pub use noise::{PropagatedNoise, PropagatedNoiseTables};
 
 /// A `timing` group is defined in a `bundle`, a `bus`, or a `pin` group within a `cell`. The `timing`
 /// group can be used to identify the name or names of multiple `timing` arcs. A `timing` group
@@ -1008,56 +1010,56 @@ pub struct Timing<C: 'static + Ctx> {
   #[liberty(after_build = TableLookUp::use_common_template)]
   pub fall_propagation: Option<TableLookUp<C>>,
   #[liberty(group)]
-  #[liberty(after_build = TableLookUp::use_common_template)]
+  #[liberty(after_build = TableLookUp::use_noise_template)]
   pub noise_immunity_above_high: Option<TableLookUp<C>>,
   #[liberty(group)]
-  #[liberty(after_build = TableLookUp::use_common_template)]
+  #[liberty(after_build = TableLookUp::use_noise_template)]
   pub noise_immunity_below_low: Option<TableLookUp<C>>,
   #[liberty(group)]
-  #[liberty(after_build = TableLookUp::use_common_template)]
+  #[liberty(after_build = TableLookUp::use_noise_template)]
   pub noise_immunity_high: Option<TableLookUp<C>>,
   #[liberty(group)]
-  #[liberty(after_build = TableLookUp::use_common_template)]
+  #[liberty(after_build = TableLookUp::use_noise_template)]
   pub noise_immunity_low: Option<TableLookUp<C>>,
   #[liberty(group)]
   pub output_current_fall: Option<ReferenceTimeVector3DGrpup<C>>,
   #[liberty(group)]
   pub output_current_rise: Option<ReferenceTimeVector3DGrpup<C>>,
   #[liberty(group)]
-  #[liberty(after_build = TableLookUp::use_common_template)]
+  #[liberty(after_build = TableLookUp::use_propagation_template)]
   pub propogated_noise_height_above_high: Option<TableLookUp<C>>,
   #[liberty(group)]
-  #[liberty(after_build = TableLookUp::use_common_template)]
+  #[liberty(after_build = TableLookUp::use_propagation_template)]
   pub propogated_noise_height_below_low: Option<TableLookUp<C>>,
   #[liberty(group)]
-  #[liberty(after_build = TableLookUp::use_common_template)]
+  #[liberty(after_build = TableLookUp::use_propagation_template)]
   pub propogated_noise_height_high: Option<TableLookUp<C>>,
   #[liberty(group)]
-  #[liberty(after_build = TableLookUp::use_common_template)]
+  #[liberty(after_build = TableLookUp::use_propagation_template)]
   pub propogated_noise_height_low: Option<TableLookUp<C>>,
   #[liberty(group)]
-  #[liberty(after_build = TableLookUp::use_common_template)]
+  #[liberty(after_build = TableLookUp::use_propagation_template)]
   pub propogated_noise_peak_time_ratio_above_high: Option<TableLookUp<C>>,
   #[liberty(group)]
-  #[liberty(after_build = TableLookUp::use_common_template)]
+  #[liberty(after_build = TableLookUp::use_propagation_template)]
   pub propogated_noise_peak_time_ratio_below_low: Option<TableLookUp<C>>,
   #[liberty(group)]
-  #[liberty(after_build = TableLookUp::use_common_template)]
+  #[liberty(after_build = TableLookUp::use_propagation_template)]
   pub propogated_noise_peak_time_ratio_high: Option<TableLookUp<C>>,
   #[liberty(group)]
-  #[liberty(after_build = TableLookUp::use_common_template)]
+  #[liberty(after_build = TableLookUp::use_propagation_template)]
   pub propogated_noise_peak_time_ratio_low: Option<TableLookUp<C>>,
   #[liberty(group)]
-  #[liberty(after_build = TableLookUp::use_common_template)]
+  #[liberty(after_build = TableLookUp::use_propagation_template)]
   pub propogated_noise_width_above_high: Option<TableLookUp<C>>,
   #[liberty(group)]
-  #[liberty(after_build = TableLookUp::use_common_template)]
+  #[liberty(after_build = TableLookUp::use_propagation_template)]
   pub propogated_noise_width_below_low: Option<TableLookUp<C>>,
   #[liberty(group)]
-  #[liberty(after_build = TableLookUp::use_common_template)]
+  #[liberty(after_build = TableLookUp::use_propagation_template)]
   pub propogated_noise_width_high: Option<TableLookUp<C>>,
   #[liberty(group)]
-  #[liberty(after_build = TableLookUp::use_common_template)]
+  #[liberty(after_build = TableLookUp::use_propagation_template)]
   pub propogated_noise_width_low: Option<TableLookUp<C>>,
   #[liberty(group)]
   #[liberty(after_build = TableLookUpMultiSegment::use_common_template)]
@@ -1104,40 +1106,40 @@ pub struct Timing<C: 'static + Ctx> {
   #[liberty(after_build = CompactCcsTable::use_compact_template)]
   pub compact_ccs_fall: Option<CompactCcsTable<C>>,
   #[liberty(group)]
-  #[liberty(after_build = TableLookUp::use_common_template)]
+  #[liberty(after_build = TableLookUp::use_propagation_template)]
   pub propagated_noise_height_above_high: Option<TableLookUp<C>>,
   #[liberty(group)]
-  #[liberty(after_build = TableLookUp::use_common_template)]
+  #[liberty(after_build = TableLookUp::use_propagation_template)]
   pub propagated_noise_height_below_low: Option<TableLookUp<C>>,
   #[liberty(group)]
-  #[liberty(after_build = TableLookUp::use_common_template)]
+  #[liberty(after_build = TableLookUp::use_propagation_template)]
   pub propagated_noise_height_high: Option<TableLookUp<C>>,
   #[liberty(group)]
-  #[liberty(after_build = TableLookUp::use_common_template)]
+  #[liberty(after_build = TableLookUp::use_propagation_template)]
   pub propagated_noise_height_low: Option<TableLookUp<C>>,
   #[liberty(group)]
-  #[liberty(after_build = TableLookUp::use_common_template)]
+  #[liberty(after_build = TableLookUp::use_propagation_template)]
   pub propagated_noise_width_above_high: Option<TableLookUp<C>>,
   #[liberty(group)]
-  #[liberty(after_build = TableLookUp::use_common_template)]
+  #[liberty(after_build = TableLookUp::use_propagation_template)]
   pub propagated_noise_width_below_low: Option<TableLookUp<C>>,
   #[liberty(group)]
-  #[liberty(after_build = TableLookUp::use_common_template)]
+  #[liberty(after_build = TableLookUp::use_propagation_template)]
   pub propagated_noise_width_high: Option<TableLookUp<C>>,
   #[liberty(group)]
-  #[liberty(after_build = TableLookUp::use_common_template)]
+  #[liberty(after_build = TableLookUp::use_propagation_template)]
   pub propagated_noise_width_low: Option<TableLookUp<C>>,
   #[liberty(group)]
-  #[liberty(after_build = TableLookUp::use_common_template)]
+  #[liberty(after_build = TableLookUp::use_propagation_template)]
   pub propagated_noise_peak_time_ratio_above_high: Option<TableLookUp<C>>,
   #[liberty(group)]
-  #[liberty(after_build = TableLookUp::use_common_template)]
+  #[liberty(after_build = TableLookUp::use_propagation_template)]
   pub propagated_noise_peak_time_ratio_below_low: Option<TableLookUp<C>>,
   #[liberty(group)]
-  #[liberty(after_build = TableLookUp::use_common_template)]
+  #[liberty(after_build = TableLookUp::use_propagation_template)]
   pub propagated_noise_peak_time_ratio_high: Option<TableLookUp<C>>,
   #[liberty(group)]
-  #[liberty(after_build = TableLookUp::use_common_template)]
+  #[liberty(after_build = TableLookUp::use_propagation_template)]
   pub propagated_noise_peak_time_ratio_low: Option<TableLookUp<C>>,
 }
 
@@ -0,0 +1,149 @@
+//! Propagated noise through the timing arc, by the `propagated_noise_*` tables,
+//! see [`Timing::propagated_noise`].
+#![allow(clippy::multiple_inherent_impl)]
+use super::Timing;
+use crate::{
+  Ctx,
+  pin::{NoiseError, NoiseRegion},
+  table::{LookupConfig, TableLookUp, Variable},
+};
+
+/// The propagated noise tables of a region, see [`Timing::propagated_noise_tables`]
+#[derive(Debug, Clone, Copy)]
+pub struct PropagatedNoiseTables<'a, C: 'static + Ctx> {
+  /// `propagated_noise_height_*`
+  pub height: Option<&'a TableLookUp<C>>,
+  /// `propagated_noise_width_*`
+  pub width: Option<&'a TableLookUp<C>>,
+  /// `propagated_noise_peak_time_ratio_*`
+  pub peak_time_ratio: Option<&'a TableLookUp<C>>,
+}
+
+/// The propagated glitch at the output,
+/// see [`Timing::propagated_noise`] / [`CCSNStage::propagated_noise`](crate::ccsn::CCSNStage::propagated_noise)
+#[derive(Debug, Clone, Copy)]
+#[derive(PartialEq)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct PropagatedNoise {
+  /// The height of the glitch, from the steady voltage
+  pub height: f64,
+  /// The width of the glitch
+  pub width: f64,
+  /// The time from the start of the glitch to its peak, i.e.,
+  /// `peak_time_ratio * width`, `None` without the `peak_time_ratio` table
+  pub peak_time: Option<f64>,
+  /// Whether the input glitch or load is out of the range of tables / vectors
+  pub extrapolated: bool,
+}
+
+impl<C: 'static + Ctx> Timing<C> {
+  /// The propagated noise tables of `region`, the `propagated_noise_*` ones,
+  /// or the misspelled `propogated_noise_*` ones
+  #[inline]
+  #[must_use]
+  pub fn propagated_noise_tables(
+    &self,
+    region: NoiseRegion,
+  ) -> PropagatedNoiseTables<'_, C> {
+    let [height, width, peak_time_ratio] = match region {
+      NoiseRegion::High => [
+        (&self.propagated_noise_height_high, &self.propogated_noise_height_high),
+        (&self.propagated_noise_width_high, &self.propogated_noise_width_high),
+        (
+          &self.propagated_noise_peak_time_ratio_high,
+          &self.propogated_noise_peak_time_ratio_high,
+        ),
+      ],
+      NoiseRegion::Low => [
+        (&self.propagated_noise_height_low, &self.propogated_noise_height_low),
+        (&self.propagated_noise_width_low, &self.propogated_noise_width_low),
+        (
+          &self.propagated_noise_peak_time_ratio_low,
+          &self.propogated_noise_peak_time_ratio_low,
+        ),
+      ],
+      NoiseRegion::AboveHigh => [
+        (
+          &self.propagated_noise_height_above_high,
+          &self.propogated_noise_height_above_high,
+        ),
+        (
+          &self.propagated_noise_width_above_high,
+          &self.propogated_noise_width_above_high,
+        ),
+        (
+          &self.propagated_noise_peak_time_ratio_above_high,
+          &self.propogated_noise_peak_time_ratio_above_high,
+        ),
+      ],
+      NoiseRegion::BelowLow => [
+        (
+          &self.propagated_noise_height_below_low,
+          &self.propogated_noise_height_below_low,
+        ),
+        (&self.propagated_noise_width_below_low, &self.propogated_noise_width_below_low),
+        (
+          &self.propagated_noise_peak_time_ratio_below_low,
+          &self.propogated_noise_peak_time_ratio_below_low,
+        ),
+      ],
+    }
+    .map(|(table, misspelled)| table.as_ref().or(misspelled.as_ref()));
+    PropagatedNoiseTables { height, width, peak_time_ratio }
+  }
+  /// The propagated glitch at the output `load`, for the input glitch of
+  /// `height` and `width` in `region`, by the tables of
+  /// [`Timing::propagated_noise_tables`].
+  ///
+  /// The variables and the omitted indices come from the linked
+  /// `propagation_lut_template` / `lu_table_template`, see
+  /// [`TableLookUp::lookup_variables`]. Without template, the variables are
+  /// `input_noise_width`, `input_noise_height` and `total_output_net_capacitance`.
+  ///
+  /// The `propagated_noise_height_*` and `propagated_noise_width_*` tables
+  /// are required, and the tables are extrapolated out of their range.
+  #[inline]
+  #[expect(clippy::float_arithmetic)]
+  pub fn propagated_noise(
+    &self,
+    region: NoiseRegion,
+    height: f64,
+    width: f64,
+    load: f64,
+  ) -> Result<PropagatedNoise, NoiseError> {
+    let tables = self.propagated_noise_tables(region);
+    let (Some(height_table), Some(width_table)) = (tables.height, tables.width) else {
+      return Err(NoiseError::NoModel(region));
+    };
+    let lookup_at = |table: &TableLookUp<C>| {
+      table.lookup_variables(
+        &[
+          Variable::INPUT_NOISE_WIDTH,
+          Variable::INPUT_NOISE_HEIGHT,
+          Variable::TOTAL_OUTPUT_NET_CAPACITANCE,
+        ],
+        |variable| {
+          if variable == Some(Variable::INPUT_NOISE_HEIGHT) {
+            height
+          } else if variable == Some(Variable::TOTAL_OUTPUT_NET_CAPACITANCE) {
+            load
+          } else {
+            width
+          }
+        },
+        LookupConfig::default(),
+      )
+    };
+    let propagated_height = lookup_at(height_table)?;
+    let propagated_width = lookup_at(width_table)?;
+    let peak_time_ratio = tables.peak_time_ratio.map(lookup_at).transpose()?;
+    Ok(PropagatedNoise {
+      height: propagated_height.value,
+      width: propagated_width.value,
+      peak_time: peak_time_ratio.map(|ratio| ratio.value * propagated_width.value),
+      extrapolated: propagated_height.extrapolated
+        || propagated_width.extrapolated
+        || peak_time_ratio.is_some_and(|ratio| ratio.extrapolated),
+    })
+  }
+}